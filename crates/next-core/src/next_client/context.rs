@@ -8,7 +8,7 @@ use turbo_tasks_fs::{FileSystem, FileSystemPathVc};
 use turbopack::{
     module_options::{
         module_options_context::{ModuleOptionsContext, ModuleOptionsContextVc},
-        PostCssTransformOptions, WebpackLoadersOptions,
+        LessTransformOptions, PostCssTransformOptions, SassTransformOptions, WebpackLoadersOptions,
     },
     resolve_options_context::{ResolveOptionsContext, ResolveOptionsContextVc},
     transition::TransitionsByNameVc,
@@ -163,6 +163,11 @@ pub async fn get_client_module_options_context(
             postcss_package: Some(get_postcss_package_mapping(project_path)),
             ..Default::default()
         }),
+        enable_sass_transform: Some(SassTransformOptions {
+            sass_options: Some(next_config.sass_options()),
+            ..Default::default()
+        }),
+        enable_less_transform: Some(LessTransformOptions::default()),
         enable_webpack_loaders,
         enable_typescript_transform: true,
         rules: vec![(
@@ -3,7 +3,7 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use turbo_tasks::{
-    primitives::{BoolVc, StringsVc},
+    primitives::{BoolVc, JsonValueVc, StringsVc},
     trace::TraceRawVcs,
     CompletionVc, Value,
 };
@@ -48,6 +48,7 @@ pub struct NextConfig {
     pub page_extensions: Vec<String>,
     pub react_strict_mode: Option<bool>,
     pub rewrites: Rewrites,
+    pub sass_options: IndexMap<String, serde_json::Value>,
     pub transpile_packages: Option<Vec<String>>,
 
     // unsupported
@@ -79,7 +80,6 @@ pub struct NextConfig {
     production_browser_source_maps: bool,
     public_runtime_config: IndexMap<String, serde_json::Value>,
     redirects: Vec<Redirect>,
-    sass_options: IndexMap<String, serde_json::Value>,
     server_runtime_config: IndexMap<String, serde_json::Value>,
     static_page_generation_timeout: f64,
     swc_minify: bool,
@@ -495,6 +495,13 @@ impl NextConfigVc {
         Ok(self.await?.rewrites.clone().cell())
     }
 
+    #[turbo_tasks::function]
+    pub async fn sass_options(self) -> Result<JsonValueVc> {
+        Ok(JsonValueVc::cell(serde_json::to_value(
+            &self.await?.sass_options,
+        )?))
+    }
+
     #[turbo_tasks::function]
     pub async fn transpile_packages(self) -> Result<StringsVc> {
         Ok(StringsVc::cell(
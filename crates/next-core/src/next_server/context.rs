@@ -4,8 +4,8 @@ use turbo_tasks_env::ProcessEnvVc;
 use turbo_tasks_fs::FileSystemPathVc;
 use turbopack::{
     module_options::{
-        ModuleOptionsContext, ModuleOptionsContextVc, PostCssTransformOptions,
-        WebpackLoadersOptions,
+        LessTransformOptions, ModuleOptionsContext, ModuleOptionsContextVc,
+        PostCssTransformOptions, SassTransformOptions, WebpackLoadersOptions,
     },
     resolve_options_context::{ResolveOptionsContext, ResolveOptionsContextVc},
 };
@@ -211,6 +211,11 @@ pub async fn get_server_module_options_context(
         postcss_package: Some(get_postcss_package_mapping(project_path)),
         ..Default::default()
     });
+    let enable_sass_transform = Some(SassTransformOptions {
+        sass_options: Some(next_config.sass_options()),
+        ..Default::default()
+    });
+    let enable_less_transform = Some(LessTransformOptions::default());
 
     let enable_webpack_loaders = {
         let options = &*next_config.webpack_loaders_options().await?;
@@ -238,6 +243,8 @@ pub async fn get_server_module_options_context(
                 enable_jsx: true,
                 enable_styled_jsx: true,
                 enable_postcss_transform,
+                enable_sass_transform,
+                enable_less_transform,
                 enable_webpack_loaders,
                 enable_typescript_transform: true,
                 rules: vec![(
@@ -257,6 +264,8 @@ pub async fn get_server_module_options_context(
                 enable_jsx: true,
                 enable_styled_jsx: true,
                 enable_postcss_transform,
+                enable_sass_transform,
+                enable_less_transform,
                 enable_webpack_loaders,
                 enable_typescript_transform: true,
                 rules: vec![(
@@ -278,6 +287,8 @@ pub async fn get_server_module_options_context(
             ModuleOptionsContext {
                 enable_jsx: true,
                 enable_postcss_transform,
+                enable_sass_transform,
+                enable_less_transform,
                 enable_webpack_loaders,
                 enable_typescript_transform: true,
                 rules: vec![(
@@ -295,6 +306,8 @@ pub async fn get_server_module_options_context(
             };
             ModuleOptionsContext {
                 enable_postcss_transform,
+                enable_sass_transform,
+                enable_less_transform,
                 enable_webpack_loaders,
                 enable_typescript_transform: true,
                 rules: vec![(
@@ -314,6 +327,8 @@ pub async fn get_server_module_options_context(
                 enable_jsx: true,
                 enable_styled_jsx: true,
                 enable_postcss_transform,
+                enable_sass_transform,
+                enable_less_transform,
                 enable_webpack_loaders,
                 enable_typescript_transform: true,
                 rules: vec![(
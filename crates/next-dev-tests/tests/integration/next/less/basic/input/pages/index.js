@@ -0,0 +1,21 @@
+import { useEffect } from "react";
+import styles from "../styles/home.module.less";
+// Doesn't compile, the error is snapshot in `issues/`
+import "../styles/invalid.module.less";
+
+export default function Home() {
+  useEffect(() => {
+    // Only run on client
+    import("@turbo/pack-test-harness").then(runTests);
+  });
+
+  return <main className={styles.main}>Hello Less</main>;
+}
+
+function runTests() {
+  it("compiles less with @import", () => {
+    const main = document.querySelector("main");
+    expect(getComputedStyle(main).color).toBe("rgb(0, 112, 243)");
+    expect(getComputedStyle(main).paddingTop).toBe("16px");
+  });
+}
@@ -0,0 +1,21 @@
+import { useEffect } from "react";
+import styles from "../styles/home.module.scss";
+// Doesn't compile, the error is snapshot in `issues/`
+import "../styles/invalid.module.scss";
+
+export default function Home() {
+  useEffect(() => {
+    // Only run on client
+    import("@turbo/pack-test-harness").then(runTests);
+  });
+
+  return <main className={styles.main}>Hello Sass</main>;
+}
+
+function runTests() {
+  it("compiles scss with @use", () => {
+    const main = document.querySelector("main");
+    expect(getComputedStyle(main).color).toBe("rgb(0, 112, 243)");
+    expect(getComputedStyle(main).paddingTop).toBe("16px");
+  });
+}
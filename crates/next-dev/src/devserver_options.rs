@@ -78,12 +78,40 @@ pub struct DevServerOptions {
     #[cfg_attr(feature = "serializable", serde(default))]
     pub full_stats: bool,
 
+    /// Record why tasks are invalidated. The records can be inspected at
+    /// `/__turbo_tasks__/invalidations`.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub track_invalidations: bool,
+
+    /// Record task executions to this file in the Chrome Trace Event format,
+    /// which can be opened in `chrome://tracing` or Perfetto. The file is
+    /// completed when the server is stopped with Ctrl-C.
+    #[cfg_attr(feature = "cli", clap(long, value_parser))]
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub chrome_trace: Option<PathBuf>,
+
+    /// Write the task stats per function as JSON to this file when the server
+    /// is stopped with Ctrl-C. Executions and total durations are only
+    /// included with --full-stats. The same data is served at
+    /// `/__turbo_tasks__/stats.json`.
+    #[cfg_attr(feature = "cli", clap(long, value_parser))]
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub stats_json: Option<PathBuf>,
+
     /// Enable experimental garbage collection with the provided memory limit in
     /// MB.
     #[cfg_attr(feature = "cli", clap(long))]
     #[cfg_attr(feature = "serializable", serde(default))]
     pub memory_limit: Option<usize>,
 
+    /// Persist compilation results in this directory, so that a restarted
+    /// server only recomputes what depends on changed files. Requires the
+    /// `persistent_cache` feature.
+    #[cfg_attr(feature = "cli", clap(long, value_parser))]
+    #[cfg_attr(feature = "serializable", serde(default))]
+    pub cache_dir: Option<PathBuf>,
+
     // ==
     // = Inherited options from next-dev, need revisit later.
     // ==
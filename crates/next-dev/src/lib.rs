@@ -2,9 +2,12 @@
 #![feature(min_specialization)]
 
 pub mod devserver_options;
+#[cfg(feature = "persistent_cache")]
+mod persistent_cache;
 mod turbo_tasks_viz;
 
 use std::{
+    any::Any,
     collections::HashSet,
     env::current_dir,
     future::{join, Future},
@@ -28,11 +31,14 @@ use next_core::{
 use owo_colors::OwoColorize;
 use turbo_malloc::TurboMalloc;
 use turbo_tasks::{
+    backend::Backend,
     util::{FormatBytes, FormatDuration},
     StatsType, TransientInstance, TurboTasks, TurboTasksBackendApi, Value,
 };
 use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemVc};
 use turbo_tasks_memory::MemoryBackend;
+#[cfg(feature = "persistent_cache")]
+use turbo_tasks_memory::MemoryBackendWithPersistedGraph;
 use turbopack_cli_utils::issue::{ConsoleUiVc, LogOptions};
 use turbopack_core::{
     environment::ServerAddr,
@@ -57,8 +63,8 @@ pub enum EntryRequest {
     Module(String, String),
 }
 
-pub struct NextDevServerBuilder {
-    turbo_tasks: Arc<TurboTasks<MemoryBackend>>,
+pub struct NextDevServerBuilder<B: Backend + 'static = MemoryBackend> {
+    turbo_tasks: Arc<TurboTasks<B>>,
     project_dir: String,
     root_dir: String,
     entry_requests: Vec<EntryRequest>,
@@ -73,12 +79,12 @@ pub struct NextDevServerBuilder {
     allow_retry: bool,
 }
 
-impl NextDevServerBuilder {
+impl<B: Backend + 'static> NextDevServerBuilder<B> {
     pub fn new(
-        turbo_tasks: Arc<TurboTasks<MemoryBackend>>,
+        turbo_tasks: Arc<TurboTasks<B>>,
         project_dir: String,
         root_dir: String,
-    ) -> NextDevServerBuilder {
+    ) -> NextDevServerBuilder<B> {
         NextDevServerBuilder {
             turbo_tasks,
             project_dir,
@@ -98,47 +104,47 @@ impl NextDevServerBuilder {
         }
     }
 
-    pub fn entry_request(mut self, entry_asset_path: EntryRequest) -> NextDevServerBuilder {
+    pub fn entry_request(mut self, entry_asset_path: EntryRequest) -> NextDevServerBuilder<B> {
         self.entry_requests.push(entry_asset_path);
         self
     }
 
-    pub fn eager_compile(mut self, eager_compile: bool) -> NextDevServerBuilder {
+    pub fn eager_compile(mut self, eager_compile: bool) -> NextDevServerBuilder<B> {
         self.eager_compile = eager_compile;
         self
     }
 
-    pub fn hostname(mut self, hostname: IpAddr) -> NextDevServerBuilder {
+    pub fn hostname(mut self, hostname: IpAddr) -> NextDevServerBuilder<B> {
         self.hostname = Some(hostname);
         self
     }
 
-    pub fn port(mut self, port: u16) -> NextDevServerBuilder {
+    pub fn port(mut self, port: u16) -> NextDevServerBuilder<B> {
         self.port = Some(port);
         self
     }
 
-    pub fn browserslist_query(mut self, browserslist_query: String) -> NextDevServerBuilder {
+    pub fn browserslist_query(mut self, browserslist_query: String) -> NextDevServerBuilder<B> {
         self.browserslist_query = browserslist_query;
         self
     }
 
-    pub fn log_level(mut self, log_level: IssueSeverity) -> NextDevServerBuilder {
+    pub fn log_level(mut self, log_level: IssueSeverity) -> NextDevServerBuilder<B> {
         self.log_level = log_level;
         self
     }
 
-    pub fn show_all(mut self, show_all: bool) -> NextDevServerBuilder {
+    pub fn show_all(mut self, show_all: bool) -> NextDevServerBuilder<B> {
         self.show_all = show_all;
         self
     }
 
-    pub fn allow_retry(mut self, allow_retry: bool) -> NextDevServerBuilder {
+    pub fn allow_retry(mut self, allow_retry: bool) -> NextDevServerBuilder<B> {
         self.allow_retry = allow_retry;
         self
     }
 
-    pub fn log_detail(mut self, log_detail: bool) -> NextDevServerBuilder {
+    pub fn log_detail(mut self, log_detail: bool) -> NextDevServerBuilder<B> {
         self.log_detail = log_detail;
         self
     }
@@ -146,7 +152,7 @@ impl NextDevServerBuilder {
     pub fn issue_reporter(
         mut self,
         issue_reporter: Box<dyn IssueReporterProvider>,
-    ) -> NextDevServerBuilder {
+    ) -> NextDevServerBuilder<B> {
         self.issue_reporter = Some(issue_reporter);
         self
     }
@@ -213,7 +219,11 @@ impl NextDevServerBuilder {
         });
         let entry_requests = Arc::new(self.entry_requests);
         let server_addr = Arc::new(server.addr);
-        let tasks = turbo_tasks.clone();
+        // The introspection of the task graph is only available for the
+        // in-memory backend.
+        let viz_turbo_tasks = (turbo_tasks.clone() as Arc<dyn Any + Send + Sync>)
+            .downcast::<TurboTasks<MemoryBackend>>()
+            .ok();
         let issue_provider = self.issue_reporter.unwrap_or_else(|| {
             // Initialize a ConsoleUi reporter if no custom reporter was provided
             Box::new(move || ConsoleUiVc::new(log_options.clone().into()).into())
@@ -225,14 +235,14 @@ impl NextDevServerBuilder {
                 project_dir.clone(),
                 entry_requests.clone().into(),
                 eager_compile,
-                turbo_tasks.clone().into(),
+                viz_turbo_tasks.clone().map(Into::into),
                 browserslist_query.clone(),
                 server_addr.clone().into(),
             )
         };
 
         let issue_reporter_arc = Arc::new(move || issue_provider.get_issue_reporter());
-        Ok(server.serve(tasks, source, issue_reporter_arc))
+        Ok(server.serve(turbo_tasks, source, issue_reporter_arc))
     }
 }
 
@@ -257,7 +267,7 @@ async fn source(
     project_dir: String,
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     eager_compile: bool,
-    turbo_tasks: TransientInstance<TurboTasks<MemoryBackend>>,
+    turbo_tasks: Option<TransientInstance<TurboTasks<MemoryBackend>>>,
     browserslist_query: String,
     server_addr: TransientInstance<SocketAddr>,
 ) -> Result<ContentSourceVc> {
@@ -326,11 +336,13 @@ async fn source(
         next_config,
         server_addr,
     );
-    let viz = turbo_tasks_viz::TurboTasksSource {
-        turbo_tasks: turbo_tasks.into(),
-    }
-    .cell()
-    .into();
+    let viz: Option<ContentSourceVc> = turbo_tasks.map(|turbo_tasks| {
+        turbo_tasks_viz::TurboTasksSource {
+            turbo_tasks: turbo_tasks.into(),
+        }
+        .cell()
+        .into()
+    });
     let static_source =
         StaticAssetsContentSourceVc::new(String::new(), project_path.join("public")).into();
     let manifest_source = DevManifestContentSource {
@@ -367,18 +379,21 @@ async fn source(
         pages_structure,
     )
     .into();
+    let mut routes = vec![
+        ("__turbopack__/".to_string(), introspect),
+        (
+            "__nextjs_original-stack-frame".to_string(),
+            source_map_trace,
+        ),
+        // TODO: Load path from next.config.js
+        ("_next/image".to_string(), img_source),
+        ("__turbopack_sourcemap__/".to_string(), source_maps),
+    ];
+    if let Some(viz) = viz {
+        routes.push(("__turbo_tasks__/".to_string(), viz));
+    }
     let source = RouterContentSource {
-        routes: vec![
-            ("__turbopack__/".to_string(), introspect),
-            ("__turbo_tasks__/".to_string(), viz),
-            (
-                "__nextjs_original-stack-frame".to_string(),
-                source_map_trace,
-            ),
-            // TODO: Load path from next.config.js
-            ("_next/image".to_string(), img_source),
-            ("__turbopack_sourcemap__/".to_string(), source_maps),
-        ],
+        routes,
         fallback: router_source,
     }
     .cell()
@@ -420,20 +435,75 @@ pub async fn start_server(options: &DevServerOptions) -> Result<()> {
         dir.clone()
     };
 
+    #[cfg(feature = "persistent_cache")]
+    {
+        if let Some(cache_dir) = options.cache_dir.as_ref() {
+            let cache = persistent_cache::open_persisted_graph(cache_dir, &dir, &root_dir)?;
+            let tt = TurboTasks::new(MemoryBackendWithPersistedGraph::new(cache.graph));
+            invalidate_changed_files(&tt, root_dir.clone(), cache.changed_files).await?;
+            return run_server(options, start, tt, dir, root_dir).await;
+        }
+    }
+    #[cfg(not(feature = "persistent_cache"))]
+    {
+        if options.cache_dir.is_some() {
+            anyhow::bail!(
+                "--cache-dir requires next-dev to be built with the `persistent_cache` feature"
+            );
+        }
+    }
+
     let tt = TurboTasks::new(MemoryBackend::new(
         options.memory_limit.map_or(usize::MAX, |l| l * 1024 * 1024),
     ));
+    run_server(options, start, tt, dir, root_dir).await
+}
+
+/// Re-reads the files which changed since the persisted graph was last
+/// opened, so the restored tasks depending on them are recomputed.
+#[cfg(feature = "persistent_cache")]
+async fn invalidate_changed_files<B: Backend + 'static>(
+    tt: &TurboTasks<B>,
+    root_dir: String,
+    changed_files: Vec<String>,
+) -> Result<()> {
+    if changed_files.is_empty() {
+        return Ok(());
+    }
+    tt.run_once(async move {
+        let root = project_fs(&root_dir).root();
+        for path in changed_files {
+            let path = root.join(&path);
+            path.read().await?;
+            // Added and removed files change the content of their directory
+            path.parent().read_dir().await?;
+        }
+        Ok(())
+    })
+    .await
+}
 
+async fn run_server<B: Backend + 'static>(
+    options: &DevServerOptions,
+    start: Instant,
+    tt: Arc<TurboTasks<B>>,
+    dir: String,
+    root_dir: String,
+) -> Result<()> {
     let stats_type = match options.full_stats {
         true => StatsType::Full,
         false => StatsType::Essential,
     };
     tt.set_stats_type(stats_type);
+    tt.set_invalidation_tracking(options.track_invalidations);
+    if let Some(path) = &options.chrome_trace {
+        tt.start_chrome_trace(path)?;
+    }
 
     let tt_clone = tt.clone();
 
     #[allow(unused_mut)]
-    let mut server = NextDevServerBuilder::new(tt, dir, root_dir)
+    let mut server = NextDevServerBuilder::new(tt.clone(), dir, root_dir)
         .entry_request(EntryRequest::Relative("src/index".into()))
         .eager_compile(options.eager_compile)
         .hostname(options.hostname)
@@ -492,6 +562,9 @@ pub async fn start_server(options: &DevServerOptions) -> Result<()> {
 
             if let Some((elapsed, count)) = update_future.await {
                 progress_counter = 0;
+                if let Err(err) = tt_clone.flush_chrome_trace() {
+                    println!("{} - unable to write trace: {err:?}", "error".red());
+                }
                 if options.log_detail {
                     println!(
                         "\x1b[2K{event_type} - updated in {elapsed} ({tasks} tasks, {memory})",
@@ -526,7 +599,44 @@ pub async fn start_server(options: &DevServerOptions) -> Result<()> {
         }
     };
 
-    join!(stats_future, async { server.future.await.unwrap() }).await;
+    let server_future = async {
+        join!(stats_future, async { server.future.await.unwrap() }).await;
+    };
+    if options.cache_dir.is_none() && options.chrome_trace.is_none() && options.stats_json.is_none()
+    {
+        server_future.await;
+        return Ok(());
+    }
+
+    // Stop gracefully on Ctrl-C, so the persisted graph is complete when the
+    // next server starts from it and the trace and stats files are written.
+    tokio::select! {
+        _ = server_future => {}
+        result = tokio::signal::ctrl_c() => {
+            result.context("unable to listen for Ctrl-C")?;
+        }
+    }
+    if let Some(path) = &options.stats_json {
+        // Stats are only collected by the in-memory backend.
+        match (tt.clone() as Arc<dyn Any + Send + Sync>).downcast::<TurboTasks<MemoryBackend>>() {
+            Ok(tt) => {
+                turbo_tasks_viz::write_stats_json(&tt, path)?;
+                println!("{} - stats written to {}", "event".purple(), path.display());
+            }
+            Err(_) => println!(
+                "{} - stats are not available with --cache-dir",
+                "warning".yellow()
+            ),
+        }
+    }
+    if let Some(path) = &options.chrome_trace {
+        tt.stop_chrome_trace()?;
+        println!("{} - trace written to {}", "event".purple(), path.display());
+    }
+    if options.cache_dir.is_some() {
+        println!("{} - writing cache", "event".purple());
+        tt.stop_and_wait().await;
+    }
 
     Ok(())
 }
@@ -534,7 +644,10 @@ pub async fn start_server(options: &DevServerOptions) -> Result<()> {
 #[cfg(feature = "profile")]
 // When profiling, exits the process when no new updates have been received for
 // a given timeout and there are no more tasks in progress.
-async fn profile_timeout<T>(tt: &TurboTasks<MemoryBackend>, future: impl Future<Output = T>) -> T {
+async fn profile_timeout<B: Backend + 'static, T>(
+    tt: &TurboTasks<B>,
+    future: impl Future<Output = T>,
+) -> T {
     /// How long to wait in between updates before force-exiting the process
     /// during profiling.
     const PROFILE_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -553,8 +666,8 @@ async fn profile_timeout<T>(tt: &TurboTasks<MemoryBackend>, future: impl Future<
 }
 
 #[cfg(not(feature = "profile"))]
-fn profile_timeout<T>(
-    _tt: &TurboTasks<MemoryBackend>,
+fn profile_timeout<B: Backend + 'static, T>(
+    _tt: &TurboTasks<B>,
     future: impl Future<Output = T>,
 ) -> impl Future<Output = T> {
     future
@@ -0,0 +1,373 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path, time::UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use turbo_tasks_rocksdb::RocksDbPersistedGraph;
+
+/// Name of the file in the cache directory which identifies the build and
+/// project the cached graph was created for.
+const FINGERPRINT_FILE: &str = "fingerprint";
+
+/// Name of the file in the cache directory which lists the size and
+/// modification time of the files in the root directory when the graph was
+/// last opened.
+const FILES_FILE: &str = "files";
+
+/// Name of the directory in the cache directory which holds the database.
+const GRAPH_DIR: &str = "graph";
+
+/// Directories which are not fingerprinted. Changes to installed packages are
+/// covered by the lockfiles and package.json files.
+const IGNORED_DIRS: &[&str] = &[".git", ".next", "node_modules"];
+
+/// A persisted task graph and the files which changed since it was last
+/// opened.
+pub struct PersistentCache {
+    pub graph: RocksDbPersistedGraph,
+    /// Paths relative to the root directory, separated by `/`, of the files
+    /// which were changed, added or removed since the graph was last opened.
+    /// The tasks reading them have to be re-executed before the graph is
+    /// used, as restored tasks don't read from the filesystem on their own.
+    pub changed_files: Vec<String>,
+}
+
+/// Opens the persisted task graph in `cache_dir`.
+///
+/// A graph written by a different build of next-dev or for a different
+/// project is discarded, as task ids and serialized values are not compatible
+/// between them. A compatible graph is validated by comparing the size and
+/// modification time of the files in `root_dir` with the ones recorded when
+/// it was last opened.
+pub fn open_persisted_graph(
+    cache_dir: &Path,
+    project_dir: &str,
+    root_dir: &str,
+) -> Result<PersistentCache> {
+    open_graph(
+        cache_dir,
+        option_env!("VERGEN_BUILD_TIMESTAMP"),
+        project_dir,
+        root_dir,
+    )
+}
+
+/// Opens the persisted task graph for the build identified by `build_id`.
+///
+/// Without a build id, graphs of different builds can't be told apart, so
+/// the graph is never reused and no fingerprint is written.
+fn open_graph(
+    cache_dir: &Path,
+    build_id: Option<&str>,
+    project_dir: &str,
+    root_dir: &str,
+) -> Result<PersistentCache> {
+    let fingerprint = build_id.map(|build_id| {
+        format!(
+            "{}\n{}\n{}\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            build_id,
+            root_dir,
+            project_dir
+        )
+    });
+    let fingerprint_path = cache_dir.join(FINGERPRINT_FILE);
+    let files_path = cache_dir.join(FILES_FILE);
+    let graph_path = cache_dir.join(GRAPH_DIR);
+
+    let existing = read_to_string_if_exists(&fingerprint_path)?;
+    let reused = fingerprint.is_some() && existing == fingerprint;
+    if !reused {
+        remove_dir_if_exists(&graph_path)?;
+        fs::create_dir_all(cache_dir)
+            .with_context(|| format!("unable to create cache directory {}", cache_dir.display()))?;
+        let result = match &fingerprint {
+            Some(fingerprint) => fs::write(&fingerprint_path, fingerprint),
+            // The graph written by this build must not be reused by any build
+            None => remove_file_if_exists(&fingerprint_path),
+        };
+        result.with_context(|| {
+            format!(
+                "unable to write cache fingerprint {}",
+                fingerprint_path.display()
+            )
+        })?;
+    }
+
+    let mut changed_files = Vec::new();
+    if fingerprint.is_some() {
+        let cache_dir = dunce::canonicalize(cache_dir).with_context(|| {
+            format!(
+                "unable to canonicalize cache directory {}",
+                cache_dir.display()
+            )
+        })?;
+        let mut files = BTreeMap::new();
+        collect_file_fingerprints(Path::new(root_dir), "", &cache_dir, &mut files)?;
+        // A discarded graph is recomputed completely, so there is nothing to
+        // invalidate
+        if reused {
+            let previous = read_to_string_if_exists(&files_path)?.unwrap_or_default();
+            let previous = parse_file_fingerprints(&previous);
+            changed_files = diff_file_fingerprints(&previous, &files);
+        }
+        fs::write(&files_path, format_file_fingerprints(&files)).with_context(|| {
+            format!(
+                "unable to write cache file fingerprints {}",
+                files_path.display()
+            )
+        })?;
+    } else {
+        remove_file_if_exists(&files_path).with_context(|| {
+            format!(
+                "unable to remove cache file fingerprints {}",
+                files_path.display()
+            )
+        })?;
+    }
+
+    let graph = RocksDbPersistedGraph::new(&graph_path)
+        .with_context(|| format!("unable to open cache in {}", graph_path.display()))?;
+    Ok(PersistentCache {
+        graph,
+        changed_files,
+    })
+}
+
+/// The size and modification time in nanoseconds of a file.
+type FileFingerprint = (u64, u128);
+
+/// Collects the fingerprints of all files in `dir`, keyed by their path
+/// relative to the root directory, which is `prefix`.
+fn collect_file_fingerprints(
+    dir: &Path,
+    prefix: &str,
+    cache_dir: &Path,
+    files: &mut BTreeMap<String, FileFingerprint>,
+) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("unable to read directory {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("unable to read directory {}", dir.display()))?;
+        let path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(|name| name.to_string()) else {
+            // Paths which are not valid unicode can't be read by the project
+            // filesystem either
+            continue;
+        };
+        let metadata = fs::symlink_metadata(&path)
+            .with_context(|| format!("unable to read metadata of {}", path.display()))?;
+        let relative_path = format!("{prefix}{name}");
+        if metadata.is_dir() {
+            if IGNORED_DIRS.contains(&name.as_str()) || path == cache_dir {
+                continue;
+            }
+            collect_file_fingerprints(&path, &format!("{relative_path}/"), cache_dir, files)?;
+        } else {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |modified| modified.as_nanos());
+            files.insert(relative_path, (metadata.len(), modified));
+        }
+    }
+    Ok(())
+}
+
+fn format_file_fingerprints(files: &BTreeMap<String, FileFingerprint>) -> String {
+    files
+        .iter()
+        .map(|(path, (size, modified))| format!("{size} {modified} {path}\n"))
+        .collect()
+}
+
+/// Parses the fingerprints written by [format_file_fingerprints]. Malformed
+/// lines are skipped, which reports the file as added.
+fn parse_file_fingerprints(content: &str) -> BTreeMap<String, FileFingerprint> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let size = parts.next()?.parse().ok()?;
+            let modified = parts.next()?.parse().ok()?;
+            let path = parts.next()?;
+            Some((path.to_string(), (size, modified)))
+        })
+        .collect()
+}
+
+/// Returns the paths of the files which were changed, added or removed.
+fn diff_file_fingerprints(
+    previous: &BTreeMap<String, FileFingerprint>,
+    current: &BTreeMap<String, FileFingerprint>,
+) -> Vec<String> {
+    let changed = current
+        .iter()
+        .filter(|(path, fingerprint)| previous.get(*path) != Some(fingerprint))
+        .map(|(path, _)| path.clone());
+    let removed = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned();
+    changed.chain(removed).collect()
+}
+
+fn read_to_string_if_exists(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("unable to read {}", path.display())),
+    }
+}
+
+fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn remove_dir_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_dir_all(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(err).with_context(|| format!("unable to remove stale cache {}", path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{open_graph, FINGERPRINT_FILE, GRAPH_DIR};
+
+    /// Opens the graph and marks it with a file, which only survives while
+    /// the graph is reused.
+    fn open_and_mark(
+        cache_dir: &Path,
+        build_id: Option<&str>,
+        project_dir: &str,
+        root_dir: &Path,
+    ) -> bool {
+        let cache =
+            open_graph(cache_dir, build_id, project_dir, root_dir.to_str().unwrap()).unwrap();
+        drop(cache);
+        let marker = cache_dir.join(GRAPH_DIR).join("marker");
+        let reused = marker.exists();
+        fs::write(marker, "").unwrap();
+        reused
+    }
+
+    #[test]
+    fn reuses_graph_of_same_build_and_project() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let root_dir = tempfile::tempdir().unwrap();
+        let root = root_dir.path();
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("1"),
+            "/root/app",
+            root
+        ));
+        assert!(open_and_mark(
+            cache_dir.path(),
+            Some("1"),
+            "/root/app",
+            root
+        ));
+    }
+
+    #[test]
+    fn discards_graph_of_other_build_or_project() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let root_dir = tempfile::tempdir().unwrap();
+        let root = root_dir.path();
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("1"),
+            "/root/app",
+            root
+        ));
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("2"),
+            "/root/app",
+            root
+        ));
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("2"),
+            "/root/other",
+            root
+        ));
+        assert!(open_and_mark(
+            cache_dir.path(),
+            Some("2"),
+            "/root/other",
+            root
+        ));
+    }
+
+    #[test]
+    fn never_reuses_graph_of_unknown_build() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let root_dir = tempfile::tempdir().unwrap();
+        let root = root_dir.path();
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("1"),
+            "/root/app",
+            root
+        ));
+        assert!(!open_and_mark(cache_dir.path(), None, "/root/app", root));
+        assert!(!cache_dir.path().join(FINGERPRINT_FILE).exists());
+        assert!(!open_and_mark(cache_dir.path(), None, "/root/app", root));
+        // The graph written by the unknown build isn't picked up by a known one
+        assert!(!open_and_mark(
+            cache_dir.path(),
+            Some("1"),
+            "/root/app",
+            root
+        ));
+    }
+
+    #[test]
+    fn reports_files_changed_between_runs() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let root = &dunce::canonicalize(root_dir.path()).unwrap();
+        let cache_dir = root.join(".turbo");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(root.join("src/index.js"), "console.log(1)").unwrap();
+        fs::write(root.join("src/util.js"), "export {}").unwrap();
+        let changed_files = || {
+            open_graph(
+                &cache_dir,
+                Some("1"),
+                root.to_str().unwrap(),
+                root.to_str().unwrap(),
+            )
+            .unwrap()
+            .changed_files
+        };
+
+        // A new graph is computed from scratch
+        assert!(changed_files().is_empty());
+        assert!(changed_files().is_empty());
+
+        fs::write(root.join("src/index.js"), "console.log(2);").unwrap();
+        fs::write(root.join("src/added.js"), "").unwrap();
+        fs::remove_file(root.join("src/util.js")).unwrap();
+        // Neither installed packages nor the cache itself are fingerprinted
+        fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+        assert_eq!(
+            changed_files(),
+            ["src/added.js", "src/index.js", "src/util.js"]
+        );
+
+        // The changes are only reported once
+        assert!(changed_files().is_empty());
+    }
+}
@@ -1,17 +1,20 @@
-use std::{sync::Arc, time::Duration};
+use std::{fs, path::Path, sync::Arc, time::Duration};
 
-use anyhow::Result;
-use mime::TEXT_HTML_UTF_8;
-use turbo_tasks::{get_invalidator, TurboTasks, TurboTasksBackendApi, Value};
+use anyhow::{Context, Result};
+use mime::{APPLICATION_JSON, TEXT_HTML_UTF_8};
+use turbo_tasks::{
+    get_invalidator, InvalidationReason, TaskId, TurboTasks, TurboTasksBackendApi, Value,
+};
 use turbo_tasks_fs::File;
 use turbo_tasks_memory::{
-    stats::{ReferenceType, Stats},
+    stats::{GroupTree, ReferenceType, Stats},
     viz, MemoryBackend,
 };
 use turbopack_core::asset::AssetContentVc;
 use turbopack_dev_server::source::{
-    ContentSource, ContentSourceContentVc, ContentSourceData, ContentSourceDataFilter,
-    ContentSourceDataVary, ContentSourceResultVc, ContentSourceVc, NeededData,
+    query::QueryValue, ContentSource, ContentSourceContentVc, ContentSourceData,
+    ContentSourceDataFilter, ContentSourceDataVary, ContentSourceResultVc, ContentSourceVc,
+    NeededData,
 };
 
 #[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new", into = "new")]
@@ -28,6 +31,36 @@ impl TurboTasksSourceVc {
 
 const INVALIDATION_INTERVAL: Duration = Duration::from_secs(3);
 
+/// Collects the stats of all cached tasks, grouped by the dependencies between
+/// them.
+fn collect_stats(
+    tt: &TurboTasks<MemoryBackend>,
+    active_only: bool,
+    include_unloaded: bool,
+) -> GroupTree {
+    let mut stats = Stats::new();
+    let b = tt.backend();
+    b.with_all_cached_tasks(|task| {
+        stats.add_id_conditional(b, task, |_, info| {
+            (include_unloaded || !info.unloaded) && (!active_only || info.active)
+        });
+    });
+    stats.treeify(ReferenceType::Dependency)
+}
+
+/// Writes the stats of all loaded tasks as JSON to `path`, in the same format
+/// as served at `/__turbo_tasks__/stats.json`.
+pub fn write_stats_json(tt: &TurboTasks<MemoryBackend>, path: &Path) -> Result<()> {
+    let tree = collect_stats(tt, false, false);
+    let json = viz::json::create_json(&tree, tt.stats_type());
+    fs::write(path, serde_json::to_string_pretty(&json)?)
+        .with_context(|| format!("unable to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Number of tasks listed on the invalidations page.
+const INVALIDATIONS_LIMIT: usize = 1000;
+
 #[turbo_tasks::value_impl]
 impl ContentSource for TurboTasksSource {
     #[turbo_tasks::function]
@@ -42,7 +75,9 @@ impl ContentSource for TurboTasksSource {
         tokio::spawn({
             async move {
                 tokio::time::sleep(INVALIDATION_INTERVAL).await;
-                invalidator.invalidate();
+                invalidator.invalidate_with_reason(InvalidationReason::Custom(
+                    "turbo_tasks_viz refresh".into(),
+                ));
             }
         });
         let html = match path {
@@ -71,18 +106,26 @@ impl ContentSource for TurboTasksSource {
                     viz::graph::visualize_stats_tree(tree, ReferenceType::Child, tt.stats_type());
                 viz::graph::wrap_html(&graph)
             }
-            "table" => {
+            "table" | "stats.json" => {
                 if let Some(query) = &data.query {
-                    let mut stats = Stats::new();
-                    let b = tt.backend();
-                    let active_only = query.contains_key("active");
-                    let include_unloaded = query.contains_key("unloaded");
-                    b.with_all_cached_tasks(|task| {
-                        stats.add_id_conditional(b, task, |_, info| {
-                            (include_unloaded || !info.unloaded) && (!active_only || info.active)
-                        });
-                    });
-                    let tree = stats.treeify(ReferenceType::Dependency);
+                    let tree = collect_stats(
+                        tt,
+                        query.contains_key("active"),
+                        query.contains_key("unloaded"),
+                    );
+                    if path == "stats.json" {
+                        let json = viz::json::create_json(&tree, tt.stats_type());
+                        return Ok(ContentSourceResultVc::exact(
+                            ContentSourceContentVc::static_content(
+                                AssetContentVc::from(
+                                    File::from(serde_json::to_string_pretty(&json)?)
+                                        .with_content_type(APPLICATION_JSON),
+                                )
+                                .into(),
+                            )
+                            .into(),
+                        ));
+                    }
                     let table = viz::table::create_table(tree, tt.stats_type());
                     viz::table::wrap_html(&table)
                 } else {
@@ -96,6 +139,33 @@ impl ContentSource for TurboTasksSource {
                     })));
                 }
             }
+            "invalidations" => {
+                if let Some(query) = &data.query {
+                    match query.get("task") {
+                        Some(QueryValue::String(task)) => {
+                            let task = TaskId::from(task.parse::<usize>()?);
+                            viz::invalidations::wrap_html(&viz::invalidations::create_chain(
+                                tt, task,
+                            ))
+                        }
+                        _ => viz::invalidations::wrap_html(&viz::invalidations::create_list(
+                            tt,
+                            INVALIDATIONS_LIMIT,
+                        )),
+                    }
+                } else {
+                    return Ok(ContentSourceResultVc::need_data(Value::new(NeededData {
+                        source: self_vc.into(),
+                        path: path.to_string(),
+                        vary: ContentSourceDataVary {
+                            query: Some(ContentSourceDataFilter::Subset(
+                                ["task".to_string()].into(),
+                            )),
+                            ..Default::default()
+                        },
+                    })));
+                }
+            }
             "reset" => {
                 let b = tt.backend();
                 b.with_all_cached_tasks(|task| {
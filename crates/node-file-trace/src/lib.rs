@@ -59,12 +59,17 @@ use crate::nft_json::NftJsonAssetVc;
     derive(Serialize, Deserialize),
     serde(rename_all = "camelCase")
 )]
-#[derive(Debug, Clone)]
-struct CacheArgs {
-    #[clap(long)]
+#[derive(Debug, Clone, Default)]
+pub struct CacheArgs {
+    /// The directory of the persistent cache.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "node-api", serde(default))]
     cache: Option<String>,
 
-    #[clap(long)]
+    /// Wait until the whole task graph has been written to the cache before
+    /// exiting.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "node-api", serde(default))]
     cache_fully: bool,
 }
 
@@ -108,6 +113,12 @@ pub struct CommonArgs {
     #[cfg_attr(feature = "node-api", serde(default))]
     watch: bool,
 
+    /// Record task executions to this file in the Chrome Trace Event format,
+    /// which can be opened in `chrome://tracing` or Perfetto.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "node-api", serde(default))]
+    chrome_trace: Option<String>,
+
     #[cfg_attr(feature = "cli", clap(short, long))]
     #[cfg_attr(feature = "node-api", serde(default))]
     /// Filter by issue severity.
@@ -329,8 +340,8 @@ pub async fn start(
         use turbo_tasks_memory::MemoryBackendWithPersistedGraph;
         use turbo_tasks_rocksdb::RocksDbPersistedGraph;
 
-        run(
-            &args,
+        return run(
+            args.clone(),
             || {
                 let start = Instant::now();
                 let backend = MemoryBackendWithPersistedGraph::new(
@@ -367,9 +378,10 @@ pub async fn start(
                 let elapsed = start.elapsed();
                 println!("writing cache {}", FormatDuration(elapsed));
             },
+            module_options,
+            resolve_options,
         )
         .await;
-        return;
     }
 
     run(
@@ -433,11 +445,13 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
             loop {
                 let (elapsed, count) = tt.get_or_wait_update_info(Duration::from_millis(100)).await;
                 println!("updated {} tasks in {}", count, FormatDuration(elapsed));
+                tt.flush_chrome_trace()?;
             }
         } else {
             let result = tt.wait_task_completion(root_task, true).await;
             let dur = start.elapsed();
             let (elapsed, count) = tt.get_or_wait_update_info(Duration::from_millis(100)).await;
+            tt.stop_chrome_trace()?;
             final_finish(tt, root_task, dur).await;
             let dur2 = start.elapsed();
             println!(
@@ -455,6 +469,9 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
     let (sender, mut receiver) = channel(1);
     let dir = current_dir().unwrap();
     let tt = create_tt();
+    if let Some(path) = &args.common().chrome_trace {
+        tt.start_chrome_trace(path)?;
+    }
     let module_options = TransientInstance::new(module_options.unwrap_or_default());
     let resolve_options = TransientInstance::new(resolve_options.unwrap_or_default());
     let log_options = TransientInstance::new(LogOptions {
@@ -0,0 +1,18 @@
+use std::env;
+
+use turbo_tasks_file_graph::FilePersistedGraph;
+
+fn main() {
+    let mut args = env::args_os();
+    args.next();
+    let path = args
+        .next()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "cache".to_string());
+    println!("{} {}", env::current_dir().unwrap().display(), path);
+
+    let graph = FilePersistedGraph::new(&path).unwrap();
+    let log_size = graph.log_size();
+    graph.compact().unwrap();
+    println!("merged {log_size} bytes of log into the snapshot");
+}
@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use turbo_tasks::RawVc;
+
+/// Task ids in the graph are independent from the [turbo_tasks::TaskId]s of a
+/// session. `0` is never assigned and used for "not in the graph".
+pub type DbTaskId = usize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct InternalTaskState {
+    pub clean: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct TaskState {
+    /// Set when the task has been persisted.
+    pub internal: Option<InternalTaskState>,
+    pub active: bool,
+    pub active_parents: u32,
+    pub externally_active: bool,
+}
+
+/// Sets of tasks that are read on startup or when the session ends.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum TaskSet {
+    ExternallyActive,
+    PotentialActiveExternal,
+    PotentialDirtyActive,
+    PendingActiveUpdate,
+}
+
+/// A single modification of the graph. Changes are written to the log in
+/// batches, which are replayed in order on startup.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Change {
+    NewTaskType {
+        task: DbTaskId,
+        key: Vec<u8>,
+    },
+    MakeDirty(DbTaskId),
+    MakeClean(DbTaskId),
+    Activate(DbTaskId),
+    Deactivate(DbTaskId),
+    IncrementActiveParents(DbTaskId),
+    DecrementActiveParents(DbTaskId),
+    SetExternallyActive(DbTaskId),
+    UnsetExternallyActive(DbTaskId),
+    Persist {
+        task: DbTaskId,
+        externally_active: bool,
+        /// [turbo_tasks::persisted_graph::TaskData] encoded with graph task
+        /// ids.
+        data: Vec<u8>,
+        children: Vec<DbTaskId>,
+        /// Dependencies, with graph task ids.
+        dependencies: Vec<RawVc>,
+    },
+    Insert(TaskSet, DbTaskId),
+    Remove(TaskSet, DbTaskId),
+}
+
+/// The whole persisted graph. All task ids in here, including the ones in
+/// [RawVc]s, are [DbTaskId]s.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Graph {
+    pub last_task_id: DbTaskId,
+    /// The encoded [turbo_tasks::backend::PersistentTaskType] of each task.
+    pub task_types: HashMap<DbTaskId, Vec<u8>>,
+    /// Ordered to allow lookups by a partial task type.
+    pub cache: BTreeMap<Vec<u8>, DbTaskId>,
+    pub states: HashMap<DbTaskId, TaskState>,
+    pub data: HashMap<DbTaskId, Vec<u8>>,
+    pub children: HashMap<DbTaskId, Vec<DbTaskId>>,
+    pub dependencies: HashMap<DbTaskId, Vec<RawVc>>,
+    pub dependents: HashMap<RawVc, HashSet<DbTaskId>>,
+    pub externally_active_tasks: HashSet<DbTaskId>,
+    pub potential_active_external_tasks: HashSet<DbTaskId>,
+    pub potential_dirty_active_tasks: HashSet<DbTaskId>,
+    pub pending_active_update: HashSet<DbTaskId>,
+}
+
+impl Graph {
+    pub fn state(&self, task: DbTaskId) -> Option<&TaskState> {
+        self.states.get(&task)
+    }
+
+    pub fn set(&self, set: TaskSet) -> &HashSet<DbTaskId> {
+        match set {
+            TaskSet::ExternallyActive => &self.externally_active_tasks,
+            TaskSet::PotentialActiveExternal => &self.potential_active_external_tasks,
+            TaskSet::PotentialDirtyActive => &self.potential_dirty_active_tasks,
+            TaskSet::PendingActiveUpdate => &self.pending_active_update,
+        }
+    }
+
+    fn set_mut(&mut self, set: TaskSet) -> &mut HashSet<DbTaskId> {
+        match set {
+            TaskSet::ExternallyActive => &mut self.externally_active_tasks,
+            TaskSet::PotentialActiveExternal => &mut self.potential_active_external_tasks,
+            TaskSet::PotentialDirtyActive => &mut self.potential_dirty_active_tasks,
+            TaskSet::PendingActiveUpdate => &mut self.pending_active_update,
+        }
+    }
+
+    pub fn apply(&mut self, change: Change) {
+        match change {
+            Change::NewTaskType { task, key } => {
+                self.last_task_id = self.last_task_id.max(task);
+                self.cache.insert(key.clone(), task);
+                self.task_types.insert(task, key);
+            }
+            Change::MakeDirty(task) => {
+                if let Some(internal) = &mut self.states.entry(task).or_default().internal {
+                    internal.clean = false;
+                }
+            }
+            Change::MakeClean(task) => {
+                if let Some(internal) = &mut self.states.entry(task).or_default().internal {
+                    internal.clean = true;
+                }
+            }
+            Change::Activate(task) => {
+                self.states.entry(task).or_default().active = true;
+            }
+            Change::Deactivate(task) => {
+                self.states.entry(task).or_default().active = false;
+            }
+            Change::IncrementActiveParents(task) => {
+                self.states.entry(task).or_default().active_parents += 1;
+            }
+            Change::DecrementActiveParents(task) => {
+                let state = self.states.entry(task).or_default();
+                state.active_parents = state.active_parents.saturating_sub(1);
+            }
+            Change::SetExternallyActive(task) => {
+                self.states.entry(task).or_default().externally_active = true;
+            }
+            Change::UnsetExternallyActive(task) => {
+                self.states.entry(task).or_default().externally_active = false;
+            }
+            Change::Persist {
+                task,
+                externally_active,
+                data,
+                children,
+                dependencies,
+            } => {
+                let state = self.states.entry(task).or_default();
+                state.internal = Some(InternalTaskState { clean: true });
+                state.externally_active = externally_active;
+                if externally_active {
+                    state.active = true;
+                }
+                self.data.insert(task, data);
+                self.children.insert(task, children);
+                if let Some(old_dependencies) = self.dependencies.get(&task) {
+                    for dep in old_dependencies {
+                        if let Some(dependents) = self.dependents.get_mut(dep) {
+                            dependents.remove(&task);
+                            if dependents.is_empty() {
+                                self.dependents.remove(dep);
+                            }
+                        }
+                    }
+                }
+                for dep in dependencies.iter() {
+                    self.dependents.entry(*dep).or_default().insert(task);
+                }
+                self.dependencies.insert(task, dependencies);
+            }
+            Change::Insert(set, task) => {
+                self.set_mut(set).insert(task);
+            }
+            Change::Remove(set, task) => {
+                self.set_mut(set).remove(&task);
+            }
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! A [turbo_tasks::persisted_graph::PersistedGraph] stored in plain files.
+//!
+//! Unlike `turbo-tasks-rocksdb`, it has no native dependencies, so it builds
+//! everywhere Rust does. The graph is stored as a snapshot plus an append-only
+//! log of changes, which can be merged into the snapshot with
+//! [FilePersistedGraph::compact] (or the `compact` example).
+
+mod graph;
+mod log;
+mod persisted_graph;
+
+pub use persisted_graph::FilePersistedGraph;
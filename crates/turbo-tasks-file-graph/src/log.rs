@@ -0,0 +1,351 @@
+//! On-disk format of the graph: a snapshot file holding the whole graph at a
+//! point in time and an append-only log of the change batches committed since.
+//!
+//! Every log record and the snapshot are checksummed. Log records carry an
+//! increasing sequence number and the snapshot stores the number of the last
+//! record it contains, so a crash at any point leaves a readable state:
+//!
+//! * A partially written record at the end of the log fails its checksum and is
+//!   truncated away on the next open. A record before the end can't be torn
+//!   that way, so when one fails its checksum, opening the log fails instead of
+//!   dropping the records after it.
+//! * The snapshot is written to a temporary file and renamed over the old one,
+//!   so it's either the old or the new snapshot.
+//! * Log records which are already part of the snapshot (the log wasn't
+//!   truncated after writing the snapshot) are skipped while replaying.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"TTFGSNP1";
+const SNAPSHOT_FILE: &str = "snapshot";
+const SNAPSHOT_TMP_FILE: &str = "snapshot.tmp";
+const LOG_FILE: &str = "log";
+
+/// Size of the record header: payload length (u32) and checksum (u64).
+const RECORD_HEADER_LEN: usize = 4 + 8;
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+/// FNV-1a, which is good enough to detect torn or garbled writes.
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Reads the snapshot in `dir`. Returns the sequence number of the last log
+/// record contained in it and its content.
+pub fn read_snapshot<T: DeserializeOwned>(dir: &Path) -> Result<Option<(u64, T)>> {
+    let path = dir.join(SNAPSHOT_FILE);
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("unable to read {}", path.display()));
+        }
+    };
+    let header_len = SNAPSHOT_MAGIC.len() + 8 + 8;
+    if bytes.len() < header_len || &bytes[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+        bail!("{} is not a snapshot of a persisted graph", path.display());
+    }
+    let read_u64 =
+        |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+    let seq = read_u64(SNAPSHOT_MAGIC.len());
+    let expected_checksum = read_u64(SNAPSHOT_MAGIC.len() + 8);
+    let payload = &bytes[header_len..];
+    if checksum(payload) != expected_checksum {
+        bail!("{} is corrupted", path.display());
+    }
+    let content = options()
+        .deserialize(payload)
+        .with_context(|| format!("unable to decode {}", path.display()))?;
+    Ok(Some((seq, content)))
+}
+
+/// Atomically replaces the snapshot in `dir` with `content`, which contains
+/// all log records up to `seq`.
+pub fn write_snapshot<T: Serialize>(dir: &Path, seq: u64, content: &T) -> Result<()> {
+    let payload = options().serialize(content)?;
+    let tmp_path = dir.join(SNAPSHOT_TMP_FILE);
+    {
+        let mut file = File::create(&tmp_path)
+            .with_context(|| format!("unable to create {}", tmp_path.display()))?;
+        file.write_all(SNAPSHOT_MAGIC)?;
+        file.write_all(&seq.to_le_bytes())?;
+        file.write_all(&checksum(&payload).to_le_bytes())?;
+        file.write_all(&payload)?;
+        file.sync_all()?;
+    }
+    let path = dir.join(SNAPSHOT_FILE);
+    fs::rename(&tmp_path, &path)
+        .with_context(|| format!("unable to replace {}", path.display()))?;
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Makes a rename within `dir` durable. Directories can't be opened on all
+/// platforms, in which case the rename is durable already or can't be made
+/// so.
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// The append-only log of change batches.
+pub struct Log {
+    path: PathBuf,
+    file: File,
+    last_seq: u64,
+    len: u64,
+}
+
+impl Log {
+    /// Opens the log in `dir` and passes all records after `snapshot_seq` to
+    /// `apply` in order. A partially written record at the end, left by a
+    /// crash, is removed, while a corrupted record before the end is an error.
+    pub fn open<T: DeserializeOwned>(
+        dir: &Path,
+        snapshot_seq: u64,
+        mut apply: impl FnMut(T) -> Result<()>,
+    ) -> Result<Self> {
+        let path = dir.join(LOG_FILE);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .with_context(|| format!("unable to open {}", path.display()))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut last_seq = snapshot_seq;
+        let mut offset = 0;
+        loop {
+            let (seq, payload, next_offset) = match read_record(&bytes, offset) {
+                ReadRecord::Record(seq, payload, next_offset) => (seq, payload, next_offset),
+                ReadRecord::End => break,
+                ReadRecord::Corrupted => {
+                    bail!("{} is corrupted at offset {}", path.display(), offset)
+                }
+            };
+            if seq > last_seq {
+                let content = options()
+                    .deserialize(payload)
+                    .with_context(|| format!("unable to decode {}", path.display()))?;
+                apply(content)?;
+                last_seq = seq;
+            }
+            offset = next_offset;
+        }
+        if offset < bytes.len() {
+            file.set_len(offset as u64)?;
+            file.sync_all()?;
+        }
+
+        Ok(Self {
+            path,
+            file,
+            last_seq,
+            len: offset as u64,
+        })
+    }
+
+    /// The sequence number of the last record in the log.
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// The size of the log file in bytes.
+    pub fn size(&self) -> u64 {
+        self.len
+    }
+
+    /// Appends a record to the log.
+    pub fn append<T: Serialize>(&mut self, content: &T) -> Result<()> {
+        let seq = self.last_seq + 1;
+        let payload = options().serialize(&(seq, content))?;
+        let mut record = Vec::with_capacity(RECORD_HEADER_LEN + payload.len());
+        record.extend_from_slice(&u32::try_from(payload.len())?.to_le_bytes());
+        record.extend_from_slice(&checksum(&payload).to_le_bytes());
+        record.extend_from_slice(&payload);
+        if let Err(err) = self.file.write_all(&record) {
+            // Remove what has been written of the record, so that following
+            // records are not hidden behind a torn one.
+            let _ = self.file.set_len(self.len);
+            return Err(err).with_context(|| format!("unable to write to {}", self.path.display()));
+        }
+        self.last_seq = seq;
+        self.len += record.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes all records to disk.
+    pub fn sync(&self) -> Result<()> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    /// Removes all records. Must only be called after they have been written
+    /// to a snapshot.
+    pub fn clear(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+enum ReadRecord<'a> {
+    /// The sequence number, the encoded content and the offset of the next
+    /// record.
+    Record(u64, &'a [u8], usize),
+    /// The end of the log, which might be a partially written record.
+    End,
+    /// A record before the end of the log is corrupted.
+    Corrupted,
+}
+
+/// Reads the record at `offset`.
+fn read_record(bytes: &[u8], offset: usize) -> ReadRecord<'_> {
+    let Some(header) = bytes.get(offset..offset + RECORD_HEADER_LEN) else {
+        return ReadRecord::End;
+    };
+    let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let expected_checksum = u64::from_le_bytes(header[4..].try_into().unwrap());
+    let start = offset + RECORD_HEADER_LEN;
+    let end = start + len;
+    let Some(payload) = bytes.get(start..end) else {
+        return ReadRecord::End;
+    };
+    // Only the last record can be torn by a crash
+    let invalid = if end == bytes.len() {
+        ReadRecord::End
+    } else {
+        ReadRecord::Corrupted
+    };
+    if checksum(payload) != expected_checksum {
+        return invalid;
+    }
+    // The payload is `(seq, content)`, where `seq` is a varint followed by the
+    // content.
+    let mut reader = payload;
+    let Ok(seq) = options().deserialize_from::<_, u64>(&mut reader) else {
+        return invalid;
+    };
+    ReadRecord::Record(seq, reader, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+
+    use anyhow::Result;
+
+    use super::{read_snapshot, write_snapshot, Log, LOG_FILE, RECORD_HEADER_LEN};
+
+    fn read_all(dir: &std::path::Path, snapshot_seq: u64) -> Result<(Log, Vec<String>)> {
+        let mut records = Vec::new();
+        let log = Log::open(dir, snapshot_seq, |record: String| {
+            records.push(record);
+            Ok(())
+        })?;
+        Ok((log, records))
+    }
+
+    #[test]
+    fn replays_appended_records() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        {
+            let (mut log, records) = read_all(dir.path(), 0)?;
+            assert!(records.is_empty());
+            log.append(&"a")?;
+            log.append(&"b")?;
+        }
+        let (log, records) = read_all(dir.path(), 0)?;
+        assert_eq!(records, ["a", "b"]);
+        assert_eq!(log.last_seq(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn truncates_torn_record() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let len_after_first = {
+            let (mut log, _) = read_all(dir.path(), 0)?;
+            log.append(&"a")?;
+            let len = log.size();
+            log.append(&"b")?;
+            len
+        };
+        let file = OpenOptions::new()
+            .write(true)
+            .open(dir.path().join(LOG_FILE))?;
+        file.set_len(len_after_first + 3)?;
+
+        let (mut log, records) = read_all(dir.path(), 0)?;
+        assert_eq!(records, ["a"]);
+        assert_eq!(log.size(), len_after_first);
+        log.append(&"c")?;
+        drop(log);
+
+        let (_, records) = read_all(dir.path(), 0)?;
+        assert_eq!(records, ["a", "c"]);
+        Ok(())
+    }
+
+    #[test]
+    fn fails_on_corrupted_record_before_end() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let len_after_first = {
+            let (mut log, _) = read_all(dir.path(), 0)?;
+            log.append(&"a")?;
+            let len = log.size();
+            log.append(&"b")?;
+            log.append(&"c")?;
+            len
+        };
+        let path = dir.path().join(LOG_FILE);
+        let mut bytes = fs::read(&path)?;
+        bytes[len_after_first as usize + RECORD_HEADER_LEN] ^= 0xff;
+        fs::write(&path, &bytes)?;
+
+        assert!(read_all(dir.path(), 0).is_err());
+        // The log is left as it is
+        assert_eq!(fs::read(&path)?, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn skips_records_contained_in_snapshot() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        {
+            let (mut log, _) = read_all(dir.path(), 0)?;
+            log.append(&"a")?;
+            log.append(&"b")?;
+            write_snapshot(dir.path(), log.last_seq(), &"ab".to_string())?;
+            // The log is not cleared, as if the process crashed after writing
+            // the snapshot.
+            log.append(&"c")?;
+        }
+        let (seq, snapshot) = read_snapshot::<String>(dir.path())?.unwrap();
+        assert_eq!((seq, snapshot.as_str()), (2, "ab"));
+        let (log, records) = read_all(dir.path(), seq)?;
+        assert_eq!(records, ["c"]);
+        assert_eq!(log.last_seq(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,1065 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bincode::Options;
+use parking_lot::Mutex;
+use turbo_tasks::{
+    backend::PersistentTaskType,
+    persisted_graph::{
+        ActivateResult, DeactivateResult, PersistResult, PersistTaskState, PersistedGraph,
+        PersistedGraphApi, ReadTaskState, TaskCell, TaskData,
+    },
+    with_task_id_mapping, without_task_id_mapping, FunctionId, IdMapping, RawVc, TaskId,
+    TraitTypeId,
+};
+
+use crate::{
+    graph::{Change, DbTaskId, Graph, InternalTaskState, TaskSet, TaskState},
+    log::{read_snapshot, write_snapshot, Log},
+};
+
+/// When the log grows beyond this size, it's compacted into the snapshot when
+/// the graph is stopped.
+const COMPACT_LOG_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Maximum number of cache entries returned by a lookup of a partial task type.
+const LOOKUP_LIMIT: usize = 1000;
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+/// Encodes a task type in a way that the encoding of a partial task type (with
+/// fewer inputs) is a prefix of it.
+fn task_type_to_bytes(ty: &PersistentTaskType) -> Result<Vec<u8>, bincode::Error> {
+    let mut result = Vec::new();
+    let inputs = match ty {
+        PersistentTaskType::Native(f, i) => {
+            result.push(0);
+            options().serialize_into(&mut result, f)?;
+            i
+        }
+        PersistentTaskType::ResolveNative(f, i) => {
+            result.push(1);
+            options().serialize_into(&mut result, f)?;
+            i
+        }
+        PersistentTaskType::ResolveTrait(t, n, i) => {
+            result.push(2);
+            options().serialize_into(&mut result, t)?;
+            options().serialize_into(&mut result, n)?;
+            i
+        }
+    };
+    for input in inputs {
+        options().serialize_into(&mut result, input)?;
+    }
+    Ok(result)
+}
+
+fn task_type_from_bytes(bytes: &[u8]) -> Result<PersistentTaskType> {
+    let (tag, mut reader) = bytes.split_first().context("empty task type")?;
+    Ok(match tag {
+        0 => {
+            let f: FunctionId = options().deserialize_from(&mut reader)?;
+            PersistentTaskType::Native(f, read_inputs(reader)?)
+        }
+        1 => {
+            let f: FunctionId = options().deserialize_from(&mut reader)?;
+            PersistentTaskType::ResolveNative(f, read_inputs(reader)?)
+        }
+        2 => {
+            let t: TraitTypeId = options().deserialize_from(&mut reader)?;
+            let n: Cow<'static, str> = options().deserialize_from(&mut reader)?;
+            PersistentTaskType::ResolveTrait(t, n, read_inputs(reader)?)
+        }
+        _ => bail!("invalid task type tag {tag}"),
+    })
+}
+
+fn read_inputs<T: serde::de::DeserializeOwned>(mut reader: &[u8]) -> Result<Vec<T>> {
+    let mut inputs = Vec::new();
+    while !reader.is_empty() {
+        inputs.push(options().deserialize_from(&mut reader)?);
+    }
+    Ok(inputs)
+}
+
+struct Inner {
+    graph: Graph,
+    log: Log,
+}
+
+/// A [PersistedGraph] stored in plain files in a directory, without any
+/// native dependencies.
+///
+/// The whole graph is kept in memory. Modifications are appended to a log,
+/// which is merged into a snapshot of the graph by
+/// [FilePersistedGraph::compact].
+pub struct FilePersistedGraph {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+    task_id_forward_mapping: Mutex<HashMap<TaskId, DbTaskId>>,
+    task_id_backward_mapping: Mutex<HashMap<DbTaskId, TaskId>>,
+    /// Tasks that have been marked as externally active in this session.
+    renewed_externally_active: Mutex<HashSet<DbTaskId>>,
+}
+
+impl FilePersistedGraph {
+    /// Opens the graph stored in the directory `path`, creating it when it
+    /// doesn't exist yet.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(&path)
+            .with_context(|| format!("unable to create {}", path.display()))?;
+        let (snapshot_seq, mut graph) = read_snapshot::<Graph>(&path)?.unwrap_or_default();
+        let log = Log::open(&path, snapshot_seq, |changes: Vec<Change>| {
+            for change in changes {
+                graph.apply(change);
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            path,
+            inner: Mutex::new(Inner { graph, log }),
+            task_id_forward_mapping: Mutex::new(HashMap::new()),
+            task_id_backward_mapping: Mutex::new(HashMap::new()),
+            renewed_externally_active: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Writes the whole graph into a new snapshot and empties the log.
+    pub fn compact(&self) -> Result<()> {
+        let mut inner = self.inner.lock();
+        let Inner { graph, log } = &mut *inner;
+        without_task_id_mapping(|| write_snapshot(&self.path, log.last_seq(), graph))?;
+        log.clear()
+    }
+
+    /// The size of the log in bytes, i. e. the amount of data that would be
+    /// merged into the snapshot by [FilePersistedGraph::compact].
+    pub fn log_size(&self) -> u64 {
+        self.inner.lock().log.size()
+    }
+
+    /// Applies a batch of changes and appends it to the log. Changes are only
+    /// applied when they could be written.
+    fn commit(inner: &mut Inner, changes: Vec<Change>) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        without_task_id_mapping(|| inner.log.append(&changes))?;
+        for change in changes {
+            inner.graph.apply(change);
+        }
+        Ok(())
+    }
+
+    fn get_task_type(&self, ty: &PersistentTaskType) -> Result<Option<DbTaskId>> {
+        // Encoding the task type may map task ids of its inputs, which needs to
+        // happen without holding the lock.
+        let key = task_type_to_bytes(ty)?;
+        Ok(self.inner.lock().graph.cache.get(&key).copied())
+    }
+
+    fn get_or_create_task_type(&self, ty: &PersistentTaskType) -> Result<DbTaskId> {
+        let key = task_type_to_bytes(ty)?;
+        let mut inner = self.inner.lock();
+        if let Some(db_task) = inner.graph.cache.get(&key) {
+            return Ok(*db_task);
+        }
+        let db_task = inner.graph.last_task_id + 1;
+        Self::commit(&mut inner, vec![Change::NewTaskType { task: db_task, key }])?;
+        Ok(db_task)
+    }
+
+    fn lookup_task_type(&self, db_task: DbTaskId) -> Result<PersistentTaskType> {
+        let key = self
+            .inner
+            .lock()
+            .graph
+            .task_types
+            .get(&db_task)
+            .cloned()
+            .ok_or_else(|| anyhow!("Invalid task id {}", db_task))?;
+        // Decoding may map task ids of its inputs, which needs to happen
+        // without holding the lock.
+        task_type_from_bytes(&key)
+    }
+
+    fn is_active(graph: &Graph, db_task: DbTaskId) -> bool {
+        graph.state(db_task).map_or(false, |state| state.active)
+    }
+
+    /// Collects the tasks of `set` that match `filter` and removes the ones
+    /// that don't.
+    fn take_matching_from_set(
+        &self,
+        set: TaskSet,
+        filter: impl Fn(&TaskState) -> bool,
+    ) -> Result<Vec<DbTaskId>> {
+        let mut inner = self.inner.lock();
+        let mut result = Vec::new();
+        let mut changes = Vec::new();
+        for db_task in inner.graph.set(set).iter() {
+            if inner.graph.state(*db_task).map_or(false, &filter) {
+                result.push(*db_task);
+            } else {
+                changes.push(Change::Remove(set, *db_task));
+            }
+        }
+        Self::commit(&mut inner, changes)?;
+        Ok(result)
+    }
+}
+
+impl PersistedGraph for FilePersistedGraph {
+    fn read(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<(TaskData, ReadTaskState)>> {
+        let mapping = PgApiMapping::read_only(self, api);
+        let db_task = mapping.forward(task);
+        if db_task == 0 {
+            return Ok(None);
+        }
+        let (data, clean, active_parents) = {
+            let inner = self.inner.lock();
+            let (Some(data), Some(TaskState {
+                internal: Some(InternalTaskState { clean }),
+                active_parents,
+                ..
+            })) = (inner.graph.data.get(&db_task), inner.graph.state(db_task)) else {
+                return Ok(None);
+            };
+            (data.clone(), *clean, *active_parents)
+        };
+        let data = with_task_id_mapping(&mapping, || options().deserialize::<TaskData>(&data))?;
+        Ok(Some((
+            data,
+            ReadTaskState {
+                clean,
+                keeps_external_active: active_parents > 0,
+            },
+        )))
+    }
+
+    fn lookup(
+        &self,
+        partial_task_type: &PersistentTaskType,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<bool> {
+        let mapping = PgApiMapping::read_only(self, api);
+        let prefix = with_task_id_mapping(mapping, || task_type_to_bytes(partial_task_type))?;
+        let db_tasks = {
+            let inner = self.inner.lock();
+            inner
+                .graph
+                .cache
+                .range(prefix.clone()..)
+                .take_while(|(key, _)| key.starts_with(&prefix))
+                .map(|(_, db_task)| *db_task)
+                .take(LOOKUP_LIMIT + 1)
+                .collect::<Vec<_>>()
+        };
+        if db_tasks.len() > LOOKUP_LIMIT {
+            return Ok(false);
+        }
+        let mapping = PgApiMapping::new(self, api);
+        with_task_id_mapping(&mapping, || {
+            for db_task in db_tasks {
+                mapping.backward(db_task);
+            }
+        });
+        Ok(true)
+    }
+
+    fn lookup_one(
+        &self,
+        task_type: &PersistentTaskType,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<TaskId>> {
+        let mapping = PgApiMapping::read_only(self, api);
+        let key = with_task_id_mapping(mapping, || task_type_to_bytes(task_type))?;
+        let db_task = {
+            let inner = self.inner.lock();
+            match inner.graph.cache.get(&key) {
+                Some(db_task)
+                    if matches!(
+                        inner.graph.state(*db_task),
+                        Some(TaskState {
+                            internal: Some(_),
+                            ..
+                        })
+                    ) =>
+                {
+                    *db_task
+                }
+                _ => return Ok(None),
+            }
+        };
+        let mapping = PgApiMapping::new(self, api);
+        Ok(Some(with_task_id_mapping(&mapping, || {
+            mapping.backward(db_task)
+        })))
+    }
+
+    fn is_persisted(&self, task: TaskId, api: &dyn PersistedGraphApi) -> Result<bool> {
+        let db_task = PgApiMapping::read_only(self, api).forward(task);
+        if db_task == 0 {
+            return Ok(false);
+        }
+        let inner = self.inner.lock();
+        Ok(matches!(
+            inner.graph.state(db_task),
+            Some(TaskState {
+                internal: Some(_),
+                ..
+            })
+        ))
+    }
+
+    fn persist(
+        &self,
+        task: TaskId,
+        mut data: TaskData,
+        state: PersistTaskState,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<PersistResult>> {
+        let mapping = PgApiMapping::new(self, api);
+        let prepared = with_task_id_mapping(&mapping, || {
+            let db_task = mapping.forward(task);
+            let children = data
+                .children
+                .iter()
+                .map(|child| (*child, mapping.forward(*child)))
+                .collect::<Vec<_>>();
+            let dependencies = data
+                .dependencies
+                .iter()
+                .map(|dep| match *dep {
+                    RawVc::TaskOutput(t) => RawVc::TaskOutput(mapping.forward(t).into()),
+                    RawVc::TaskCell(t, index) => RawVc::TaskCell(mapping.forward(t).into(), index),
+                })
+                .collect::<Vec<_>>();
+            for (_, cell) in data.cells.iter_mut() {
+                if let TaskCell::Content(c) = &*cell {
+                    // Cells that can't be serialized need to be recomputed after
+                    // reading the task.
+                    if options().serialize(c).is_err() {
+                        *cell = TaskCell::NeedComputation;
+                    }
+                }
+            }
+            options()
+                .serialize(&data)
+                .ok()
+                .map(|encoded| (db_task, children, dependencies, encoded))
+        });
+        let Some((db_task, children, dependencies, encoded)) = prepared else {
+            return Ok(None);
+        };
+
+        let mut tasks_to_activate = Vec::new();
+        let mut db_tasks_to_deactivate = Vec::new();
+        {
+            let mut inner = self.inner.lock();
+            let graph = &inner.graph;
+            let mut changes = Vec::new();
+            let mut activate_child = |child: TaskId, db_child: DbTaskId| {
+                changes.push(Change::IncrementActiveParents(db_child));
+                changes.push(Change::Insert(TaskSet::PendingActiveUpdate, db_child));
+                tasks_to_activate.push(child);
+            };
+            if Self::is_active(graph, db_task) {
+                // Task is and will stay active, only the difference in children
+                // needs to update the active parents counts
+                let mut removed_children = graph
+                    .children
+                    .get(&db_task)
+                    .map(|children| children.iter().copied().collect::<HashSet<_>>())
+                    .unwrap_or_default();
+                for &(child, db_child) in children.iter() {
+                    if !removed_children.remove(&db_child) {
+                        activate_child(child, db_child);
+                    }
+                }
+                for db_child in removed_children {
+                    changes.push(Change::DecrementActiveParents(db_child));
+                    changes.push(Change::Insert(TaskSet::PendingActiveUpdate, db_child));
+                    db_tasks_to_deactivate.push(db_child);
+                }
+            } else if state.externally_active {
+                // Task was not active before, but will activate with this operation
+                for &(child, db_child) in children.iter() {
+                    activate_child(child, db_child);
+                }
+            }
+            changes.push(Change::Persist {
+                task: db_task,
+                externally_active: state.externally_active,
+                data: encoded,
+                children: children.iter().map(|(_, db_child)| *db_child).collect(),
+                dependencies,
+            });
+            changes.push(Change::Remove(TaskSet::PendingActiveUpdate, db_task));
+            Self::commit(&mut inner, changes)?;
+        }
+
+        let tasks_to_deactivate = with_task_id_mapping(&mapping, || {
+            db_tasks_to_deactivate
+                .into_iter()
+                .map(|db_child| mapping.backward(db_child))
+                .collect()
+        });
+        Ok(Some(PersistResult {
+            tasks_to_activate,
+            tasks_to_deactivate,
+        }))
+    }
+
+    fn activate_when_needed(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<ActivateResult>> {
+        let mapping = PgApiMapping::new(self, api);
+        let db_task = with_task_id_mapping(&mapping, || mapping.forward(task));
+        let (result, db_children) = {
+            let mut inner = self.inner.lock();
+            let graph = &inner.graph;
+            let mut changes = Vec::new();
+            if graph.set(TaskSet::PendingActiveUpdate).contains(&db_task) {
+                changes.push(Change::Remove(TaskSet::PendingActiveUpdate, db_task));
+            }
+            let mut result = None;
+            if let Some(&TaskState {
+                internal,
+                active,
+                active_parents,
+                externally_active,
+            }) = graph.state(db_task)
+            {
+                if !active && (active_parents > 0 || externally_active) {
+                    let db_children = graph.children.get(&db_task).cloned().unwrap_or_default();
+                    for db_child in db_children.iter() {
+                        changes.push(Change::IncrementActiveParents(*db_child));
+                        changes.push(Change::Insert(TaskSet::PendingActiveUpdate, *db_child));
+                    }
+                    changes.push(Change::Activate(db_task));
+                    if let Some(InternalTaskState { clean: false }) = internal {
+                        changes.push(Change::Insert(TaskSet::PotentialDirtyActive, db_task));
+                    }
+                    if internal.is_none() {
+                        changes.push(Change::Insert(TaskSet::PotentialActiveExternal, db_task));
+                    }
+                    result = Some((
+                        ActivateResult {
+                            keeps_external_active: active_parents > 0,
+                            external: internal.is_none(),
+                            dirty: internal.map(|i| !i.clean).unwrap_or_default(),
+                            more_tasks_to_activate: Vec::new(),
+                        },
+                        db_children,
+                    ));
+                }
+            }
+            Self::commit(&mut inner, changes)?;
+            match result {
+                Some(result) => result,
+                None => return Ok(None),
+            }
+        };
+        let more_tasks_to_activate = with_task_id_mapping(&mapping, || {
+            db_children
+                .into_iter()
+                .map(|db_child| mapping.backward(db_child))
+                .collect()
+        });
+        Ok(Some(ActivateResult {
+            more_tasks_to_activate,
+            ..result
+        }))
+    }
+
+    fn deactivate_when_needed(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<DeactivateResult>> {
+        let mapping = PgApiMapping::new(self, api);
+        let db_task = with_task_id_mapping(&mapping, || mapping.forward(task));
+        let db_children = {
+            let mut inner = self.inner.lock();
+            let graph = &inner.graph;
+            let mut changes = Vec::new();
+            if graph.set(TaskSet::PendingActiveUpdate).contains(&db_task) {
+                changes.push(Change::Remove(TaskSet::PendingActiveUpdate, db_task));
+            }
+            let mut db_children = None;
+            if let Some(&TaskState {
+                active,
+                active_parents,
+                externally_active,
+                ..
+            }) = graph.state(db_task)
+            {
+                if active && active_parents == 0 && !externally_active {
+                    let children = graph.children.get(&db_task).cloned().unwrap_or_default();
+                    for db_child in children.iter() {
+                        changes.push(Change::DecrementActiveParents(*db_child));
+                        changes.push(Change::Insert(TaskSet::PendingActiveUpdate, *db_child));
+                    }
+                    changes.push(Change::Deactivate(db_task));
+                    changes.push(Change::Remove(TaskSet::PotentialDirtyActive, db_task));
+                    db_children = Some(children);
+                }
+            }
+            Self::commit(&mut inner, changes)?;
+            match db_children {
+                Some(db_children) => db_children,
+                None => return Ok(None),
+            }
+        };
+        let more_tasks_to_deactivate = with_task_id_mapping(&mapping, || {
+            db_children
+                .into_iter()
+                .map(|db_child| mapping.backward(db_child))
+                .collect()
+        });
+        Ok(Some(DeactivateResult {
+            more_tasks_to_deactivate,
+        }))
+    }
+
+    fn set_externally_active(&self, task: TaskId, api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mapping = PgApiMapping::new(self, api);
+        let db_task = with_task_id_mapping(&mapping, || mapping.forward(task));
+        self.renewed_externally_active.lock().insert(db_task);
+        let mut inner = self.inner.lock();
+        Self::commit(
+            &mut inner,
+            vec![
+                Change::SetExternallyActive(db_task),
+                Change::Insert(TaskSet::ExternallyActive, db_task),
+            ],
+        )?;
+        Ok(inner
+            .graph
+            .state(db_task)
+            .map_or(false, |state| !state.active && state.active_parents == 0))
+    }
+
+    fn unset_externally_active(&self, task: TaskId, api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mapping = PgApiMapping::new(self, api);
+        let db_task = with_task_id_mapping(&mapping, || mapping.forward(task));
+        self.renewed_externally_active.lock().remove(&db_task);
+        let mut inner = self.inner.lock();
+        Self::commit(
+            &mut inner,
+            vec![
+                Change::UnsetExternallyActive(db_task),
+                Change::Remove(TaskSet::ExternallyActive, db_task),
+            ],
+        )?;
+        Ok(inner
+            .graph
+            .state(db_task)
+            .map_or(false, |state| state.active && state.active_parents == 0))
+    }
+
+    fn remove_outdated_externally_active(
+        &self,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Vec<TaskId>> {
+        let db_tasks = {
+            let renewed = self.renewed_externally_active.lock();
+            let mut inner = self.inner.lock();
+            let mut changes = Vec::new();
+            let mut db_tasks = Vec::new();
+            for db_task in inner.graph.set(TaskSet::ExternallyActive).iter() {
+                if renewed.contains(db_task) {
+                    continue;
+                }
+                changes.push(Change::UnsetExternallyActive(*db_task));
+                changes.push(Change::Remove(TaskSet::ExternallyActive, *db_task));
+                if inner
+                    .graph
+                    .state(*db_task)
+                    .map_or(false, |state| state.active && state.active_parents == 0)
+                {
+                    db_tasks.push(*db_task);
+                }
+            }
+            Self::commit(&mut inner, changes)?;
+            db_tasks
+        };
+        let mapping = PgApiMapping::new(self, api);
+        Ok(with_task_id_mapping(&mapping, || {
+            db_tasks
+                .into_iter()
+                .map(|db_task| mapping.backward(db_task))
+                .collect()
+        }))
+    }
+
+    fn make_dirty(&self, task: TaskId, api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mapping = PgApiMapping::new(self, api);
+        let db_task = with_task_id_mapping(&mapping, || mapping.forward(task));
+        let mut inner = self.inner.lock();
+        Self::commit(
+            &mut inner,
+            vec![
+                Change::MakeDirty(db_task),
+                Change::Insert(TaskSet::PotentialDirtyActive, db_task),
+            ],
+        )?;
+        Ok(Self::is_active(&inner.graph, db_task))
+    }
+
+    fn make_clean(&self, task: TaskId, api: &dyn PersistedGraphApi) -> Result<()> {
+        let db_task = PgApiMapping::read_only(self, api).forward(task);
+        if db_task == 0 {
+            return Ok(());
+        }
+        let mut inner = self.inner.lock();
+        Self::commit(
+            &mut inner,
+            vec![
+                Change::MakeClean(db_task),
+                Change::Remove(TaskSet::PotentialDirtyActive, db_task),
+            ],
+        )
+    }
+
+    fn make_dependent_dirty(&self, vc: RawVc, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        let db_task = PgApiMapping::read_only(self, api).forward(vc.get_task_id());
+        if db_task == 0 {
+            return Ok(Vec::new());
+        }
+        let db_vc = match vc {
+            RawVc::TaskOutput(_) => RawVc::TaskOutput(db_task.into()),
+            RawVc::TaskCell(_, index) => RawVc::TaskCell(db_task.into(), index),
+        };
+        let active_db_tasks = {
+            let mut inner = self.inner.lock();
+            let Some(dependents) = inner.graph.dependents.get(&db_vc) else {
+                return Ok(Vec::new());
+            };
+            let mut changes = Vec::new();
+            let mut active_db_tasks = Vec::new();
+            for db_task in dependents.iter() {
+                changes.push(Change::MakeDirty(*db_task));
+                changes.push(Change::Insert(TaskSet::PotentialDirtyActive, *db_task));
+                if Self::is_active(&inner.graph, *db_task) {
+                    active_db_tasks.push(*db_task);
+                }
+            }
+            Self::commit(&mut inner, changes)?;
+            active_db_tasks
+        };
+        let mapping = PgApiMapping::new(self, api);
+        Ok(with_task_id_mapping(&mapping, || {
+            active_db_tasks
+                .into_iter()
+                .map(|db_task| mapping.backward(db_task))
+                .collect()
+        }))
+    }
+
+    fn get_active_external_tasks(&self, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        // For startup
+        let db_tasks = self.take_matching_from_set(TaskSet::PotentialActiveExternal, |state| {
+            state.internal.is_none() && state.active
+        })?;
+        let mapping = PgApiMapping::new(self, api);
+        Ok(with_task_id_mapping(&mapping, || {
+            db_tasks
+                .into_iter()
+                .map(|db_task| mapping.backward(db_task))
+                .collect()
+        }))
+    }
+
+    fn get_dirty_active_tasks(&self, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        // For startup
+        let db_tasks = self.take_matching_from_set(TaskSet::PotentialDirtyActive, |state| {
+            matches!(state.internal, Some(InternalTaskState { clean: false })) && state.active
+        })?;
+        let mapping = PgApiMapping::new(self, api);
+        Ok(with_task_id_mapping(&mapping, || {
+            db_tasks
+                .into_iter()
+                .map(|db_task| mapping.backward(db_task))
+                .collect()
+        }))
+    }
+
+    fn get_pending_active_update(
+        &self,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<(Vec<TaskId>, Vec<TaskId>)> {
+        // For startup
+        let db_tasks = self.take_matching_from_set(TaskSet::PendingActiveUpdate, |state| {
+            needs_activation(state) || needs_deactivation(state)
+        })?;
+        let mapping = PgApiMapping::new(self, api);
+        let (to_activate, to_deactivate): (Vec<_>, Vec<_>) = {
+            let inner = self.inner.lock();
+            db_tasks
+                .into_iter()
+                .partition(|db_task| inner.graph.state(*db_task).map_or(false, needs_activation))
+        };
+        Ok(with_task_id_mapping(&mapping, || {
+            (
+                to_activate
+                    .into_iter()
+                    .map(|db_task| mapping.backward(db_task))
+                    .collect(),
+                to_deactivate
+                    .into_iter()
+                    .map(|db_task| mapping.backward(db_task))
+                    .collect(),
+            )
+        }))
+    }
+
+    fn stop(&self, _api: &dyn PersistedGraphApi) -> Result<()> {
+        if self.log_size() > COMPACT_LOG_SIZE {
+            self.compact()
+        } else {
+            self.inner.lock().log.sync()
+        }
+    }
+}
+
+fn needs_activation(state: &TaskState) -> bool {
+    !state.active && (state.active_parents > 0 || state.externally_active)
+}
+
+fn needs_deactivation(state: &TaskState) -> bool {
+    state.active && state.active_parents == 0 && !state.externally_active
+}
+
+/// Maps between the task ids of the session and the ones of the graph.
+struct PgApiMapping<'a> {
+    this: &'a FilePersistedGraph,
+    api: &'a dyn PersistedGraphApi,
+    /// Doesn't add task types to the graph and maps unknown tasks to `0`
+    /// instead.
+    read_only: bool,
+}
+
+impl<'a> PgApiMapping<'a> {
+    fn new(this: &'a FilePersistedGraph, api: &'a dyn PersistedGraphApi) -> Self {
+        Self {
+            this,
+            api,
+            read_only: false,
+        }
+    }
+
+    fn read_only(this: &'a FilePersistedGraph, api: &'a dyn PersistedGraphApi) -> Self {
+        Self {
+            this,
+            api,
+            read_only: true,
+        }
+    }
+}
+
+impl<'a> IdMapping<TaskId> for PgApiMapping<'a> {
+    fn forward(&self, id: TaskId) -> usize {
+        if let Some(r) = self.this.task_id_forward_mapping.lock().get(&id) {
+            return *r;
+        }
+        let ty = self.api.lookup_task_type(id);
+        if self.read_only {
+            return self.this.get_task_type(ty).unwrap().unwrap_or_default();
+        }
+        let new_id = self.this.get_or_create_task_type(ty).unwrap();
+        self.this
+            .task_id_backward_mapping
+            .lock()
+            .entry(new_id)
+            .or_insert(id);
+        self.this
+            .task_id_forward_mapping
+            .lock()
+            .entry(id)
+            .or_insert(new_id);
+        new_id
+    }
+
+    fn backward(&self, id: usize) -> TaskId {
+        if let Some(r) = self.this.task_id_backward_mapping.lock().get(&id) {
+            return *r;
+        }
+        let ty = self.this.lookup_task_type(id).unwrap();
+        let new_id = self.api.get_or_create_task_type(ty);
+        self.this
+            .task_id_forward_mapping
+            .lock()
+            .entry(new_id)
+            .or_insert(id);
+        *self
+            .this
+            .task_id_backward_mapping
+            .lock()
+            .entry(id)
+            .or_insert(new_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Once};
+
+    use anyhow::{bail, Result};
+    use turbo_tasks::{
+        backend::PersistentTaskType,
+        persisted_graph::{PersistTaskState, PersistedGraph, PersistedGraphApi, TaskData},
+        registry, FunctionId, NativeFunction, RawVc, TaskId, TaskInput,
+    };
+
+    use super::FilePersistedGraph;
+
+    const FUNCTION_NAME: &str = "turbo-tasks-file-graph::tests::function";
+
+    fn function() -> FunctionId {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| {
+            let function = NativeFunction::new("function".to_string(), |_| {
+                bail!("tasks are not executed in these tests")
+            });
+            registry::register_function(FUNCTION_NAME, Box::leak(Box::new(function)));
+        });
+        registry::get_function_id_by_global_name(FUNCTION_NAME).unwrap()
+    }
+
+    fn task_type(index: u32) -> PersistentTaskType {
+        PersistentTaskType::Native(function(), vec![TaskInput::U32(index)])
+    }
+
+    /// The task types of a session. Every session numbers the tasks
+    /// differently, so the graph has to map them.
+    struct TestApi {
+        task_types: Vec<PersistentTaskType>,
+    }
+
+    impl TestApi {
+        fn new(indices: impl IntoIterator<Item = u32>) -> Self {
+            Self {
+                task_types: indices.into_iter().map(task_type).collect(),
+            }
+        }
+
+        fn task(&self, index: u32) -> TaskId {
+            self.get_or_create_task_type(task_type(index))
+        }
+    }
+
+    impl PersistedGraphApi for TestApi {
+        fn get_or_create_task_type(&self, ty: PersistentTaskType) -> TaskId {
+            let index = self
+                .task_types
+                .iter()
+                .position(|t| *t == ty)
+                .expect("unknown task type");
+            TaskId::from(index + 1)
+        }
+
+        fn lookup_task_type(&self, id: TaskId) -> &PersistentTaskType {
+            &self.task_types[*id - 1]
+        }
+    }
+
+    fn sessions() -> (TestApi, TestApi) {
+        (TestApi::new(1..=3), TestApi::new((1..=3).rev()))
+    }
+
+    fn task_data(children: Vec<TaskId>, dependencies: Vec<RawVc>, output: RawVc) -> TaskData {
+        TaskData {
+            children,
+            dependencies,
+            cells: Vec::new(),
+            output,
+        }
+    }
+
+    fn persist(
+        graph: &FilePersistedGraph,
+        api: &TestApi,
+        task: u32,
+        children: &[u32],
+        dependencies: &[u32],
+        externally_active: bool,
+    ) -> Result<Vec<TaskId>> {
+        let data = task_data(
+            children.iter().map(|child| api.task(*child)).collect(),
+            dependencies
+                .iter()
+                .map(|dep| RawVc::TaskOutput(api.task(*dep)))
+                .collect(),
+            RawVc::TaskOutput(api.task(task)),
+        );
+        let result = graph
+            .persist(
+                api.task(task),
+                data,
+                PersistTaskState { externally_active },
+                api,
+            )?
+            .unwrap();
+        Ok(result.tasks_to_activate)
+    }
+
+    fn is_clean(graph: &FilePersistedGraph, api: &TestApi, task: u32) -> Result<bool> {
+        Ok(graph.read(api.task(task), api)?.unwrap().1.clean)
+    }
+
+    fn keeps_external_active(graph: &FilePersistedGraph, api: &TestApi, task: u32) -> Result<bool> {
+        Ok(graph
+            .read(api.task(task), api)?
+            .unwrap()
+            .1
+            .keeps_external_active)
+    }
+
+    fn open(path: &Path) -> Result<FilePersistedGraph> {
+        FilePersistedGraph::new(path)
+    }
+
+    #[test]
+    fn reads_and_looks_up_persisted_tasks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (first, second) = sessions();
+        {
+            let graph = open(dir.path())?;
+            assert!(graph.read(first.task(1), &first)?.is_none());
+            persist(&graph, &first, 1, &[2], &[3], false)?;
+            persist(&graph, &first, 2, &[], &[], false)?;
+        }
+
+        let graph = open(dir.path())?;
+        let (data, state) = graph.read(second.task(1), &second)?.unwrap();
+        assert_eq!(data.children, [second.task(2)]);
+        assert_eq!(data.dependencies, [RawVc::TaskOutput(second.task(3))]);
+        assert_eq!(data.output, RawVc::TaskOutput(second.task(1)));
+        assert!(state.clean);
+        assert!(!state.keeps_external_active);
+
+        assert!(graph.is_persisted(second.task(2), &second)?);
+        assert!(!graph.is_persisted(second.task(3), &second)?);
+        assert_eq!(
+            graph.lookup_one(&task_type(2), &second)?,
+            Some(second.task(2))
+        );
+        assert_eq!(graph.lookup_one(&task_type(3), &second)?, None);
+        assert!(graph.lookup(&PersistentTaskType::Native(function(), Vec::new()), &second)?);
+        Ok(())
+    }
+
+    #[test]
+    fn activates_and_deactivates_tasks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (first, second) = sessions();
+        {
+            let graph = open(dir.path())?;
+            let to_activate = persist(&graph, &first, 1, &[2], &[], true)?;
+            assert_eq!(to_activate, [first.task(2)]);
+            assert!(persist(&graph, &first, 2, &[], &[], false)?.is_empty());
+            let activated = graph.activate_when_needed(first.task(2), &first)?.unwrap();
+            assert!(activated.keeps_external_active);
+            assert!(!activated.dirty);
+            assert!(activated.more_tasks_to_activate.is_empty());
+        }
+
+        {
+            let graph = open(dir.path())?;
+            assert!(keeps_external_active(&graph, &second, 2)?);
+            assert!(graph
+                .activate_when_needed(second.task(2), &second)?
+                .is_none());
+
+            assert!(graph.unset_externally_active(second.task(1), &second)?);
+            let deactivated = graph
+                .deactivate_when_needed(second.task(1), &second)?
+                .unwrap();
+            assert_eq!(deactivated.more_tasks_to_deactivate, [second.task(2)]);
+            let deactivated = graph
+                .deactivate_when_needed(second.task(2), &second)?
+                .unwrap();
+            assert!(deactivated.more_tasks_to_deactivate.is_empty());
+        }
+
+        let graph = open(dir.path())?;
+        assert!(!keeps_external_active(&graph, &first, 2)?);
+        assert!(graph.activate_when_needed(first.task(2), &first)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn makes_dependent_tasks_dirty() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (first, second) = sessions();
+        {
+            let graph = open(dir.path())?;
+            persist(&graph, &first, 1, &[], &[2], true)?;
+            persist(&graph, &first, 2, &[], &[], false)?;
+            let dirty = graph.make_dependent_dirty(RawVc::TaskOutput(first.task(2)), &first)?;
+            assert_eq!(dirty, [first.task(1)]);
+            assert!(!is_clean(&graph, &first, 1)?);
+            assert!(is_clean(&graph, &first, 2)?);
+        }
+
+        {
+            let graph = open(dir.path())?;
+            assert!(!is_clean(&graph, &second, 1)?);
+            assert_eq!(graph.get_dirty_active_tasks(&second)?, [second.task(1)]);
+            graph.make_clean(second.task(1), &second)?;
+        }
+
+        let graph = open(dir.path())?;
+        assert!(is_clean(&graph, &first, 1)?);
+        assert!(graph.get_dirty_active_tasks(&first)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reopens_compacted_graph() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (first, second) = sessions();
+        {
+            let graph = open(dir.path())?;
+            persist(&graph, &first, 1, &[2], &[], false)?;
+            graph.compact()?;
+            assert_eq!(graph.log_size(), 0);
+            // Changes after compacting are appended to the log again
+            persist(&graph, &first, 2, &[], &[1], false)?;
+            assert!(graph.log_size() > 0);
+        }
+
+        {
+            let graph = open(dir.path())?;
+            assert!(graph.is_persisted(second.task(1), &second)?);
+            let (data, _) = graph.read(second.task(2), &second)?.unwrap();
+            assert_eq!(data.dependencies, [RawVc::TaskOutput(second.task(1))]);
+            graph.compact()?;
+        }
+
+        let graph = open(dir.path())?;
+        assert_eq!(graph.log_size(), 0);
+        let (data, _) = graph.read(first.task(1), &first)?.unwrap();
+        assert_eq!(data.children, [first.task(2)]);
+        let (data, _) = graph.read(first.task(2), &first)?.unwrap();
+        assert_eq!(data.dependencies, [RawVc::TaskOutput(first.task(1))]);
+        Ok(())
+    }
+}
@@ -57,7 +57,7 @@ use turbo_tasks::{
     primitives::{BoolVc, StringReadRef, StringVc},
     spawn_thread,
     trace::TraceRawVcs,
-    CompletionVc, Invalidator, ValueToString, ValueToStringVc,
+    CompletionVc, InvalidationReason, Invalidator, ValueToString, ValueToStringVc,
 };
 use turbo_tasks_hash::hash_xxh3_hash64;
 use util::{extract_disk_access, join_path, normalize_path, sys_to_unix, unix_to_sys};
@@ -188,11 +188,12 @@ impl DiskFileSystem {
     }
 
     pub fn invalidate(&self) {
-        for (_, invalidators) in take(&mut *self.invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
+        for (key, invalidators) in take(&mut *self.invalidator_map.lock().unwrap()).into_iter() {
+            invalidate_key(&key, invalidators);
         }
-        for (_, invalidators) in take(&mut *self.dir_invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
+        for (key, invalidators) in take(&mut *self.dir_invalidator_map.lock().unwrap()).into_iter()
+        {
+            invalidate_key(&key, invalidators);
         }
     }
 
@@ -220,11 +221,11 @@ impl DiskFileSystem {
 
         // We need to invalidate all reads that happened before watching
         // Best is to start_watching before starting to read
-        for (_, invalidators) in take(&mut *invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
+        for (key, invalidators) in take(&mut *invalidator_map.lock().unwrap()).into_iter() {
+            invalidate_key(&key, invalidators);
         }
-        for (_, invalidators) in take(&mut *dir_invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
+        for (key, invalidators) in take(&mut *dir_invalidator_map.lock().unwrap()).into_iter() {
+            invalidate_key(&key, invalidators);
         }
 
         watcher_guard.replace(watcher);
@@ -323,7 +324,7 @@ impl DiskFileSystem {
                     for path in paths {
                         let key = path_to_key(path);
                         if let Some(invalidators) = invalidator_map.remove(&key) {
-                            invalidators.into_iter().for_each(|i| i.invalidate());
+                            invalidate_key(&key, invalidators);
                         }
                     }
                 }
@@ -331,12 +332,12 @@ impl DiskFileSystem {
                     invalidator_map: &mut HashMap<String, HashSet<Invalidator>>,
                     paths: &mut HashSet<PathBuf>,
                 ) {
-                    for (_, invalidators) in invalidator_map.drain_filter(|key, _| {
+                    for (key, invalidators) in invalidator_map.drain_filter(|key, _| {
                         paths
                             .iter()
                             .any(|path_key| key.starts_with(&path_to_key(path_key)))
                     }) {
-                        invalidators.into_iter().for_each(|i| i.invalidate());
+                        invalidate_key(&key, invalidators);
                     }
                     paths.clear()
                 }
@@ -393,6 +394,14 @@ pub fn path_to_key(path: impl AsRef<Path>) -> String {
     path.as_ref().to_string_lossy().to_string()
 }
 
+/// Invalidates the tasks that read the file or directory at `key`.
+fn invalidate_key(key: &str, invalidators: HashSet<Invalidator>) {
+    let path: Arc<str> = Arc::from(key);
+    for invalidator in invalidators {
+        invalidator.invalidate_with_reason(InvalidationReason::FileChanged { path: path.clone() });
+    }
+}
+
 #[turbo_tasks::value_impl]
 impl DiskFileSystemVc {
     #[turbo_tasks::function]
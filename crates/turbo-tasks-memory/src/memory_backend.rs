@@ -130,6 +130,12 @@ impl MemoryBackend {
         func(self.memory_tasks.get(*id).unwrap())
     }
 
+    /// Like [MemoryBackend::with_task], but returns `None` when the task
+    /// doesn't exist.
+    pub(crate) fn try_with_task<T>(&self, id: TaskId, func: impl FnOnce(&Task) -> T) -> Option<T> {
+        self.memory_tasks.get(*id).map(func)
+    }
+
     #[inline(always)]
     pub fn with_scope<T>(&self, id: TaskScopeId, func: impl FnOnce(&TaskScope) -> T) -> T {
         func(self.memory_task_scopes.get(*id).unwrap())
@@ -3,6 +3,7 @@ use std::{
     collections::{BinaryHeap, HashMap},
     fmt::Debug,
     future::Future,
+    iter::once,
     mem::{replace, take},
     pin::Pin,
     sync::{
@@ -1148,7 +1149,7 @@ impl<P: PersistedGraph> Backend for MemoryBackendWithPersistedGraph<P> {
         task: TaskId,
         duration: Duration,
         _instant: Instant,
-        _stateful: bool,
+        stateful: bool,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackendWithPersistedGraph<P>>,
     ) -> bool {
         #[cfg(feature = "log_running_tasks")]
@@ -1180,9 +1181,36 @@ impl<P: PersistedGraph> Backend for MemoryBackendWithPersistedGraph<P> {
             .as_ref()
             .map(|p| p.clean != Some(true))
             .unwrap_or_default();
+        let cells = if stateful && has_changes {
+            mem_state.cells.keys().copied().collect()
+        } else {
+            Vec::new()
+        };
         drop(state);
 
         if let TaskType::Persistent(_) = task_info.task_type {
+            if stateful {
+                // Stateful tasks hold state which can't be restored from the
+                // persisted graph (e. g. file watchers), so they are never
+                // persisted and execute again in every session. Tasks restored
+                // from the persisted graph might depend on a previous execution.
+                if has_changes {
+                    let vcs = once(RawVc::TaskOutput(task))
+                        .chain(cells.into_iter().map(|index| RawVc::TaskCell(task, index)));
+                    for vc in vcs {
+                        for task in self.pg_make_dependent_dirty(vc, turbo_tasks) {
+                            let (mut state, _) = self.state_mut(task, turbo_tasks);
+                            if !state.scheduled {
+                                state.scheduled = true;
+                                #[cfg(feature = "log_scheduled_tasks")]
+                                println!("schedule({task}) in task_execution_completed");
+                                turbo_tasks.schedule(task);
+                            }
+                        }
+                    }
+                }
+                return false;
+            }
             if has_changes && (is_persisted || !self.only_known_to_memory_tasks.contains(&task)) {
                 for task in self.pg_make_dependent_dirty(RawVc::TaskOutput(task), turbo_tasks) {
                     let (mut state, _) = self.state_mut(task, turbo_tasks);
@@ -0,0 +1,113 @@
+use turbo_tasks::{util::FormatDuration, Invalidation, InvalidationReason, TaskId, TurboTasks};
+
+use super::*;
+use crate::MemoryBackend;
+
+pub fn wrap_html(html: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset=\"utf-8\">
+  <title>turbo-tasks invalidations</title>
+  <style>
+    body {{ margin: 0; font-family: monospace; }}
+    p {{ padding: 0 10px; }}
+    table {{ border-spacing: 0; }}
+    td, th {{ padding: 10px; text-align: left; vertical-align: baseline; }}
+    th {{ background: gray; color: #fff; font-weight: normal; }}
+  </style>
+</head>
+<body>
+  {html}
+</body>
+</html>"#
+    )
+}
+
+fn task_description(tt: &TurboTasks<MemoryBackend>, task: TaskId) -> String {
+    // The task might have been removed since it has been invalidated.
+    tt.backend()
+        .try_with_task(task, |t| t.get_description())
+        .unwrap_or_else(|| format!("Task {task} (removed)"))
+}
+
+fn task_link(tt: &TurboTasks<MemoryBackend>, task: TaskId) -> String {
+    format!(
+        "<a href=\"invalidations?task={task}\">{}</a>",
+        escape_html(&task_description(tt, task))
+    )
+}
+
+fn reason_html(tt: &TurboTasks<MemoryBackend>, reason: &InvalidationReason) -> String {
+    match reason {
+        InvalidationReason::DependencyChanged { task } => {
+            format!("dependency in {} changed", task_link(tt, *task))
+        }
+        _ => escape_html(&reason.to_string()),
+    }
+}
+
+fn disabled_disclaimer(tt: &TurboTasks<MemoryBackend>) -> &'static str {
+    if tt.is_invalidation_tracking_enabled() {
+        ""
+    } else {
+        "<p>Invalidation tracking is disabled. Run with --track-invalidations to enable it.</p>"
+    }
+}
+
+/// Lists the most recently invalidated tasks with the reason of their last
+/// invalidation.
+pub fn create_list(tt: &TurboTasks<MemoryBackend>, limit: usize) -> String {
+    let mut out = disabled_disclaimer(tt).to_string();
+    out += r#"<table><thead><tr><th>time</th><th>task</th><th>reason</th></tr></thead><tbody>"#;
+    for (task, Invalidation { time, reason, .. }) in tt.latest_invalidations(limit) {
+        write!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            FormatDuration(time),
+            task_link(tt, task),
+            reason_html(tt, &reason)
+        )
+        .unwrap();
+    }
+    out += r#"</tbody></table>"#;
+    out
+}
+
+/// Explains the last invalidation of `task` by following it back to the
+/// root cause, and lists the earlier invalidations of `task`.
+pub fn create_chain(tt: &TurboTasks<MemoryBackend>, task: TaskId) -> String {
+    let mut out = disabled_disclaimer(tt).to_string();
+    write!(
+        out,
+        "<p>{} has been invalidated because of:</p>",
+        escape_html(&task_description(tt, task))
+    )
+    .unwrap();
+    out += r#"<table><thead><tr><th>time</th><th>task</th><th>reason</th></tr></thead><tbody>"#;
+    for (task, Invalidation { time, reason, .. }) in tt.invalidation_chain(task) {
+        write!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            FormatDuration(time),
+            task_link(tt, task),
+            reason_html(tt, &reason)
+        )
+        .unwrap();
+    }
+    out += r#"</tbody></table>"#;
+    out += r#"<p>All recorded invalidations of this task:</p>"#;
+    out += r#"<table><thead><tr><th>time</th><th>reason</th></tr></thead><tbody>"#;
+    for Invalidation { time, reason, .. } in tt.invalidations(task).into_iter().rev() {
+        write!(
+            out,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            FormatDuration(time),
+            reason_html(tt, &reason)
+        )
+        .unwrap();
+    }
+    out += r#"</tbody></table>"#;
+    out
+}
@@ -0,0 +1,175 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::Serialize;
+use turbo_tasks::StatsType;
+
+use crate::stats::{ExportedTaskStats, GroupTree, ReferenceType};
+
+/// Version of the JSON schema. It's increased on changes which are not
+/// backwards compatible.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Stats of all functions, for consumption by other tools. Durations are in
+/// microseconds.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsJson {
+    pub version: u32,
+    /// Without full stats, `executions`, `totalDurationUs` and `avgDurationUs`
+    /// are `null`.
+    pub full_stats: bool,
+    /// Sorted by name.
+    pub functions: Vec<FunctionStatsJson>,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionStatsJson {
+    pub name: String,
+    /// Number of tasks of the function.
+    pub count: usize,
+    pub active_count: usize,
+    pub unloaded_count: usize,
+    pub executions: Option<u64>,
+    pub total_duration_us: Option<u64>,
+    pub avg_duration_us: Option<u64>,
+    /// Sum of the last execution durations of all tasks.
+    pub total_current_duration_us: u64,
+    /// Sum of the last execution durations of the tasks which have been
+    /// executed more than once.
+    pub total_update_duration_us: u64,
+    pub max_duration_us: u64,
+    /// Number of tasks which are the root of a scope.
+    pub roots: usize,
+    pub scopes: usize,
+    /// Number of tasks read by the tasks of the function, summed over them.
+    pub dependencies: usize,
+    /// Number of tasks called by the tasks of the function, summed over them.
+    pub children: usize,
+}
+
+fn micros(duration: Duration) -> u64 {
+    duration.as_micros() as u64
+}
+
+fn add_optional<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+fn reference_count(stats: &ExportedTaskStats, ty: ReferenceType) -> usize {
+    stats
+        .references
+        .iter()
+        .filter(|((ref_ty, _), _)| *ref_ty == ty)
+        .map(|(_, ref_stats)| ref_stats.count)
+        .sum()
+}
+
+fn add_task_type(
+    functions: &mut BTreeMap<String, FunctionStatsJson>,
+    name: String,
+    s: &ExportedTaskStats,
+) {
+    // Task types with the same name, e. g. all root tasks, are merged.
+    let f = functions.entry(name).or_default();
+    f.count += s.count;
+    f.active_count += s.active_count;
+    f.unloaded_count += s.unloaded_count;
+    f.executions = add_optional(f.executions, s.executions.map(u64::from));
+    f.total_duration_us = add_optional(f.total_duration_us, s.total_duration.map(micros));
+    f.total_current_duration_us += micros(s.total_current_duration);
+    f.total_update_duration_us += micros(s.total_update_duration);
+    f.max_duration_us = f.max_duration_us.max(micros(s.max_duration));
+    f.roots += s.roots;
+    f.scopes += s.scopes;
+    f.dependencies += reference_count(s, ReferenceType::Dependency);
+    f.children += reference_count(s, ReferenceType::Child);
+}
+
+fn add_group(functions: &mut BTreeMap<String, FunctionStatsJson>, node: &GroupTree) {
+    for (ty, stats) in node.primary.iter().chain(node.task_types.iter()) {
+        add_task_type(functions, ty.to_string(), stats);
+    }
+    for child in node.children.iter() {
+        add_group(functions, child);
+    }
+}
+
+/// Flattens the stats `root` into stats per function.
+pub fn create_json(root: &GroupTree, stats_type: StatsType) -> StatsJson {
+    let mut functions = BTreeMap::new();
+    add_group(&mut functions, root);
+    StatsJson {
+        version: SCHEMA_VERSION,
+        full_stats: stats_type.is_full(),
+        functions: functions
+            .into_iter()
+            .map(|(name, mut f)| {
+                f.avg_duration_us = f
+                    .total_duration_us
+                    .zip(f.executions)
+                    .filter(|&(_, executions)| executions > 0)
+                    .map(|(total, executions)| total / executions);
+                f.name = name;
+                f
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use turbo_tasks::{StatsType, TaskId};
+
+    use super::{create_json, FunctionStatsJson};
+    use crate::stats::{
+        ExportedTaskStats, GroupTree, ReferenceStats, ReferenceType, StatsTaskType,
+    };
+
+    #[test]
+    fn merges_task_types_by_name() {
+        let root_stats = |executions, millis| ExportedTaskStats {
+            count: 1,
+            executions: Some(executions),
+            total_duration: Some(Duration::from_millis(millis)),
+            max_duration: Duration::from_millis(millis),
+            references: [(
+                (ReferenceType::Child, StatsTaskType::Once(TaskId::from(3))),
+                ReferenceStats { count: 1 },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let tree = GroupTree {
+            primary: Some((StatsTaskType::Root(TaskId::from(1)), root_stats(1, 10))),
+            children: vec![GroupTree {
+                primary: None,
+                children: Vec::new(),
+                task_types: vec![(StatsTaskType::Root(TaskId::from(2)), root_stats(3, 20))],
+            }],
+            task_types: Vec::new(),
+        };
+
+        let json = create_json(&tree, StatsType::Full);
+        assert!(json.full_stats);
+        assert_eq!(
+            json.functions,
+            vec![FunctionStatsJson {
+                name: "root".to_string(),
+                count: 2,
+                executions: Some(4),
+                total_duration_us: Some(30_000),
+                avg_duration_us: Some(7_500),
+                max_duration_us: 20_000,
+                children: 2,
+                ..Default::default()
+            }]
+        );
+    }
+}
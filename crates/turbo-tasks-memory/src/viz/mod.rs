@@ -1,4 +1,6 @@
 pub mod graph;
+pub mod invalidations;
+pub mod json;
 pub mod table;
 
 use std::{
@@ -14,7 +14,7 @@ use turbo_tasks::{
         ActivateResult, DeactivateResult, PersistResult, PersistTaskState, PersistedGraph,
         PersistedGraphApi, ReadTaskState, TaskCell, TaskData,
     },
-    util::{InfiniteVec, SharedError},
+    util::{NoMoveVec, SharedError},
     with_task_id_mapping, FunctionId, IdMapping, TaskId,
 };
 
@@ -48,14 +48,19 @@ fn task_type_to_bytes(ty: &PersistentTaskType) -> Result<Vec<u8>, bincode::Error
     Ok(result)
 }
 
-#[derive(Default)]
 pub struct CountsByFunction(pub NoMoveVec<AtomicUsize>);
 
+impl Default for CountsByFunction {
+    fn default() -> Self {
+        Self(NoMoveVec::new_init_default())
+    }
+}
+
 impl Debug for CountsByFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut m = f.debug_map();
-        for (fn_id, count) in self.0.iter() {
-            let i = count.load(Ordering::Relaxed);
+        for fn_id in 0..self.0.capacity() {
+            let i = self.0.get_init_default(fn_id).load(Ordering::Relaxed);
             if i > 0 {
                 m.entry(&FunctionId::from(fn_id), &i);
             }
@@ -94,7 +99,7 @@ pub struct RocksDbPersistedGraph {
     cache_once: turbo_tasks::util::SafeOnceConcurrentlyMap<Vec<u8>, Result<usize, SharedError>>,
     stats: Stats,
     /// AC_UNKNOWN | AC_ACTIVE | AC_INACTIVE
-    active_cache: InfiniteVec<AtomicU8>,
+    active_cache: NoMoveVec<AtomicU8>,
 }
 
 impl RocksDbPersistedGraph {
@@ -111,7 +116,7 @@ impl RocksDbPersistedGraph {
             #[cfg(not(feature = "unsafe_once_map"))]
             cache_once: turbo_tasks::util::SafeOnceConcurrentlyMap::new(),
             stats: Stats::default(),
-            active_cache: InfiniteVec::new(),
+            active_cache: NoMoveVec::new_init_default(),
         })
     }
 
@@ -171,7 +176,7 @@ impl RocksDbPersistedGraph {
     }
 
     fn get_active(&self, db_task: usize) -> Result<bool> {
-        let ac = self.active_cache.get(db_task);
+        let ac = self.active_cache.get_init_default(db_task);
         let ac_value = ac.load(Ordering::Acquire);
         if ac_value != AC_UNKNOWN {
             return Ok(ac_value == AC_ACTIVE);
@@ -319,7 +324,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
             let mut tasks_to_deactivate = Vec::new();
             let mut tasks_to_activate = Vec::new();
             let b = &mut db.batch();
-            let ac = self.active_cache.get(db_task);
+            let ac = self.active_cache.get_init_default(db_task);
             let old_active = {
                 match ac.load(Ordering::Acquire) {
                     AC_ACTIVE => true,
@@ -453,7 +458,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
             let db = &self.database;
             let b = &mut db.batch();
             db.pending_active_update.remove(b, &(), &db_task)?;
-            let ac = self.active_cache.get(db_task);
+            let ac = self.active_cache.get_init_default(db_task);
             if ac.load(Ordering::Acquire) != AC_ACTIVE {
                 if let Some(TaskState {
                     internal,
@@ -513,7 +518,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
             let db = &self.database;
             let b = &mut db.batch();
             db.pending_active_update.remove(b, &(), &db_task)?;
-            let ac = self.active_cache.get(db_task);
+            let ac = self.active_cache.get_init_default(db_task);
             if ac.load(Ordering::Acquire) != AC_INACTIVE {
                 if let Some(TaskState {
                     active,
@@ -563,7 +568,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
                 .merge(b, &db_task, &TaskStateChange::SetExternallyActive)?;
             db.externally_active_tasks.insert(b, &(), &db_task)?;
             b.write()?;
-            let ac = self.active_cache.get(db_task);
+            let ac = self.active_cache.get_init_default(db_task);
             let ac_value = ac.load(Ordering::Acquire);
             if ac_value == AC_ACTIVE {
                 return Ok(false);
@@ -597,7 +602,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
                 .merge(b, &db_task, &TaskStateChange::UnsetExternallyActive)?;
             db.externally_active_tasks.insert(b, &(), &db_task)?;
             b.write()?;
-            let ac = self.active_cache.get(db_task);
+            let ac = self.active_cache.get_init_default(db_task);
             let ac_value = ac.load(Ordering::Acquire);
             if ac_value == AC_INACTIVE {
                 return Ok(false);
@@ -704,7 +709,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
                     internal, active, ..
                 }) = db.state.get(&db_task)?
                 {
-                    self.active_cache.get(db_task).store(
+                    self.active_cache.get_init_default(db_task).store(
                         if active { AC_ACTIVE } else { AC_INACTIVE },
                         Ordering::Release,
                     );
@@ -735,7 +740,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
                     internal, active, ..
                 }) = db.state.get(&db_task)?
                 {
-                    self.active_cache.get(db_task).store(
+                    self.active_cache.get_init_default(db_task).store(
                         if active { AC_ACTIVE } else { AC_INACTIVE },
                         Ordering::Release,
                     );
@@ -772,7 +777,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
                     ..
                 }) = db.state.get(&db_task)?
                 {
-                    self.active_cache.get(db_task).store(
+                    self.active_cache.get_init_default(db_task).store(
                         if active { AC_ACTIVE } else { AC_INACTIVE },
                         Ordering::Release,
                     );
@@ -801,6 +806,7 @@ impl PersistedGraph for RocksDbPersistedGraph {
     }
 
     fn stop(&self, _api: &dyn PersistedGraphApi) -> Result<()> {
+        #[cfg(feature = "log_db")]
         println!("{:#?}", self.stats);
         // self.with_task_id_mapping(api, || {
         //     self.print_db(api)?;
@@ -21,7 +21,7 @@ use turbo_tasks::{
     primitives::RawVcSetVc,
     registry,
     test_helpers::{current_task_for_testing, with_turbo_tasks_for_testing},
-    CellId, RawVc, TaskId, TraitTypeId, TurboTasksApi, TurboTasksCallApi,
+    CellId, InvalidationReason, RawVc, TaskId, TraitTypeId, TurboTasksApi, TurboTasksCallApi,
 };
 
 enum Task {
@@ -105,6 +105,10 @@ impl TurboTasksApi for VcStorage {
         unreachable!()
     }
 
+    fn invalidate_with_reason(&self, _task: TaskId, _reason: InvalidationReason) {
+        unreachable!()
+    }
+
     fn notify_scheduled_tasks(&self) {
         // ignore
     }
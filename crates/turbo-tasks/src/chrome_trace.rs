@@ -0,0 +1,306 @@
+//! Recording of task executions as a timeline in the [Chrome Trace Event
+//! format], which can be inspected in `chrome://tracing` or
+//! [Perfetto](https://ui.perfetto.dev).
+//!
+//! Every poll of a task execution is a slice on the thread that polled it. The
+//! whole execution of a task, from the first to the last poll, is an async
+//! slice, as it can move between threads. Reads of outputs and cells are
+//! instant events on the reading thread, named `await` when the value isn't
+//! available yet and the reading task has to wait.
+//!
+//! [Chrome Trace Event format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+
+use std::{
+    cell::Cell,
+    fs::File,
+    future::Future,
+    io::{self, BufWriter, Write},
+    path::Path,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use anyhow::{Context as _, Result};
+use parking_lot::Mutex;
+use pin_project_lite::pin_project;
+use serde_json::{json, Value};
+
+use crate::{CellId, TaskId};
+
+/// All events are reported for a single process.
+const PID: u32 = 1;
+
+static NEXT_THREAD_ID: AtomicUsize = AtomicUsize::new(1);
+static NEXT_RECORDER_ID: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// The id of the current thread in traces and the id of the last recorder
+    /// the name of the thread has been written to. `0` when unassigned.
+    static THREAD: Cell<(usize, usize)> = Cell::new((0, 0));
+}
+
+struct Output {
+    writer: BufWriter<File>,
+    empty: bool,
+    /// The first write error, which is reported by the next flush.
+    error: Option<io::Error>,
+}
+
+/// Writes events of task executions to a Chrome Trace Event file.
+pub(crate) struct ChromeTraceRecorder {
+    id: usize,
+    start: Instant,
+    /// `None` once the trace has been finished.
+    output: Mutex<Option<Output>>,
+}
+
+impl ChromeTraceRecorder {
+    /// Creates the trace file at `path`.
+    pub(crate) fn new(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("unable to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(b"[")?;
+        Ok(Self {
+            id: NEXT_RECORDER_ID.fetch_add(1, Ordering::Relaxed),
+            start: Instant::now(),
+            output: Mutex::new(Some(Output {
+                writer,
+                empty: true,
+                error: None,
+            })),
+        })
+    }
+
+    /// Microseconds since the start of the recording.
+    fn timestamp(&self, instant: Instant) -> f64 {
+        instant.saturating_duration_since(self.start).as_nanos() as f64 / 1000.0
+    }
+
+    /// The id of the current thread. Writes the name of the thread when it's
+    /// seen for the first time.
+    fn thread_id(&self) -> usize {
+        let (mut tid, named_for) = THREAD.with(|thread| thread.get());
+        if tid == 0 {
+            tid = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+        }
+        if named_for != self.id {
+            THREAD.with(|thread| thread.set((tid, self.id)));
+            let name = std::thread::current()
+                .name()
+                .map_or_else(|| format!("thread {tid}"), |name| name.to_string());
+            self.write(&json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": PID,
+                "tid": tid,
+                "args": { "name": name },
+            }));
+        }
+        tid
+    }
+
+    fn write(&self, event: &Value) {
+        let event = match serde_json::to_vec(event) {
+            Ok(event) => event,
+            Err(_) => return,
+        };
+        let mut output = self.output.lock();
+        let Some(output) = &mut *output else {
+            return;
+        };
+        let separator: &[u8] = if output.empty { b"\n" } else { b",\n" };
+        output.empty = false;
+        if let Err(err) = output
+            .writer
+            .write_all(separator)
+            .and_then(|_| output.writer.write_all(&event))
+        {
+            output.error.get_or_insert(err);
+        }
+    }
+
+    /// Records a poll of the execution of `task`.
+    pub(crate) fn poll(&self, task: TaskId, name: &str, start: Instant, end: Instant) {
+        let tid = self.thread_id();
+        self.write(&json!({
+            "name": name,
+            "cat": "poll",
+            "ph": "X",
+            "ts": self.timestamp(start),
+            "dur": self.timestamp(end) - self.timestamp(start),
+            "pid": PID,
+            "tid": tid,
+            "args": { "task": *task },
+        }));
+    }
+
+    fn execution_event(&self, task: TaskId, name: &str, phase: &str, instant: Instant) {
+        let tid = self.thread_id();
+        self.write(&json!({
+            "name": name,
+            "cat": "execution",
+            "ph": phase,
+            "id": *task,
+            "ts": self.timestamp(instant),
+            "pid": PID,
+            "tid": tid,
+        }));
+    }
+
+    /// Records a read of the output (`cell` is `None`) or a cell of `task` by
+    /// the `reader` task. `wait` is set when the value isn't available yet.
+    pub(crate) fn read(
+        &self,
+        reader: Option<TaskId>,
+        task: TaskId,
+        cell: Option<CellId>,
+        wait: bool,
+    ) {
+        let tid = self.thread_id();
+        let name = match (wait, cell.is_some()) {
+            (false, false) => "read output",
+            (false, true) => "read cell",
+            (true, false) => "await output",
+            (true, true) => "await cell",
+        };
+        self.write(&json!({
+            "name": name,
+            "cat": "read",
+            "ph": "i",
+            "s": "t",
+            "ts": self.timestamp(Instant::now()),
+            "pid": PID,
+            "tid": tid,
+            "args": {
+                "reader": reader.map(|reader| *reader),
+                "task": *task,
+                "cell": cell.map(|cell| cell.to_string()),
+            },
+        }));
+    }
+
+    /// Writes buffered events to the file.
+    pub(crate) fn flush(&self) -> Result<()> {
+        let mut output = self.output.lock();
+        if let Some(output) = &mut *output {
+            if let Some(err) = output.error.take() {
+                return Err(err.into());
+            }
+            output.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Completes the trace file. Events recorded afterwards are dropped.
+    pub(crate) fn finish(&self) -> Result<()> {
+        let Some(mut output) = self.output.lock().take() else {
+            return Ok(());
+        };
+        if let Some(err) = output.error.take() {
+            return Err(err.into());
+        }
+        output.writer.write_all(b"\n]\n")?;
+        output.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for ChromeTraceRecorder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+pin_project! {
+    /// Records the polls of a task execution, when a recorder is given.
+    pub(crate) struct TracedFuture<F> {
+        recording: Option<(Arc<ChromeTraceRecorder>, TaskId, String)>,
+        started: bool,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F: Future> TracedFuture<F> {
+    /// `recording` is the recorder together with the task and its
+    /// description.
+    pub(crate) fn new(
+        recording: Option<(Arc<ChromeTraceRecorder>, TaskId, String)>,
+        future: F,
+    ) -> Self {
+        Self {
+            recording,
+            started: false,
+            future,
+        }
+    }
+}
+
+impl<F: Future> Future for TracedFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let Some((recorder, task, name)) = this.recording else {
+            return this.future.poll(cx);
+        };
+        let start = Instant::now();
+        if !*this.started {
+            *this.started = true;
+            recorder.execution_event(*task, name, "b", start);
+        }
+        let result = this.future.poll(cx);
+        let end = Instant::now();
+        recorder.poll(*task, name, start, end);
+        if result.is_ready() {
+            recorder.execution_event(*task, name, "e", end);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use anyhow::Result;
+
+    use super::ChromeTraceRecorder;
+    use crate::TaskId;
+
+    #[test]
+    fn writes_valid_trace() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("turbo-tasks-trace-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("trace.json");
+
+        let recorder = ChromeTraceRecorder::new(&path)?;
+        let task = TaskId::from(1);
+        let start = Instant::now();
+        recorder.execution_event(task, "\"quoted\" task", "b", start);
+        recorder.poll(task, "\"quoted\" task", start, Instant::now());
+        recorder.read(Some(task), TaskId::from(2), None, true);
+        recorder.finish()?;
+        // Events after finishing are dropped.
+        recorder.read(None, task, None, false);
+
+        let events: Vec<serde_json::Value> = serde_json::from_slice(&std::fs::read(&path)?)?;
+        let phases = events
+            .iter()
+            .map(|event| event["ph"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(phases, ["M", "b", "X", "i"]);
+        assert_eq!(events[2]["name"], "\"quoted\" task");
+        assert_eq!(events[3]["name"], "await output");
+        assert_eq!(events[3]["args"]["task"], 2);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
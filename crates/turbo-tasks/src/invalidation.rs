@@ -0,0 +1,257 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    fmt::{self, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::TaskId;
+
+/// Number of invalidations that are kept per task. Older ones are dropped.
+const MAX_INVALIDATIONS_PER_TASK: usize = 16;
+
+/// The cause of an invalidation of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum InvalidationReason {
+    /// A file or directory read by the task has changed on disk.
+    FileChanged { path: Arc<str> },
+    /// A [crate::State] read by the task has been updated or dropped.
+    StateChanged { ty: &'static str },
+    /// The output or a cell of `task`, which was read by the invalidated task,
+    /// has changed.
+    DependencyChanged { task: TaskId },
+    /// A reason given by the code that invalidated the task.
+    Custom(Cow<'static, str>),
+    /// The invalidation has been triggered without a reason.
+    Unknown,
+}
+
+impl Display for InvalidationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidationReason::FileChanged { path } => write!(f, "file {path} changed"),
+            InvalidationReason::StateChanged { ty } => write!(f, "state of {ty} changed"),
+            InvalidationReason::DependencyChanged { task } => {
+                write!(f, "dependency in task {task} changed")
+            }
+            InvalidationReason::Custom(reason) => write!(f, "{reason}"),
+            InvalidationReason::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A recorded invalidation of a task.
+#[derive(Clone, Debug, Serialize)]
+pub struct Invalidation {
+    /// Increases with every recorded event. Tasks invalidated by the same event
+    /// share the number.
+    pub seq: u64,
+    /// Time since the start of the program.
+    pub time: Duration,
+    pub reason: InvalidationReason,
+}
+
+#[derive(Default)]
+struct InvalidationLogInner {
+    next_seq: u64,
+    tasks: HashMap<TaskId, VecDeque<Invalidation>>,
+}
+
+/// Records the invalidations of tasks while enabled, to answer why a task has
+/// been executed again.
+#[derive(Default)]
+pub(crate) struct InvalidationLog {
+    enabled: AtomicBool,
+    inner: Mutex<InvalidationLogInner>,
+}
+
+impl InvalidationLog {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Enables or disables recording. Disabling drops all recorded
+    /// invalidations.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+        if !enabled {
+            *self.inner.lock() = Default::default();
+        }
+    }
+
+    /// Records that `tasks` have been invalidated by a single event.
+    pub fn record(
+        &self,
+        tasks: impl IntoIterator<Item = TaskId>,
+        time: Duration,
+        reason: InvalidationReason,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        for task in tasks {
+            let invalidations = inner.tasks.entry(task).or_default();
+            if invalidations.len() == MAX_INVALIDATIONS_PER_TASK {
+                invalidations.pop_front();
+            }
+            invalidations.push_back(Invalidation {
+                seq,
+                time,
+                reason: reason.clone(),
+            });
+        }
+    }
+
+    /// The recorded invalidations of `task`, oldest first.
+    pub fn get(&self, task: TaskId) -> Vec<Invalidation> {
+        self.inner
+            .lock()
+            .tasks
+            .get(&task)
+            .map(|invalidations| invalidations.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The last invalidation of each task, most recent first and at most
+    /// `limit` of them.
+    pub fn latest(&self, limit: usize) -> Vec<(TaskId, Invalidation)> {
+        let inner = self.inner.lock();
+        let mut latest = inner
+            .tasks
+            .iter()
+            .filter_map(|(task, invalidations)| Some((*task, invalidations.back()?.clone())))
+            .collect::<Vec<_>>();
+        latest.sort_by(|(_, a), (_, b)| b.seq.cmp(&a.seq));
+        latest.truncate(limit);
+        latest
+    }
+
+    /// Follows the latest invalidation of `task` back through the tasks whose
+    /// changes caused it. The first entry is the invalidation of `task`, each
+    /// following entry is the invalidation that made the previous cause
+    /// execute again. The chain ends at a cause which isn't a dependency
+    /// change, or at a task which has no earlier recorded invalidation.
+    pub fn chain(&self, task: TaskId) -> Vec<(TaskId, Invalidation)> {
+        let inner = self.inner.lock();
+        let mut chain = Vec::new();
+        let mut current = task;
+        let mut before = u64::MAX;
+        // `before` decreases with every step, so this terminates even with
+        // cyclic reasons.
+        while let Some(invalidation) = inner.tasks.get(&current).and_then(|invalidations| {
+            invalidations
+                .iter()
+                .rev()
+                .find(|invalidation| invalidation.seq < before)
+        }) {
+            before = invalidation.seq;
+            chain.push((current, invalidation.clone()));
+            match invalidation.reason {
+                InvalidationReason::DependencyChanged { task } => current = task,
+                _ => break,
+            }
+        }
+        chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{InvalidationLog, InvalidationReason};
+    use crate::TaskId;
+
+    fn id(id: usize) -> TaskId {
+        TaskId::from(id)
+    }
+
+    fn chain_of(log: &InvalidationLog, task: usize) -> Vec<(usize, InvalidationReason)> {
+        log.chain(id(task))
+            .into_iter()
+            .map(|(task, invalidation)| (*task, invalidation.reason))
+            .collect()
+    }
+
+    #[test]
+    fn records_only_when_enabled() {
+        let log = InvalidationLog::default();
+        log.record([id(1)], Duration::ZERO, InvalidationReason::Unknown);
+        assert!(log.get(id(1)).is_empty());
+
+        log.set_enabled(true);
+        log.record([id(1)], Duration::ZERO, InvalidationReason::Unknown);
+        assert_eq!(log.get(id(1)).len(), 1);
+
+        log.set_enabled(false);
+        assert!(log.get(id(1)).is_empty());
+    }
+
+    #[test]
+    fn follows_chain_to_root_cause() {
+        let log = InvalidationLog::default();
+        log.set_enabled(true);
+        let file = InvalidationReason::FileChanged {
+            path: Arc::from("/src/index.js"),
+        };
+        log.record([id(1)], Duration::ZERO, file.clone());
+        log.record(
+            [id(2), id(3)],
+            Duration::ZERO,
+            InvalidationReason::DependencyChanged { task: id(1) },
+        );
+        log.record(
+            [id(4)],
+            Duration::ZERO,
+            InvalidationReason::DependencyChanged { task: id(3) },
+        );
+        // A later invalidation of a cause must not be attributed to earlier
+        // invalidations.
+        log.record(
+            [id(1)],
+            Duration::ZERO,
+            InvalidationReason::StateChanged { ty: "u32" },
+        );
+
+        assert_eq!(
+            chain_of(&log, 4),
+            vec![
+                (4, InvalidationReason::DependencyChanged { task: id(3) }),
+                (3, InvalidationReason::DependencyChanged { task: id(1) }),
+                (1, file),
+            ]
+        );
+        assert_eq!(
+            chain_of(&log, 1),
+            vec![(1, InvalidationReason::StateChanged { ty: "u32" })]
+        );
+        assert!(chain_of(&log, 5).is_empty());
+    }
+
+    #[test]
+    fn stops_at_cycles() {
+        let log = InvalidationLog::default();
+        log.set_enabled(true);
+        log.record(
+            [id(1)],
+            Duration::ZERO,
+            InvalidationReason::DependencyChanged { task: id(2) },
+        );
+        log.record(
+            [id(2)],
+            Duration::ZERO,
+            InvalidationReason::DependencyChanged { task: id(1) },
+        );
+        assert_eq!(chain_of(&log, 2).len(), 2);
+    }
+}
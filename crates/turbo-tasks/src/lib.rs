@@ -35,6 +35,7 @@
 #![feature(never_type)]
 
 pub mod backend;
+mod chrome_trace;
 mod collectibles;
 mod completion;
 pub mod debug;
@@ -43,6 +44,7 @@ pub mod event;
 pub mod graph;
 mod id;
 mod id_factory;
+mod invalidation;
 mod join_iter_ext;
 mod magic_any;
 mod manager;
@@ -73,6 +75,7 @@ pub use id::{
     with_task_id_mapping, without_task_id_mapping, FunctionId, IdMapping, TaskId, TraitTypeId,
     ValueTypeId,
 };
+pub use invalidation::{Invalidation, InvalidationReason};
 pub use join_iter_ext::{JoinIterExt, TryJoinIterExt};
 pub use manager::{
     dynamic_call, emit, get_invalidator, mark_stateful, run_once, spawn_blocking, spawn_thread,
@@ -6,6 +6,7 @@ use std::{
     hash::Hash,
     mem::take,
     panic::AssertUnwindSafe,
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -24,9 +25,11 @@ use tokio::{runtime::Handle, select, task_local};
 
 use crate::{
     backend::{Backend, CellContent, PersistentTaskType, TransientTaskType},
+    chrome_trace::{ChromeTraceRecorder, TracedFuture},
     event::{Event, EventListener},
     id::{BackendJobId, FunctionId, TraitTypeId},
     id_factory::IdFactory,
+    invalidation::{Invalidation, InvalidationLog, InvalidationReason},
     primitives::RawVcSetVc,
     raw_vc::{CellId, RawVc},
     registry,
@@ -59,6 +62,7 @@ pub trait TurboTasksCallApi: Sync + Send {
 
 pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     fn invalidate(&self, task: TaskId);
+    fn invalidate_with_reason(&self, task: TaskId, reason: InvalidationReason);
 
     /// Eagerly notifies all tasks that were scheduled for notifications via
     /// `schedule_notify_tasks_set()`
@@ -252,6 +256,11 @@ pub struct TurboTasks<B: Backend + 'static> {
     // NOTE(alexkirsz) We use an atomic bool instead of a lock around `StatsType` to avoid the
     // locking overhead.
     enable_full_stats: AtomicBool,
+    invalidations: InvalidationLog,
+    // Checked before locking `chrome_trace`, to avoid the locking overhead when
+    // no trace is recorded.
+    enable_chrome_trace: AtomicBool,
+    chrome_trace: Mutex<Option<Arc<ChromeTraceRecorder>>>,
     program_start: Instant,
 }
 
@@ -303,6 +312,9 @@ impl<B: Backend + 'static> TurboTasks<B> {
             event_foreground: Event::new(|| "TurboTasks::event_foreground".to_string()),
             event_background: Event::new(|| "TurboTasks::event_background".to_string()),
             enable_full_stats: AtomicBool::new(false),
+            invalidations: InvalidationLog::default(),
+            enable_chrome_trace: AtomicBool::new(false),
+            chrome_trace: Mutex::new(None),
             program_start: Instant::now(),
         });
         this.backend.startup(&*this);
@@ -435,9 +447,19 @@ impl<B: Backend + 'static> TurboTasks<B> {
                     // Setup thread locals
                     let execution_future = CELL_COUNTERS.scope(Default::default(), async {
                         let execution = this.backend.try_start_task_execution(task_id, &*this)?;
+                        let recording = this.chrome_trace().map(|recorder| {
+                            (
+                                recorder,
+                                task_id,
+                                this.backend.get_task_description(task_id),
+                            )
+                        });
                         Some(
-                            TimedFuture::new(AssertUnwindSafe(execution.future).catch_unwind())
-                                .await,
+                            TimedFuture::new(TracedFuture::new(
+                                recording,
+                                AssertUnwindSafe(execution.future).catch_unwind(),
+                            ))
+                            .await,
                         )
                     });
                     if let Some((result, duration, instant)) = execution_future.await {
@@ -721,15 +743,110 @@ impl<B: Backend + 'static> TurboTasks<B> {
             } = &mut *cell.borrow_mut();
             let tasks = take(tasks_to_notify);
             if !tasks.is_empty() {
+                self.record_dependency_invalidations(&tasks);
                 self.backend.invalidate_tasks(tasks, self);
             }
             *stateful
         })
     }
 
+    /// Records that `tasks` are invalidated because the current task changed
+    /// its output or cells.
+    fn record_dependency_invalidations(&self, tasks: &[TaskId]) {
+        if !self.invalidations.is_enabled() {
+            return;
+        }
+        let reason = match CURRENT_TASK_ID.try_with(|id| *id) {
+            Ok(task) => InvalidationReason::DependencyChanged { task },
+            Err(_) => InvalidationReason::Unknown,
+        };
+        self.invalidations
+            .record(tasks.iter().copied(), self.program_start.elapsed(), reason);
+    }
+
     pub fn backend(&self) -> &B {
         &self.backend
     }
+
+    /// Enables recording why tasks are invalidated, which can be queried with
+    /// [TurboTasks::invalidations] and [TurboTasks::invalidation_chain].
+    /// Disabling it drops all recorded invalidations.
+    pub fn set_invalidation_tracking(&self, enabled: bool) {
+        self.invalidations.set_enabled(enabled);
+    }
+
+    pub fn is_invalidation_tracking_enabled(&self) -> bool {
+        self.invalidations.is_enabled()
+    }
+
+    /// The last recorded invalidations of `task`, oldest first.
+    pub fn invalidations(&self, task: TaskId) -> Vec<Invalidation> {
+        self.invalidations.get(task)
+    }
+
+    /// Starts recording task executions and reads to a Chrome Trace Event file
+    /// at `path`, which can be opened in `chrome://tracing` or Perfetto. A
+    /// trace that is already being recorded is finished.
+    pub fn start_chrome_trace(&self, path: impl AsRef<Path>) -> Result<()> {
+        let recorder = Arc::new(ChromeTraceRecorder::new(path.as_ref())?);
+        let old = self.chrome_trace.lock().unwrap().replace(recorder);
+        self.enable_chrome_trace.store(true, Ordering::Release);
+        if let Some(old) = old {
+            old.finish()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the events of the current trace recorded so far to its file.
+    pub fn flush_chrome_trace(&self) -> Result<()> {
+        if let Some(recorder) = self.chrome_trace() {
+            recorder.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Stops recording the current trace and completes its file.
+    pub fn stop_chrome_trace(&self) -> Result<()> {
+        self.enable_chrome_trace.store(false, Ordering::Release);
+        let recorder = self.chrome_trace.lock().unwrap().take();
+        if let Some(recorder) = recorder {
+            recorder.finish()?;
+        }
+        Ok(())
+    }
+
+    fn chrome_trace(&self) -> Option<Arc<ChromeTraceRecorder>> {
+        if !self.enable_chrome_trace.load(Ordering::Acquire) {
+            return None;
+        }
+        self.chrome_trace.lock().unwrap().clone()
+    }
+
+    /// Records a read of the output or a cell of `task` in the current trace.
+    fn trace_read<T>(
+        &self,
+        task: TaskId,
+        cell: Option<CellId>,
+        result: &Result<Result<T, EventListener>>,
+    ) {
+        if let Some(recorder) = self.chrome_trace() {
+            let reader = CURRENT_TASK_ID.try_with(|id| *id).ok();
+            recorder.read(reader, task, cell, matches!(result, Ok(Err(_))));
+        }
+    }
+
+    /// The last invalidation of the most recently invalidated tasks, most
+    /// recent first.
+    pub fn latest_invalidations(&self, limit: usize) -> Vec<(TaskId, Invalidation)> {
+        self.invalidations.latest(limit)
+    }
+
+    /// Explains the latest invalidation of `task`: the invalidation of `task`
+    /// followed by the invalidations of the tasks whose changes caused it,
+    /// down to the root cause.
+    pub fn invalidation_chain(&self, task: TaskId) -> Vec<(TaskId, Invalidation)> {
+        self.invalidations.chain(task)
+    }
 }
 
 impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
@@ -776,6 +893,12 @@ impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
 
 impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
     fn invalidate(&self, task: TaskId) {
+        self.invalidate_with_reason(task, InvalidationReason::Unknown);
+    }
+
+    fn invalidate_with_reason(&self, task: TaskId, reason: InvalidationReason) {
+        self.invalidations
+            .record([task], self.program_start.elapsed(), reason);
         self.backend.invalidate_task(task, self);
     }
 
@@ -788,6 +911,7 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
             if tasks.is_empty() {
                 return;
             }
+            self.record_dependency_invalidations(&tasks);
             self.backend.invalidate_tasks(tasks, self);
         });
     }
@@ -797,12 +921,14 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         strongly_consistent: bool,
     ) -> Result<Result<RawVc, EventListener>> {
-        self.backend.try_read_task_output(
+        let result = self.backend.try_read_task_output(
             task,
             current_task("reading Vcs"),
             strongly_consistent,
             self,
-        )
+        );
+        self.trace_read(task, None, &result);
+        result
     }
 
     fn try_read_task_output_untracked(
@@ -810,8 +936,11 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         strongly_consistent: bool,
     ) -> Result<Result<RawVc, EventListener>> {
-        self.backend
-            .try_read_task_output_untracked(task, strongly_consistent, self)
+        let result = self
+            .backend
+            .try_read_task_output_untracked(task, strongly_consistent, self);
+        self.trace_read(task, None, &result);
+        result
     }
 
     fn try_read_task_cell(
@@ -819,8 +948,11 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         index: CellId,
     ) -> Result<Result<CellContent, EventListener>> {
-        self.backend
-            .try_read_task_cell(task, index, current_task("reading Vcs"), self)
+        let result =
+            self.backend
+                .try_read_task_cell(task, index, current_task("reading Vcs"), self);
+        self.trace_read(task, Some(index), &result);
+        result
     }
 
     fn try_read_task_cell_untracked(
@@ -828,7 +960,9 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         index: CellId,
     ) -> Result<Result<CellContent, EventListener>> {
-        self.backend.try_read_task_cell_untracked(task, index, self)
+        let result = self.backend.try_read_task_cell_untracked(task, index, self);
+        self.trace_read(task, Some(index), &result);
+        result
     }
 
     fn try_read_own_task_cell_untracked(
@@ -964,6 +1098,7 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
             tasks_to_notify.extend(tasks.iter());
         });
         if result.is_err() {
+            self.record_dependency_invalidations(tasks);
             self.backend.invalidate_tasks(tasks.to_vec(), self);
         }
     }
@@ -978,8 +1113,9 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
             tasks_to_notify.extend(tasks.iter());
         });
         if result.is_err() {
-            self.backend
-                .invalidate_tasks(tasks.iter().copied().collect(), self);
+            let tasks = tasks.iter().copied().collect::<Vec<_>>();
+            self.record_dependency_invalidations(&tasks);
+            self.backend.invalidate_tasks(tasks, self);
         };
     }
 
@@ -1050,6 +1186,12 @@ impl Eq for Invalidator {}
 
 impl Invalidator {
     pub fn invalidate(self) {
+        self.invalidate_with_reason(InvalidationReason::Unknown)
+    }
+
+    /// Invalidates the task and records `reason` when invalidation tracking is
+    /// enabled.
+    pub fn invalidate_with_reason(self, reason: InvalidationReason) {
         let Invalidator {
             task,
             turbo_tasks,
@@ -1057,7 +1199,7 @@ impl Invalidator {
         } = self;
         let _ = handle.enter();
         if let Some(turbo_tasks) = turbo_tasks.upgrade() {
-            turbo_tasks.invalidate(task);
+            turbo_tasks.invalidate_with_reason(task, reason);
         }
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    any::type_name,
     fmt::Debug,
     mem::take,
     ops::{Deref, DerefMut},
@@ -8,7 +9,7 @@ use auto_hash_map::AutoSet;
 use parking_lot::{Mutex, MutexGuard};
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{get_invalidator, mark_stateful, trace::TraceRawVcs, Invalidator};
+use crate::{get_invalidator, mark_stateful, trace::TraceRawVcs, InvalidationReason, Invalidator};
 
 pub struct State<T> {
     inner: Mutex<StateInner<T>>,
@@ -71,9 +72,16 @@ impl<'de, T> Deserialize<'de> for State<T> {
 impl<T> Drop for State<T> {
     fn drop(&mut self) {
         let mut inner = self.inner.lock();
-        for invalidator in take(&mut inner.invalidators) {
-            invalidator.invalidate();
-        }
+        invalidate::<T>(take(&mut inner.invalidators));
+    }
+}
+
+/// Invalidates the tasks that read a [State] of `T`.
+fn invalidate<T>(invalidators: AutoSet<Invalidator>) {
+    for invalidator in invalidators {
+        invalidator.invalidate_with_reason(InvalidationReason::StateChanged {
+            ty: type_name::<T>(),
+        });
     }
 }
 
@@ -115,9 +123,7 @@ impl<T> State<T> {
     pub fn set_unconditionally(&self, value: T) {
         let mut inner = self.inner.lock();
         inner.value = value;
-        for invalidator in take(&mut inner.invalidators) {
-            invalidator.invalidate();
-        }
+        invalidate::<T>(take(&mut inner.invalidators));
     }
 
     /// Updates the current state with the `update` function. The `update`
@@ -129,9 +135,7 @@ impl<T> State<T> {
         if !update(&mut inner.value) {
             return;
         }
-        for invalidator in take(&mut inner.invalidators) {
-            invalidator.invalidate();
-        }
+        invalidate::<T>(take(&mut inner.invalidators));
     }
 }
 
@@ -144,9 +148,7 @@ impl<T: PartialEq> State<T> {
             return;
         }
         inner.value = value;
-        for invalidator in take(&mut inner.invalidators) {
-            invalidator.invalidate();
-        }
+        invalidate::<T>(take(&mut inner.invalidators));
     }
 }
 
@@ -168,9 +170,7 @@ impl<'a, T> DerefMut for StateRef<'a, T> {
 impl<'a, T> Drop for StateRef<'a, T> {
     fn drop(&mut self) {
         if self.mutated {
-            for invalidator in take(&mut self.inner.invalidators) {
-                invalidator.invalidate();
-            }
+            invalidate::<T>(take(&mut self.inner.invalidators));
         }
     }
 }
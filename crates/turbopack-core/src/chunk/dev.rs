@@ -1,6 +1,6 @@
 use std::fmt::Write;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use turbo_tasks::{
     primitives::{BoolVc, StringVc},
     Value, ValueToString,
@@ -8,7 +8,7 @@ use turbo_tasks::{
 use turbo_tasks_fs::FileSystemPathVc;
 use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64, DeterministicHash, Xxh3Hash64Hasher};
 
-use super::{ChunkingContext, ChunkingContextVc};
+use super::{ChunkingContext, ChunkingContextVc, ModuleId, ModuleIdVc};
 use crate::{
     asset::{Asset, AssetVc},
     environment::EnvironmentVc,
@@ -242,6 +242,22 @@ impl ChunkingContext for DevChunkingContext {
             .join(&format!("{content_hash}.{extension}"))
     }
 
+    #[turbo_tasks::function]
+    async fn asset_url(&self, path: FileSystemPathVc) -> Result<StringVc> {
+        let output_root = self.output_root_path.await?;
+        let path = path.await?;
+        let Some(relative_path) = output_root.get_path_to(&path) else {
+            bail!("{} is not in the output root {}", path.path, output_root.path);
+        };
+        // The output root is served at the root of the server.
+        Ok(StringVc::cell(format!("/{relative_path}")))
+    }
+
+    #[turbo_tasks::function]
+    async fn chunk_item_id(&self, ident: AssetIdentVc) -> Result<ModuleIdVc> {
+        Ok(ModuleId::String(ident.to_string().await?.clone_value()).cell())
+    }
+
     #[turbo_tasks::function]
     fn is_hot_module_replacement_enabled(&self) -> BoolVc {
         BoolVc::cell(self.enable_hot_module_replacement)
@@ -258,4 +274,14 @@ impl ChunkingContext for DevChunkingContext {
         context.layer = (!layer.is_empty()).then(|| layer.to_string());
         Ok(DevChunkingContextVc::new(Value::new(context)).into())
     }
+
+    #[turbo_tasks::function]
+    async fn with_environment(
+        self_vc: DevChunkingContextVc,
+        environment: EnvironmentVc,
+    ) -> Result<ChunkingContextVc> {
+        let mut context = self_vc.await?.clone_value();
+        context.environment = environment;
+        Ok(DevChunkingContextVc::new(Value::new(context)).into())
+    }
 }
@@ -1,15 +1,17 @@
 pub mod chunk_in_group;
 pub mod dev;
 pub mod optimize;
+pub mod production;
 
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
     fmt::{Debug, Display},
     future::Future,
     marker::PhantomData,
 };
 
 use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
     debug::ValueDebugFormat,
@@ -17,12 +19,12 @@ use turbo_tasks::{
         GraphTraversal, GraphTraversalResult, ReverseTopological, SkipDuplicates, Visit,
         VisitControlFlow,
     },
-    primitives::{BoolVc, StringVc},
+    primitives::{BoolVc, OptionStringVc, StringVc},
     trace::TraceRawVcs,
     TryJoinIterExt, ValueToString, ValueToStringVc,
 };
 use turbo_tasks_fs::FileSystemPathVc;
-use turbo_tasks_hash::DeterministicHash;
+use turbo_tasks_hash::{encode_hex, DeterministicHash};
 
 use self::{chunk_in_group::ChunkInGroupVc, optimize::optimize};
 use crate::{
@@ -72,6 +74,36 @@ impl ModuleId {
 #[turbo_tasks::value(transparent, shared)]
 pub struct ModuleIds(Vec<ModuleIdVc>);
 
+/// The exports of a module which are used by other modules.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub enum UsedExports {
+    /// All exports are used, or their usage can't be determined statically.
+    All,
+    /// Only these exports are used. When empty, the module is only imported
+    /// for its side effects.
+    Names(BTreeSet<String>),
+}
+
+impl UsedExports {
+    pub fn is_used(&self, export: &str) -> bool {
+        match self {
+            UsedExports::All => true,
+            UsedExports::Names(names) => names.contains(export),
+        }
+    }
+}
+
+/// The [UsedExports] of all modules of a module graph, keyed by the string
+/// representation of the module ident.
+#[turbo_tasks::value(transparent)]
+pub struct ExportUsage(IndexMap<String, UsedExports>);
+
+/// Maps the string representation of the ident of modules which are
+/// concatenated into another module to the ident of that module.
+#[turbo_tasks::value(transparent)]
+pub struct ModuleConcatenation(IndexMap<String, String>);
+
 /// A context for the chunking that influences the way chunks are created
 #[turbo_tasks::value_trait]
 pub trait ChunkingContext {
@@ -87,15 +119,57 @@ pub trait ChunkingContext {
 
     fn asset_path(&self, content_hash: &str, extension: &str) -> FileSystemPathVc;
 
+    /// Returns the URL which the chunk or asset at the given path, which must
+    /// be inside of the [ChunkingContext::output_root], is requested from.
+    fn asset_url(&self, path: FileSystemPathVc) -> StringVc;
+
+    /// Returns the [ModuleId] of the chunk item with the given ident.
+    fn chunk_item_id(&self, ident: AssetIdentVc) -> ModuleIdVc;
+
     fn is_hot_module_replacement_enabled(&self) -> BoolVc {
         BoolVc::cell(false)
     }
 
+    fn is_minification_enabled(&self) -> BoolVc {
+        BoolVc::cell(false)
+    }
+
+    /// Whether chunk paths should change with the content of the chunks.
+    /// When enabled, chunks add a [content_hash_modifier] to the ident they
+    /// pass to [ChunkingContext::chunk_path].
+    fn is_content_hashing_enabled(&self) -> BoolVc {
+        BoolVc::cell(false)
+    }
+
+    /// Returns the exports of the module with the given ident which are used
+    /// in the module graph. Unused exports can be omitted from the output.
+    fn used_exports(&self, _ident: AssetIdentVc) -> UsedExportsVc {
+        UsedExports::All.cell()
+    }
+
+    /// Returns the ident of the module which the module with the given ident
+    /// is concatenated into, if any. Concatenated modules are emitted as part
+    /// of that module instead of as separate chunk items.
+    fn concatenation_root(&self, _ident: AssetIdentVc) -> OptionStringVc {
+        OptionStringVc::cell(None)
+    }
+
     fn layer(&self) -> StringVc {
         StringVc::cell("".to_string())
     }
 
     fn with_layer(&self, layer: &str) -> ChunkingContextVc;
+
+    /// Returns a chunking context which creates chunks that are evaluated in
+    /// the given environment, e.g. for web workers.
+    fn with_environment(&self, environment: EnvironmentVc) -> ChunkingContextVc;
+}
+
+/// The modifier which carries the content hash of a chunk in the ident of the
+/// chunk, see [ChunkingContext::is_content_hashing_enabled].
+#[turbo_tasks::function]
+pub fn content_hash_modifier(hash: u64) -> StringVc {
+    StringVc::cell(format!("content {}", encode_hex(hash)))
 }
 
 /// An [Asset] that can be converted into a [Chunk].
@@ -0,0 +1,420 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use anyhow::{bail, Result};
+use turbo_tasks::{
+    primitives::{BoolVc, OptionStringVc, StringVc},
+    Value, ValueToString,
+};
+use turbo_tasks_fs::FileSystemPathVc;
+use turbo_tasks_hash::{encode_hex, DeterministicHash, Xxh3Hash64Hasher};
+
+use super::{
+    ChunkingContext, ChunkingContextVc, ExportUsageVc, ModuleConcatenationVc, ModuleId, ModuleIdVc,
+    UsedExports, UsedExportsVc,
+};
+use crate::{
+    asset::{Asset, AssetVc, AssetsVc},
+    environment::EnvironmentVc,
+    ident::{AssetIdent, AssetIdentVc},
+    reference::all_referenced_assets,
+};
+
+/// Module ids are at least this many hex digits long.
+const MIN_MODULE_ID_LENGTH: usize = 4;
+
+pub struct ProductionChunkingContextBuilder {
+    context: ProductionChunkingContext,
+}
+
+impl ProductionChunkingContextBuilder {
+    pub fn minify(mut self) -> Self {
+        self.context.enable_minification = true;
+        self
+    }
+
+    /// Omits exports which are unused according to the given [ExportUsage]
+    /// from the emitted modules.
+    ///
+    /// [ExportUsage]: super::ExportUsage
+    pub fn tree_shaking(mut self, export_usage: ExportUsageVc) -> Self {
+        self.context.export_usage = Some(export_usage);
+        self
+    }
+
+    /// Concatenates modules into the modules they are imported from according
+    /// to the given [ModuleConcatenation].
+    ///
+    /// [ModuleConcatenation]: super::ModuleConcatenation
+    pub fn scope_hoisting(mut self, module_concatenation: ModuleConcatenationVc) -> Self {
+        self.context.module_concatenation = Some(module_concatenation);
+        self
+    }
+
+    /// Shortens the ids of the modules reachable from `entries` to the
+    /// shortest prefix of their hash which is unique among these modules.
+    /// Other modules get the full hash as id.
+    pub fn module_ids(mut self, entries: AssetsVc) -> Self {
+        self.context.module_graph_entries = Some(entries);
+        self
+    }
+
+    pub fn layer(mut self, layer: &str) -> Self {
+        self.context.layer = (!layer.is_empty()).then(|| layer.to_string());
+        self
+    }
+
+    pub fn css_chunk_root_path(mut self, path: FileSystemPathVc) -> Self {
+        self.context.css_chunk_root_path = Some(path);
+        self
+    }
+
+    pub fn build(self) -> ChunkingContextVc {
+        ProductionChunkingContextVc::new(Value::new(self.context)).into()
+    }
+}
+
+/// A chunking context for production builds.
+/// Chunk filenames contain a hash of the chunk content, so they change
+/// whenever the content changes and can be cached as immutable. Manifest
+/// chunks, which list the chunks of a chunk group, are the exception: they are
+/// named `*.manifest.js` and must be served with cache headers which make the
+/// browser revalidate them. Module ids are hashes of the project relative
+/// identity of the assets, so building the same sources yields identical
+/// output. They are shortened when the module graph is known, see
+/// [ProductionChunkingContextBuilder::module_ids]. Hot module replacement is
+/// never enabled.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Clone, Hash, PartialOrd, Ord)]
+pub struct ProductionChunkingContext {
+    /// This path get striped off of path before hashing it
+    context_path: FileSystemPathVc,
+    /// This path is used to compute the url to request chunks or assets from
+    output_root_path: FileSystemPathVc,
+    /// Chunks are placed at this path
+    chunk_root_path: FileSystemPathVc,
+    /// Css Chunks are placed at this path
+    css_chunk_root_path: Option<FileSystemPathVc>,
+    /// Static assets are placed at this path
+    asset_root_path: FileSystemPathVc,
+    /// Layer name within this context
+    layer: Option<String>,
+    /// Enable minification of the emitted chunks
+    enable_minification: bool,
+    /// Usage of exports in the module graph, used to omit unused exports
+    export_usage: Option<ExportUsageVc>,
+    /// Modules which are concatenated into other modules
+    module_concatenation: Option<ModuleConcatenationVc>,
+    /// Entries of the module graph whose module ids are shortened
+    module_graph_entries: Option<AssetsVc>,
+    /// The environment chunks will be evaluated in.
+    environment: EnvironmentVc,
+}
+
+impl ProductionChunkingContextVc {
+    pub fn builder(
+        context_path: FileSystemPathVc,
+        output_root_path: FileSystemPathVc,
+        chunk_root_path: FileSystemPathVc,
+        asset_root_path: FileSystemPathVc,
+        environment: EnvironmentVc,
+    ) -> ProductionChunkingContextBuilder {
+        ProductionChunkingContextBuilder {
+            context: ProductionChunkingContext {
+                context_path,
+                output_root_path,
+                chunk_root_path,
+                css_chunk_root_path: None,
+                asset_root_path,
+                layer: None,
+                enable_minification: false,
+                export_usage: None,
+                module_concatenation: None,
+                module_graph_entries: None,
+                environment,
+            },
+        }
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProductionChunkingContextVc {
+    #[turbo_tasks::function]
+    fn new(this: Value<ProductionChunkingContext>) -> Self {
+        this.into_value().cell()
+    }
+
+    /// Maps the full hashes of the idents of the modules in the module graph
+    /// to their shortened module ids.
+    #[turbo_tasks::function]
+    async fn module_ids(self) -> Result<ModuleIdsVc> {
+        let this = self.await?;
+        let Some(entries) = this.module_graph_entries else {
+            return Ok(ModuleIdsVc::cell(HashMap::new()));
+        };
+        let layer = this.layer.clone().map(StringVc::cell);
+        let mut hashes = BTreeSet::new();
+        let mut seen = HashSet::new();
+        let mut queue: VecDeque<AssetVc> = entries.await?.iter().copied().collect();
+        while let Some(asset) = queue.pop_front() {
+            if !seen.insert(asset) {
+                continue;
+            }
+            // Chunk items of modules are identified with the layer as modifier
+            let mut ident = asset.ident();
+            if let Some(layer) = layer {
+                ident = ident.with_modifier(layer);
+            }
+            hashes.insert(encode_hex(this.hash_ident(ident).await?.finish()));
+            queue.extend(all_referenced_assets(asset).await?.iter().copied());
+        }
+        Ok(ModuleIdsVc::cell(shorten_hashes(&hashes)))
+    }
+}
+
+/// See [ProductionChunkingContextVc::module_ids].
+#[turbo_tasks::value(transparent)]
+struct ModuleIds(HashMap<String, String>);
+
+/// Shortens each hash to its shortest prefix which is not a prefix of any
+/// other hash. Only the neighbors in sorted order need to be compared, which
+/// makes the result independent of the order in which modules are found.
+/// Modules only get longer ids when a new module shares a prefix with them.
+fn shorten_hashes(hashes: &BTreeSet<String>) -> HashMap<String, String> {
+    fn common_prefix_len(a: &str, b: &str) -> usize {
+        a.bytes().zip(b.bytes()).take_while(|(a, b)| a == b).count()
+    }
+
+    let hashes: Vec<&String> = hashes.iter().collect();
+    let mut ids = HashMap::with_capacity(hashes.len());
+    for (index, hash) in hashes.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .map_or(0, |previous| common_prefix_len(hash, hashes[previous]));
+        let next = hashes
+            .get(index + 1)
+            .map_or(0, |next| common_prefix_len(hash, next));
+        let len = (previous.max(next) + 1).max(MIN_MODULE_ID_LENGTH);
+        ids.insert((*hash).clone(), hash[..len.min(hash.len())].to_string());
+    }
+    ids
+}
+
+impl ProductionChunkingContext {
+    /// Hashes an [AssetIdent] with its path relative to the context path, so
+    /// the hash doesn't depend on the location of the project.
+    async fn hash_ident(&self, ident: AssetIdentVc) -> Result<Xxh3Hash64Hasher> {
+        let ident = &*ident.await?;
+        let mut hasher = Xxh3Hash64Hasher::new();
+        let AssetIdent {
+            path,
+            query,
+            fragment,
+            assets,
+            modifiers,
+        } = ident;
+        let context_path = self.context_path.await?;
+        if let Some(inner) = context_path.get_path_to(&*path.await?) {
+            inner.deterministic_hash(&mut hasher);
+        } else {
+            path.to_string().await?.deterministic_hash(&mut hasher);
+        }
+        if let Some(query) = query {
+            0_u8.deterministic_hash(&mut hasher);
+            query.await?.deterministic_hash(&mut hasher);
+        }
+        if let Some(fragment) = fragment {
+            1_u8.deterministic_hash(&mut hasher);
+            fragment.await?.deterministic_hash(&mut hasher);
+        }
+        for (key, ident) in assets.iter() {
+            2_u8.deterministic_hash(&mut hasher);
+            key.await?.deterministic_hash(&mut hasher);
+            ident.to_string().await?.deterministic_hash(&mut hasher);
+        }
+        for modifier in modifiers.iter() {
+            3_u8.deterministic_hash(&mut hasher);
+            modifier.await?.deterministic_hash(&mut hasher);
+        }
+        Ok(hasher)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkingContext for ProductionChunkingContext {
+    #[turbo_tasks::function]
+    fn output_root(&self) -> FileSystemPathVc {
+        self.output_root_path
+    }
+
+    #[turbo_tasks::function]
+    fn environment(&self) -> EnvironmentVc {
+        self.environment
+    }
+
+    #[turbo_tasks::function]
+    async fn chunk_path(&self, ident: AssetIdentVc, extension: &str) -> Result<FileSystemPathVc> {
+        let mut hasher = self.hash_ident(ident).await?;
+        extension.deterministic_hash(&mut hasher);
+        let hash = encode_hex(hasher.finish());
+        let name = format!("{hash}{extension}");
+
+        let mut root_path = self.chunk_root_path;
+        #[allow(clippy::single_match, reason = "future extensions")]
+        match extension {
+            ".css" => {
+                if let Some(path) = self.css_chunk_root_path {
+                    root_path = path;
+                }
+            }
+            _ => {}
+        }
+        let root_path = if let Some(layer) = self.layer.as_deref() {
+            root_path.join(layer)
+        } else {
+            root_path
+        };
+        Ok(root_path.join(&name))
+    }
+
+    #[turbo_tasks::function]
+    fn can_be_in_same_chunk(&self, _asset_a: AssetVc, _asset_b: AssetVc) -> BoolVc {
+        BoolVc::cell(true)
+    }
+
+    #[turbo_tasks::function]
+    fn asset_path(&self, content_hash: &str, extension: &str) -> FileSystemPathVc {
+        self.asset_root_path
+            .join(&format!("{content_hash}.{extension}"))
+    }
+
+    #[turbo_tasks::function]
+    async fn asset_url(&self, path: FileSystemPathVc) -> Result<StringVc> {
+        let output_root = self.output_root_path.await?;
+        let path = path.await?;
+        let Some(relative_path) = output_root.get_path_to(&path) else {
+            bail!("{} is not in the output root {}", path.path, output_root.path);
+        };
+        // The output root is served at the root of the server.
+        Ok(StringVc::cell(format!("/{relative_path}")))
+    }
+
+    /// Module ids are the shortened 64 bit hash of the ident. Chunks fail
+    /// when two of their chunk items share an id, which only happens when
+    /// the full hashes collide.
+    #[turbo_tasks::function]
+    async fn chunk_item_id(
+        self_vc: ProductionChunkingContextVc,
+        ident: AssetIdentVc,
+    ) -> Result<ModuleIdVc> {
+        let hash = encode_hex(self_vc.await?.hash_ident(ident).await?.finish());
+        let id = match self_vc.module_ids().await?.get(&hash) {
+            Some(id) => id.clone(),
+            None => hash,
+        };
+        Ok(ModuleId::String(id).cell())
+    }
+
+    #[turbo_tasks::function]
+    fn is_hot_module_replacement_enabled(&self) -> BoolVc {
+        BoolVc::cell(false)
+    }
+
+    #[turbo_tasks::function]
+    fn is_minification_enabled(&self) -> BoolVc {
+        BoolVc::cell(self.enable_minification)
+    }
+
+    #[turbo_tasks::function]
+    fn is_content_hashing_enabled(&self) -> BoolVc {
+        BoolVc::cell(true)
+    }
+
+    #[turbo_tasks::function]
+    async fn used_exports(&self, ident: AssetIdentVc) -> Result<UsedExportsVc> {
+        let Some(export_usage) = self.export_usage else {
+            return Ok(UsedExports::All.cell());
+        };
+        let ident = ident.to_string().await?;
+        Ok(export_usage
+            .await?
+            .get(&*ident)
+            .cloned()
+            .unwrap_or(UsedExports::All)
+            .cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn concatenation_root(&self, ident: AssetIdentVc) -> Result<OptionStringVc> {
+        let Some(module_concatenation) = self.module_concatenation else {
+            return Ok(OptionStringVc::cell(None));
+        };
+        let ident = ident.to_string().await?;
+        Ok(OptionStringVc::cell(
+            module_concatenation.await?.get(&*ident).cloned(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn layer(&self) -> StringVc {
+        StringVc::cell(self.layer.clone().unwrap_or_default())
+    }
+
+    #[turbo_tasks::function]
+    async fn with_layer(
+        self_vc: ProductionChunkingContextVc,
+        layer: &str,
+    ) -> Result<ChunkingContextVc> {
+        let mut context = self_vc.await?.clone_value();
+        context.layer = (!layer.is_empty()).then(|| layer.to_string());
+        Ok(ProductionChunkingContextVc::new(Value::new(context)).into())
+    }
+
+    #[turbo_tasks::function]
+    async fn with_environment(
+        self_vc: ProductionChunkingContextVc,
+        environment: EnvironmentVc,
+    ) -> Result<ChunkingContextVc> {
+        let mut context = self_vc.await?.clone_value();
+        context.environment = environment;
+        Ok(ProductionChunkingContextVc::new(Value::new(context)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::shorten_hashes;
+
+    #[test]
+    fn shortens_hashes_to_unique_prefixes() {
+        let hashes: BTreeSet<String> = [
+            "0123456789abcdef",
+            "0123ffff00000000",
+            "01234fff00000000",
+            "fedcba9876543210",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let ids = shorten_hashes(&hashes);
+        assert_eq!(ids["0123456789abcdef"], "012345");
+        assert_eq!(ids["01234fff00000000"], "01234f");
+        assert_eq!(ids["0123ffff00000000"], "0123f");
+        assert_eq!(ids["fedcba9876543210"], "fedc");
+    }
+
+    #[test]
+    fn keeps_ids_when_unrelated_hashes_are_added() {
+        let mut hashes: BTreeSet<String> = ["0123456789abcdef", "fedcba9876543210"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let before = shorten_hashes(&hashes);
+        hashes.insert("8888888888888888".to_string());
+        let after = shorten_hashes(&hashes);
+        assert_eq!(before["0123456789abcdef"], after["0123456789abcdef"]);
+        assert_eq!(before["fedcba9876543210"], after["fedcba9876543210"]);
+        assert_eq!(after["8888888888888888"], "8888");
+    }
+}
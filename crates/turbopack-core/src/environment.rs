@@ -72,6 +72,8 @@ pub enum ChunkLoading {
     NodeJs,
     /// <script> and <link> tags in the browser
     Dom,
+    /// importScripts() in web workers
+    WebWorker,
 }
 
 #[turbo_tasks::value]
@@ -252,10 +254,53 @@ impl EnvironmentVc {
                 ChunkLoading::NodeJs.cell()
             }
             ExecutionEnvironment::EdgeWorker(_) => ChunkLoading::None.cell(),
-            ExecutionEnvironment::Browser(_) => ChunkLoading::Dom.cell(),
+            ExecutionEnvironment::Browser(env) => {
+                let env = env.await?;
+                if env.web_worker && !env.dom {
+                    ChunkLoading::WebWorker.cell()
+                } else {
+                    ChunkLoading::Dom.cell()
+                }
+            }
             _ => ChunkLoading::None.cell(),
         })
     }
+
+    /// Returns the environment of the web workers which are created in this
+    /// environment. Other environments than browsers are returned unchanged.
+    #[turbo_tasks::function]
+    pub async fn web_worker(self) -> Result<EnvironmentVc> {
+        let env = self.await?;
+        Ok(match env.execution {
+            ExecutionEnvironment::Browser(browser_env) => {
+                let browser_env = browser_env.await?;
+                EnvironmentVc::new(
+                    Value::new(ExecutionEnvironment::Browser(
+                        BrowserEnvironment {
+                            dom: false,
+                            web_worker: true,
+                            service_worker: false,
+                            browserslist_query: browser_env.browserslist_query.clone(),
+                        }
+                        .into(),
+                    )),
+                    Value::new(env.intention),
+                )
+            }
+            _ => self,
+        })
+    }
+
+    #[turbo_tasks::function]
+    pub async fn browserslist_query(self) -> Result<OptionStringVc> {
+        let env = self.await?;
+        Ok(match env.execution {
+            ExecutionEnvironment::Browser(env) => {
+                OptionStringVc::cell(Some(env.await?.browserslist_query.clone()))
+            }
+            _ => OptionStringVc::cell(None),
+        })
+    }
 }
 
 pub enum NodeEnvironmentType {
@@ -1,5 +1,6 @@
 pub mod analyze;
 pub mod code_gen;
+pub mod lockfile;
 pub mod package_json;
 pub mod resolve;
 pub mod unsupported_module;
@@ -424,7 +425,7 @@ impl CapturedIssues {
     }
 }
 
-#[turbo_tasks::value]
+#[turbo_tasks::value(shared)]
 #[derive(Clone)]
 pub struct IssueSource {
     pub asset: AssetVc,
@@ -0,0 +1,123 @@
+use anyhow::{anyhow, bail, Result};
+use base64::Engine;
+use mime::Mime;
+use turbo_tasks_fs::{File, FileSystemPathVc};
+use turbo_tasks_hash::{encode_hex, Xxh3Hash64Hasher};
+
+use crate::{asset::AssetVc, virtual_asset::VirtualAssetVc};
+
+/// Creates a [VirtualAsset](crate::virtual_asset::VirtualAsset) for the content
+/// of a `data:` URI. The asset is placed next to the importing file with an
+/// extension matching the media type, so it is processed by the same module
+/// rules as an ordinary file of that type.
+#[turbo_tasks::function]
+pub async fn data_uri_asset(
+    context: FileSystemPathVc,
+    media_type: String,
+    encoding: String,
+    data: String,
+) -> Result<AssetVc> {
+    let media_type = parse_media_type(&media_type)?;
+    let content = decode_data(&encoding, &data)?;
+    let extension = extension_for_media_type(&media_type);
+    let mut hasher = Xxh3Hash64Hasher::new();
+    hasher.write_ref(&encoding);
+    hasher.write_ref(&data);
+    let hash = encode_hex(hasher.finish());
+    let path = context.join(&format!("data-uri-{hash}.{extension}"));
+    let content = File::from(content).with_content_type(media_type);
+    Ok(VirtualAssetVc::new(path, content.into()).into())
+}
+
+/// Parses the media type of a `data:` URI, which defaults to `text/plain`.
+fn parse_media_type(media_type: &str) -> Result<Mime> {
+    if media_type.is_empty() || media_type.starts_with(';') {
+        return Ok(mime::TEXT_PLAIN_UTF_8);
+    }
+    // parameters without a value (e.g. `image/svg+xml;utf8`) are common in data
+    // uris, but not valid media type syntax
+    let essence = media_type.split(';').next().unwrap_or(media_type);
+    media_type
+        .parse::<Mime>()
+        .or_else(|_| essence.parse())
+        .map_err(|err| anyhow!("invalid media type \"{media_type}\" in data uri: {err}"))
+}
+
+/// Decodes the data of a `data:` URI, which is either base64 or percent
+/// encoded.
+fn decode_data(encoding: &str, data: &str) -> Result<Vec<u8>> {
+    match encoding {
+        "base64" => {
+            let data = data
+                .chars()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect::<String>();
+            let data = urlencoding::decode(&data)?;
+            base64::engine::general_purpose::STANDARD
+                .decode(data.as_bytes())
+                .map_err(|err| anyhow!("invalid base64 data in data uri: {err}"))
+        }
+        "" => Ok(urlencoding::decode_binary(data.as_bytes()).into_owned()),
+        encoding => bail!("unsupported encoding \"{encoding}\" in data uri"),
+    }
+}
+
+/// Picks a file extension for a media type, so that module rules based on
+/// extensions apply to the content.
+fn extension_for_media_type(media_type: &Mime) -> &'static str {
+    match media_type.essence_str() {
+        "application/json" | "text/json" => "json",
+        "text/css" => "css",
+        "text/javascript" | "application/javascript" | "application/ecmascript" => "js",
+        "application/wasm" => "wasm",
+        "image/svg+xml" => "svg",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "font/woff" => "woff",
+        "font/woff2" => "woff2",
+        "font/ttf" => "ttf",
+        "font/otf" => "otf",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_data, extension_for_media_type, parse_media_type};
+
+    #[test]
+    fn decode() {
+        assert_eq!(
+            decode_data("base64", "eyJhIjogMX0=").unwrap(),
+            br#"{"a": 1}"#.to_vec()
+        );
+        assert_eq!(
+            decode_data("base64", "eyJh\n IjogMX0%3D").unwrap(),
+            br#"{"a": 1}"#.to_vec()
+        );
+        assert_eq!(
+            decode_data("", "%3Csvg%3E%3C/svg%3E").unwrap(),
+            b"<svg></svg>".to_vec()
+        );
+        assert!(decode_data("base64", "!!!").is_err());
+        assert!(decode_data("gzip", "").is_err());
+    }
+
+    #[test]
+    fn media_types() {
+        let extension =
+            |media_type: &str| extension_for_media_type(&parse_media_type(media_type).unwrap());
+        assert_eq!(extension("application/json"), "json");
+        assert_eq!(extension("text/css;charset=utf-8"), "css");
+        assert_eq!(extension("image/svg+xml"), "svg");
+        assert_eq!(extension("image/svg+xml;utf8"), "svg");
+        assert_eq!(extension(""), "txt");
+        assert_eq!(extension(";charset=utf-8"), "txt");
+        assert_eq!(extension("application/x-unknown"), "bin");
+    }
+}
@@ -320,6 +320,67 @@ impl ExportsField {
     }
 }
 
+impl<'a> IntoIterator for &'a ExportsField {
+    type Item = (AliasPattern, &'a ExportsValue);
+    type IntoIter = AliasMapIter<'a, ExportsValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (&self.0).into_iter()
+    }
+}
+
+/// Content of an "imports" field in a package.json
+#[derive(PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportsField(AliasMap<ExportsValue>);
+
+impl TryFrom<&Value> for ImportsField {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        // The "imports" field must be an object.
+        // https://nodejs.org/api/packages.html#imports
+        let map = match value {
+            Value::Object(object) => {
+                let mut map = AliasMap::new();
+
+                for (key, value) in object.iter() {
+                    if !key.starts_with('#') {
+                        bail!(
+                            "invalid key \"{}\" in an imports field. Keys must start with \"#\".",
+                            key
+                        );
+                    }
+                    if key == "#" || key.starts_with("#/") {
+                        bail!(
+                            "invalid key \"{}\" in an imports field. \"#\" and \"#/\" are not \
+                             allowed as the start of a key.",
+                            key
+                        );
+                    }
+
+                    let value: ExportsValue = value.try_into()?;
+                    map.insert(AliasPattern::parse(key), value);
+                }
+
+                map
+            }
+            _ => {
+                bail!("\"imports\" field must be an object");
+            }
+        };
+        Ok(Self(map))
+    }
+}
+
+impl ImportsField {
+    /// Looks up a request string in the "imports" field. Returns an iterator of
+    /// matching requests. Usually only the first one is relevant, except
+    /// when conditions don't match or only partially match.
+    pub fn lookup<'a>(&'a self, request: &'a str) -> AliasMapLookupIterator<'a, ExportsValue> {
+        self.0.lookup(request)
+    }
+}
+
 /// Content of an "alias" configuration
 #[turbo_tasks::value(shared)]
 #[derive(Default)]
@@ -0,0 +1,715 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+use turbo_tasks::primitives::OptionStringVc;
+use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemPathVc};
+
+use super::{find_context_file, package_json, FindContextFileResult};
+use crate::issue::lockfile::{LockfileIssue, LockfileIssueVc};
+
+/// package.json fields which declare dependencies of a package.
+const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "optionalDependencies",
+    "peerDependencies",
+];
+
+/// Versions of packages locked by a pnpm, npm or yarn lockfile. Used to pick
+/// a package version from a registry filesystem (see
+/// [ResolveModules::Registry](super::options::ResolveModules::Registry)).
+#[turbo_tasks::value(shared)]
+#[derive(Hash, Debug)]
+pub struct LockedVersions {
+    /// The lockfile these versions were read from.
+    pub lockfile: FileSystemPathVc,
+    /// Locked dependencies of the workspace packages (importers), keyed by the
+    /// path of the importer relative to the lockfile directory. The root
+    /// importer has an empty path.
+    pub importers: BTreeMap<String, BTreeMap<String, String>>,
+    /// Locked dependencies of the packages in the registry, keyed by
+    /// `name@version`.
+    pub packages: BTreeMap<String, BTreeMap<String, String>>,
+    /// Locked versions by `name@range` descriptor. Only yarn lockfiles don't
+    /// record the dependencies of importers, in which case the ranges are read
+    /// from the nearest package.json and looked up here.
+    pub descriptors: BTreeMap<String, String>,
+}
+
+/// The content of a lockfile, independent of its format.
+#[derive(Default, Debug, PartialEq, Eq)]
+struct ParsedLockfile {
+    importers: BTreeMap<String, BTreeMap<String, String>>,
+    packages: BTreeMap<String, BTreeMap<String, String>>,
+    descriptors: BTreeMap<String, String>,
+}
+
+#[turbo_tasks::value_impl]
+impl LockedVersionsVc {
+    /// Reads the locked versions from a `pnpm-lock.yaml`, `package-lock.json`,
+    /// `npm-shrinkwrap.json` or `yarn.lock` file.
+    #[turbo_tasks::function]
+    pub async fn from_lockfile(lockfile: FileSystemPathVc) -> Result<Self> {
+        let parsed = match parse_lockfile(lockfile).await {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let issue: LockfileIssueVc = LockfileIssue {
+                    path: lockfile,
+                    error_message: format!("{err:#}"),
+                }
+                .into();
+                issue.as_issue().emit();
+                ParsedLockfile::default()
+            }
+        };
+        let ParsedLockfile {
+            importers,
+            packages,
+            descriptors,
+        } = parsed;
+        Ok(LockedVersions {
+            lockfile,
+            importers,
+            packages,
+            descriptors,
+        }
+        .cell())
+    }
+
+    /// Selects the locked version of `package_name` for a request from
+    /// `context`. Requests from inside of the `registry` use the dependencies
+    /// of the package they originate from, all other requests use the
+    /// dependencies of the closest importer.
+    #[turbo_tasks::function]
+    pub async fn select_version(
+        self,
+        registry: FileSystemPathVc,
+        context: FileSystemPathVc,
+        package_name: String,
+    ) -> Result<OptionStringVc> {
+        let this = self.await?;
+        let context_value = context.await?;
+
+        let registry_value = registry.await?;
+        if let Some(inner_path) = registry_value.get_path_to(&context_value) {
+            let version = registry_package_key(inner_path)
+                .and_then(|key| this.packages.get(&key))
+                .and_then(|dependencies| dependencies.get(&package_name))
+                .cloned();
+            return Ok(OptionStringVc::cell(version));
+        }
+
+        let root = this.lockfile.parent().await?;
+        if !context_value.is_inside_or_equal(&root) {
+            return Ok(OptionStringVc::cell(None));
+        }
+        let inner_path = root.get_path_to(&context_value).unwrap_or("");
+        if let Some(version) = select_importer(&this.importers, inner_path)
+            .and_then(|dependencies| dependencies.get(&package_name))
+        {
+            return Ok(OptionStringVc::cell(Some(version.clone())));
+        }
+
+        if !this.descriptors.is_empty() {
+            if let FindContextFileResult::Found(package_json_path, _) =
+                &*find_context_file(context, package_json()).await?
+            {
+                if let FileJsonContent::Content(package_json) =
+                    &*package_json_path.read_json().await?
+                {
+                    for field in DEPENDENCY_FIELDS {
+                        if let Some(range) = package_json[field][&package_name].as_str() {
+                            if let Some(version) =
+                                this.descriptors.get(&format!("{package_name}@{range}"))
+                            {
+                                return Ok(OptionStringVc::cell(Some(version.clone())));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(OptionStringVc::cell(None))
+    }
+}
+
+async fn parse_lockfile(lockfile: FileSystemPathVc) -> Result<ParsedLockfile> {
+    let content = lockfile.read().await?;
+    let FileContent::Content(file) = &*content else {
+        bail!("lockfile not found");
+    };
+    let content = file
+        .content()
+        .to_str()
+        .context("lockfile is not valid UTF-8")?;
+    let lockfile_value = lockfile.await?;
+    match lockfile_value.file_name() {
+        "pnpm-lock.yaml" => parse_pnpm_lockfile(&content),
+        "package-lock.json" | "npm-shrinkwrap.json" => parse_npm_lockfile(&content),
+        "yarn.lock" => parse_yarn_lockfile(&content),
+        name => bail!("unsupported lockfile \"{name}\""),
+    }
+}
+
+/// Returns the dependencies of the innermost importer containing `path`.
+fn select_importer<'a>(
+    importers: &'a BTreeMap<String, BTreeMap<String, String>>,
+    path: &str,
+) -> Option<&'a BTreeMap<String, String>> {
+    importers
+        .iter()
+        .filter(|(importer, _)| {
+            importer.is_empty()
+                || path == importer.as_str()
+                || path
+                    .strip_prefix(importer.as_str())
+                    .map_or(false, |rest| rest.starts_with('/'))
+        })
+        .max_by_key(|(importer, _)| importer.len())
+        .map(|(_, dependencies)| dependencies)
+}
+
+/// Converts a path inside of the registry filesystem
+/// (`@scope/module/version/<path-in-package>`) into a `name@version` key.
+fn registry_package_key(path: &str) -> Option<String> {
+    let mut segments = path.split('/');
+    let mut name = segments.next()?.to_string();
+    if name.starts_with('@') {
+        name.push('/');
+        name.push_str(segments.next()?);
+    }
+    let version = segments.next()?;
+    Some(format!("{name}@{version}"))
+}
+
+/// Splits a `name@version` descriptor, taking care of scoped package names.
+fn split_descriptor(descriptor: &str) -> Option<(&str, &str)> {
+    let index = descriptor.get(1..)?.find('@')? + 1;
+    Some((&descriptor[..index], &descriptor[index + 1..]))
+}
+
+/// Parses a `package-lock.json` (lockfileVersion 2 or 3). Dependencies are
+/// resolved by walking up the `node_modules` locations like Node.js would.
+fn parse_npm_lockfile(content: &str) -> Result<ParsedLockfile> {
+    let lockfile: JsonValue = serde_json::from_str(content)?;
+    let Some(locations) = lockfile["packages"].as_object() else {
+        bail!("missing \"packages\" field, only lockfileVersion 2 and 3 are supported");
+    };
+
+    let version_at = |location: &str| -> Option<&str> {
+        let entry = locations.get(location)?;
+        if entry["link"].as_bool() == Some(true) {
+            // links point to workspace packages, which are not in the registry
+            return None;
+        }
+        entry["version"].as_str()
+    };
+    let resolve_dependency = |location: &str, name: &str| -> Option<String> {
+        let mut base = location;
+        loop {
+            let candidate = if base.is_empty() {
+                format!("node_modules/{name}")
+            } else {
+                format!("{base}/node_modules/{name}")
+            };
+            if locations.contains_key(&candidate) {
+                return version_at(&candidate).map(|version| version.to_string());
+            }
+            if base.is_empty() {
+                return None;
+            }
+            base = base.rsplit_once('/').map_or("", |(parent, _)| parent);
+        }
+    };
+
+    let mut parsed = ParsedLockfile::default();
+    for (location, entry) in locations {
+        if entry["link"].as_bool() == Some(true) {
+            continue;
+        }
+        let dependencies = DEPENDENCY_FIELDS
+            .iter()
+            .filter_map(|field| entry[field].as_object())
+            .flat_map(|dependencies| dependencies.keys())
+            .filter_map(|name| {
+                resolve_dependency(location, name).map(|version| (name.clone(), version))
+            })
+            .collect();
+        match location.rsplit_once("node_modules/") {
+            Some((_, name)) => {
+                let name = entry["name"].as_str().unwrap_or(name);
+                let Some(version) = entry["version"].as_str() else {
+                    continue;
+                };
+                parsed
+                    .packages
+                    .entry(format!("{name}@{version}"))
+                    .or_insert(dependencies);
+            }
+            None => {
+                parsed.importers.insert(location.clone(), dependencies);
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Removes the peer dependency suffix from a pnpm version, e.g.
+/// `1.0.0_react@18.2.0` (lockfile v5) or `1.0.0(react@18.2.0)` (lockfile v6).
+fn strip_pnpm_peer_suffix(version: &str) -> &str {
+    version.split(['_', '(']).next().unwrap_or(version)
+}
+
+/// Parses a pnpm package key, e.g. `/@scope/name/1.0.0_peer` (lockfile v5) or
+/// `/@scope/name@1.0.0(peer)` (lockfile v6), into name and version.
+fn parse_pnpm_package_key(key: &str) -> Option<(String, String)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    let (scope, rest) = if key.starts_with('@') {
+        let (scope, rest) = key.split_once('/')?;
+        (Some(scope), rest)
+    } else {
+        (None, key)
+    };
+    let (name, version) = match (rest.find('/'), rest.find('@')) {
+        (Some(slash), at) if at.map_or(true, |at| slash < at) => rest.split_at(slash),
+        (_, Some(at)) => rest.split_at(at),
+        _ => return None,
+    };
+    let name = match scope {
+        Some(scope) => format!("{scope}/{name}"),
+        None => name.to_string(),
+    };
+    Some((name, strip_pnpm_peer_suffix(&version[1..]).to_string()))
+}
+
+/// Reads a pnpm dependency map. Values are either a version (lockfile v5) or
+/// an object with `specifier` and `version` (lockfile v6).
+fn pnpm_dependencies(entry: &YamlValue) -> BTreeMap<String, String> {
+    let mut dependencies = BTreeMap::new();
+    for field in DEPENDENCY_FIELDS {
+        let Some(map) = entry[field].as_mapping() else {
+            continue;
+        };
+        for (name, value) in map {
+            let (Some(name), Some(version)) = (
+                name.as_str(),
+                value.as_str().or_else(|| value["version"].as_str()),
+            ) else {
+                continue;
+            };
+            if version.starts_with("link:") || version.starts_with("file:") {
+                continue;
+            }
+            let version = if version.starts_with('/') {
+                // aliased dependency referencing a package key
+                match parse_pnpm_package_key(version) {
+                    Some((_, version)) => version,
+                    None => continue,
+                }
+            } else {
+                strip_pnpm_peer_suffix(version).to_string()
+            };
+            dependencies.insert(name.to_string(), version);
+        }
+    }
+    dependencies
+}
+
+/// Parses a `pnpm-lock.yaml` (lockfile v5 and v6).
+fn parse_pnpm_lockfile(content: &str) -> Result<ParsedLockfile> {
+    let lockfile: YamlValue = serde_yaml::from_str(content)?;
+    let mut parsed = ParsedLockfile::default();
+
+    if let Some(importers) = lockfile["importers"].as_mapping() {
+        for (path, importer) in importers {
+            let path = path
+                .as_str()
+                .ok_or_else(|| anyhow!("invalid importer key"))?;
+            let path = if path == "." { "" } else { path };
+            parsed
+                .importers
+                .insert(path.to_string(), pnpm_dependencies(importer));
+        }
+    } else {
+        // single project lockfiles list the dependencies at the top level
+        parsed
+            .importers
+            .insert(String::new(), pnpm_dependencies(&lockfile));
+    }
+
+    if let Some(packages) = lockfile["packages"].as_mapping() {
+        for (key, package) in packages {
+            let Some((name, version)) = key.as_str().and_then(parse_pnpm_package_key) else {
+                continue;
+            };
+            parsed
+                .packages
+                .entry(format!("{name}@{version}"))
+                .or_insert_with(|| pnpm_dependencies(package));
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parses a `yarn.lock`, either in the classic (v1) format or the YAML format
+/// used by yarn 2+.
+fn parse_yarn_lockfile(content: &str) -> Result<ParsedLockfile> {
+    if content.contains("\n__metadata:") {
+        return parse_yarn_berry_lockfile(content);
+    }
+
+    fn unquote(s: &str) -> &str {
+        s.trim().trim_matches('"')
+    }
+
+    struct Entry<'a> {
+        descriptors: Vec<&'a str>,
+        version: Option<&'a str>,
+        dependencies: Vec<(&'a str, &'a str)>,
+    }
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_dependencies = false;
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        match indent {
+            0 => {
+                let Some(descriptors) = line.strip_suffix(':') else {
+                    bail!("unexpected line in yarn.lock: {line}");
+                };
+                entries.push(Entry {
+                    descriptors: descriptors.split(',').map(unquote).collect(),
+                    version: None,
+                    dependencies: Vec::new(),
+                });
+                in_dependencies = false;
+            }
+            2 => {
+                let Some(entry) = entries.last_mut() else {
+                    bail!("unexpected indentation in yarn.lock: {line}");
+                };
+                in_dependencies = line == "dependencies:" || line == "optionalDependencies:";
+                if let Some(version) = line.strip_prefix("version ") {
+                    entry.version = Some(unquote(version));
+                }
+            }
+            _ if in_dependencies => {
+                let Some(entry) = entries.last_mut() else {
+                    bail!("unexpected indentation in yarn.lock: {line}");
+                };
+                let (name, range) = if let Some(rest) = line.strip_prefix('"') {
+                    let (name, range) = rest
+                        .split_once('"')
+                        .ok_or_else(|| anyhow!("invalid dependency in yarn.lock: {line}"))?;
+                    (name, range)
+                } else {
+                    line.split_once(' ')
+                        .ok_or_else(|| anyhow!("invalid dependency in yarn.lock: {line}"))?
+                };
+                entry.dependencies.push((name, unquote(range)));
+            }
+            _ => {}
+        }
+    }
+
+    let mut parsed = ParsedLockfile::default();
+    for entry in entries.iter() {
+        let Some(version) = entry.version else {
+            continue;
+        };
+        for descriptor in entry.descriptors.iter() {
+            parsed
+                .descriptors
+                .insert(descriptor.to_string(), version.to_string());
+        }
+    }
+    for entry in entries.iter() {
+        let (Some(version), Some((name, _))) = (
+            entry.version,
+            entry.descriptors.first().and_then(|d| split_descriptor(d)),
+        ) else {
+            continue;
+        };
+        let dependencies = entry
+            .dependencies
+            .iter()
+            .filter_map(|(name, range)| {
+                parsed
+                    .descriptors
+                    .get(&format!("{name}@{range}"))
+                    .map(|version| (name.to_string(), version.clone()))
+            })
+            .collect();
+        parsed
+            .packages
+            .entry(format!("{name}@{version}"))
+            .or_insert(dependencies);
+    }
+    Ok(parsed)
+}
+
+/// Parses a yarn 2+ lockfile. In contrast to the classic format it also
+/// contains the workspace packages as `name@workspace:path` entries.
+fn parse_yarn_berry_lockfile(content: &str) -> Result<ParsedLockfile> {
+    fn normalize_range(range: &str) -> &str {
+        range.strip_prefix("npm:").unwrap_or(range)
+    }
+
+    let lockfile: YamlValue = serde_yaml::from_str(content)?;
+    let Some(entries) = lockfile.as_mapping() else {
+        bail!("yarn.lock must be a mapping");
+    };
+    let entries = entries
+        .iter()
+        .filter_map(|(key, entry)| Some((key.as_str()?, entry)))
+        .filter(|(key, _)| *key != "__metadata")
+        .collect::<Vec<_>>();
+
+    let mut parsed = ParsedLockfile::default();
+    for (key, entry) in entries.iter() {
+        let Some(version) = entry["version"].as_str() else {
+            continue;
+        };
+        for descriptor in key.split(',').map(str::trim) {
+            if let Some((name, range)) = split_descriptor(descriptor) {
+                parsed.descriptors.insert(
+                    format!("{name}@{}", normalize_range(range)),
+                    version.to_string(),
+                );
+            }
+        }
+    }
+    for (key, entry) in entries.iter() {
+        let (Some(version), Some((name, range))) = (
+            entry["version"].as_str(),
+            key.split(',').next().and_then(|d| split_descriptor(d.trim())),
+        ) else {
+            continue;
+        };
+        let dependencies = entry["dependencies"]
+            .as_mapping()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, range)| {
+                let (name, range) = (name.as_str()?, range.as_str()?);
+                parsed
+                    .descriptors
+                    .get(&format!("{name}@{}", normalize_range(range)))
+                    .map(|version| (name.to_string(), version.clone()))
+            })
+            .collect();
+        if let Some(path) = range.strip_prefix("workspace:") {
+            let path = if path == "." { "" } else { path };
+            parsed.importers.insert(path.to_string(), dependencies);
+        } else {
+            parsed
+                .packages
+                .entry(format!("{name}@{version}"))
+                .or_insert(dependencies);
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{
+        parse_npm_lockfile, parse_pnpm_lockfile, parse_pnpm_package_key, parse_yarn_lockfile,
+        registry_package_key, select_importer,
+    };
+
+    fn deps(list: &[(&str, &str)]) -> BTreeMap<String, String> {
+        list.iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn registry_keys() {
+        assert_eq!(
+            registry_package_key("react/18.2.0/index.js").as_deref(),
+            Some("react@18.2.0")
+        );
+        assert_eq!(
+            registry_package_key("@scope/module/1.0.0/lib/a.js").as_deref(),
+            Some("@scope/module@1.0.0")
+        );
+        assert_eq!(registry_package_key("@scope/module"), None);
+    }
+
+    #[test]
+    fn pnpm_package_keys() {
+        assert_eq!(
+            parse_pnpm_package_key("/react-dom/18.2.0_react@18.2.0"),
+            Some(("react-dom".to_string(), "18.2.0".to_string()))
+        );
+        assert_eq!(
+            parse_pnpm_package_key("/@babel/core/7.20.0"),
+            Some(("@babel/core".to_string(), "7.20.0".to_string()))
+        );
+        assert_eq!(
+            parse_pnpm_package_key("/@babel/core@7.20.0(supports-color@5.5.0)"),
+            Some(("@babel/core".to_string(), "7.20.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn pnpm() {
+        let parsed = parse_pnpm_lockfile(
+            r#"
+lockfileVersion: 5.4
+
+importers:
+  .:
+    specifiers:
+      react: ^18.0.0
+    dependencies:
+      react: 18.2.0
+  packages/app:
+    specifiers:
+      react: ^17.0.0
+      react-dom: ^17.0.0
+      lib: workspace:*
+    dependencies:
+      lib: link:../lib
+      react: 17.0.2
+      react-dom: 17.0.2_react@17.0.2
+
+packages:
+  /react/18.2.0:
+    dependencies:
+      loose-envify: 1.4.0
+  /react/17.0.2:
+    dependencies:
+      loose-envify: 1.4.0
+  /react-dom/17.0.2_react@17.0.2:
+    dependencies:
+      react: 17.0.2
+  /loose-envify/1.4.0: {}
+"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.importers[""], deps(&[("react", "18.2.0")]));
+        assert_eq!(
+            parsed.importers["packages/app"],
+            deps(&[("react", "17.0.2"), ("react-dom", "17.0.2")])
+        );
+        assert_eq!(
+            parsed.packages["react-dom@17.0.2"],
+            deps(&[("react", "17.0.2")])
+        );
+        assert_eq!(
+            parsed.packages["react@18.2.0"],
+            deps(&[("loose-envify", "1.4.0")])
+        );
+        assert_eq!(
+            select_importer(&parsed.importers, "packages/app/src"),
+            Some(&parsed.importers["packages/app"])
+        );
+        assert_eq!(
+            select_importer(&parsed.importers, "packages/application"),
+            Some(&parsed.importers[""])
+        );
+    }
+
+    #[test]
+    fn npm() {
+        let parsed = parse_npm_lockfile(
+            r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "workspaces": ["packages/app"], "dependencies": { "react": "^18.0.0" } },
+    "packages/app": { "dependencies": { "react": "^17.0.0", "react-dom": "^17.0.0" } },
+    "node_modules/app": { "resolved": "packages/app", "link": true },
+    "node_modules/react": { "version": "18.2.0", "dependencies": { "loose-envify": "^1.1.0" } },
+    "node_modules/loose-envify": { "version": "1.4.0" },
+    "node_modules/react-dom": { "version": "17.0.2", "dependencies": { "react": "17.0.2" } },
+    "node_modules/react-dom/node_modules/react": { "version": "17.0.2" },
+    "packages/app/node_modules/react": { "version": "17.0.2" }
+  }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.importers[""], deps(&[("react", "18.2.0")]));
+        assert_eq!(
+            parsed.importers["packages/app"],
+            deps(&[("react", "17.0.2"), ("react-dom", "17.0.2")])
+        );
+        assert_eq!(
+            parsed.packages["react-dom@17.0.2"],
+            deps(&[("react", "17.0.2")])
+        );
+        assert_eq!(
+            parsed.packages["react@18.2.0"],
+            deps(&[("loose-envify", "1.4.0")])
+        );
+    }
+
+    #[test]
+    fn yarn() {
+        let parsed = parse_yarn_lockfile(
+            r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0":
+  version "7.18.6"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.18.6.tgz"
+  dependencies:
+    "@babel/highlight" "^7.18.6"
+
+"@babel/highlight@^7.18.6", "@babel/highlight@^7.10.4":
+  version "7.18.6"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.descriptors["@babel/highlight@^7.10.4"],
+            "7.18.6".to_string()
+        );
+        assert_eq!(
+            parsed.packages["@babel/code-frame@7.18.6"],
+            deps(&[("@babel/highlight", "7.18.6")])
+        );
+    }
+
+    #[test]
+    fn yarn_berry() {
+        let parsed = parse_yarn_lockfile(
+            r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 6
+
+"app@workspace:packages/app":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:packages/app"
+  dependencies:
+    react: ^17.0.0
+  languageName: unknown
+  linkType: soft
+
+"react@npm:^17.0.0":
+  version: 17.0.2
+  resolution: "react@npm:17.0.2"
+  languageName: node
+  linkType: hard
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.importers["packages/app"],
+            deps(&[("react", "17.0.2")])
+        );
+        assert!(parsed.packages.contains_key("react@17.0.2"));
+    }
+}
@@ -17,7 +17,7 @@ use turbo_tasks_fs::{
 };
 
 use self::{
-    exports::ExportsField,
+    exports::{ExportsField, ImportsField},
     options::{
         resolve_modules_options, ImportMapResult, ResolveInPackage, ResolveIntoPackage,
         ResolveModules, ResolveModulesOptionsVc, ResolveOptionsVc,
@@ -42,7 +42,9 @@ use crate::{
 };
 
 mod alias_map;
+pub(crate) mod data_uri;
 pub(crate) mod exports;
+pub(crate) mod lockfile;
 pub mod node;
 pub mod options;
 pub mod origin;
@@ -416,6 +418,53 @@ async fn exports_field(
     }
 }
 
+#[turbo_tasks::value(shared)]
+enum ImportsFieldResult {
+    Some(
+        #[turbo_tasks(debug_ignore, trace_ignore)] ImportsField,
+        FileSystemPathVc,
+        Vec<AssetReferenceVc>,
+    ),
+    /// There is no usable "imports" field. The references point to the files
+    /// which would need to change for one to appear.
+    None(Vec<AssetReferenceVc>),
+}
+
+/// Reads the "imports" field of the package.json closest to `context`.
+#[turbo_tasks::function]
+async fn imports_field(context: FileSystemPathVc) -> Result<ImportsFieldResultVc> {
+    let (package_json_path, mut refs) = match &*find_context_file(context, package_json()).await? {
+        FindContextFileResult::Found(package_json_path, refs) => (*package_json_path, refs.clone()),
+        FindContextFileResult::NotFound(refs) => {
+            return Ok(ImportsFieldResult::None(refs.clone()).cell());
+        }
+    };
+    // the package.json affects resolving even when it has no "imports" field
+    refs.push(AffectingResolvingAssetReferenceVc::new(package_json_path).into());
+    let FileJsonContent::Content(package_json) = &*package_json_path.read_json().await? else {
+        return Ok(ImportsFieldResult::None(refs).cell());
+    };
+    let field_value = &package_json["imports"];
+    if let serde_json::Value::Null = field_value {
+        return Ok(ImportsFieldResult::None(refs).cell());
+    }
+    let imports_field: Result<ImportsField> = field_value.try_into();
+    match imports_field {
+        Ok(imports_field) => {
+            Ok(ImportsFieldResult::Some(imports_field, package_json_path, refs).cell())
+        }
+        Err(err) => {
+            let issue: PackageJsonIssueVc = PackageJsonIssue {
+                path: package_json_path,
+                error_message: err.to_string(),
+            }
+            .into();
+            issue.as_issue().emit();
+            Ok(ImportsFieldResult::None(refs).cell())
+        }
+    }
+}
+
 #[turbo_tasks::function]
 pub fn package_json() -> StringsVc {
     StringsVc::cell(vec!["package.json".to_string()])
@@ -508,7 +557,20 @@ async fn find_package(
                     packages.push(package_dir.resolve().await?);
                 }
             }
-            ResolveModules::Registry(_, _) => todo!(),
+            ResolveModules::Registry(registry, locked_versions) => {
+                references.push(
+                    AffectingResolvingAssetReferenceVc::new(locked_versions.await?.lockfile).into(),
+                );
+                if let Some(version) = &*locked_versions
+                    .select_version(*registry, context, package_name.clone())
+                    .await?
+                {
+                    let package_dir = registry.join(&format!("{package_name}/{version}"));
+                    if dir_exists(package_dir, &mut references).await?.is_some() {
+                        packages.push(package_dir.resolve().await?);
+                    }
+                }
+            }
         }
     }
     Ok(FindPackageResultVc::cell(FindPackageResult {
@@ -804,17 +866,43 @@ async fn resolve_internal(
             ResolveResult::unresolveable().into()
         }
         Request::Empty => ResolveResult::unresolveable().into(),
-        Request::PackageInternal { path: _ } => {
-            let issue: ResolvingIssueVc = ResolvingIssue {
-                request_type: "package internal import: not implemented yet".to_string(),
-                request,
-                context,
-                resolve_options: options,
-                error_message: Some("package internal imports are not implemented yet".to_string()),
+        Request::PackageInternal { path } => {
+            let imports_field_options =
+                options_value
+                    .in_package
+                    .iter()
+                    .find_map(|resolve_in_package| match resolve_in_package {
+                        ResolveInPackage::ImportsField {
+                            conditions,
+                            unspecified_conditions,
+                        } => Some((conditions, unspecified_conditions)),
+                        _ => None,
+                    });
+            if let Some((conditions, unspecified_conditions)) = imports_field_options {
+                resolve_package_internal_with_imports_field(
+                    context,
+                    request,
+                    options,
+                    path,
+                    conditions,
+                    unspecified_conditions,
+                )
+                .await?
+            } else {
+                let issue: ResolvingIssueVc = ResolvingIssue {
+                    request_type: "package internal import: not enabled".to_string(),
+                    request,
+                    context,
+                    resolve_options: options,
+                    error_message: Some(
+                        "package internal imports are not enabled in the resolve options"
+                            .to_string(),
+                    ),
+                }
+                .into();
+                issue.as_issue().emit();
+                ResolveResult::unresolveable().into()
             }
-            .into();
-            issue.as_issue().emit();
-            ResolveResult::unresolveable().into()
         }
         Request::Uri {
             protocol,
@@ -823,6 +911,33 @@ async fn resolve_internal(
             format!("{}{}", protocol, remainder),
         ))
         .into(),
+        Request::DataUri {
+            media_type,
+            encoding,
+            data,
+        } => {
+            let asset = data_uri::data_uri_asset(
+                context,
+                media_type.clone(),
+                encoding.clone(),
+                data.clone(),
+            );
+            match asset.resolve().await {
+                Ok(asset) => ResolveResult::asset(asset).into(),
+                Err(err) => {
+                    let issue: ResolvingIssueVc = ResolvingIssue {
+                        request_type: "data uri".to_string(),
+                        request,
+                        context,
+                        resolve_options: options,
+                        error_message: Some(format!("{err:#}")),
+                    }
+                    .into();
+                    issue.as_issue().emit();
+                    ResolveResult::unresolveable().into()
+                }
+            }
+        }
         Request::Unknown { path } => {
             let issue: ResolvingIssueVc = ResolvingIssue {
                 request_type: format!("unknown import: `{}`", path),
@@ -955,6 +1070,7 @@ async fn resolve_module_request(
             );
         }
         if could_match_others {
+            let mut has_exports_field = false;
             for resolve_into_package in options_value.into_package.iter() {
                 match resolve_into_package {
                     ResolveIntoPackage::Default(_) | ResolveIntoPackage::MainField(_) => {
@@ -990,19 +1106,38 @@ async fn resolve_module_request(
                                     unspecified_conditions,
                                 )?);
                             } else {
-                                todo!("pattern into an exports field is not implemented yet");
+                                let mut pattern = path.clone();
+                                pattern.push_front(".".to_string().into());
+                                pattern.normalize();
+                                results.push(
+                                    handle_exports_field_pattern(
+                                        *package_path,
+                                        package_json_path,
+                                        options,
+                                        exports_field,
+                                        &pattern,
+                                        conditions,
+                                        unspecified_conditions,
+                                    )
+                                    .await?,
+                                );
                             }
                             // other options do not apply anymore when an exports
                             // field exist
+                            has_exports_field = true;
                             break;
                         }
                     }
                 }
             }
-            let mut new_pat = path.clone();
-            new_pat.push_front(".".to_string().into());
-            let relative = RequestVc::relative(Value::new(new_pat), true);
-            results.push(resolve_internal(*package_path, relative, options));
+            // the exports field encapsulates the package, so subpaths which it
+            // doesn't export can't be requested directly
+            if !has_exports_field {
+                let mut new_pat = path.clone();
+                new_pat.push_front(".".to_string().into());
+                let relative = RequestVc::relative(Value::new(new_pat), true);
+                results.push(resolve_internal(*package_path, relative, options));
+            }
         }
     }
 
@@ -1143,6 +1278,9 @@ async fn resolved(
                     }
                 }
             }
+            ResolveInPackage::ImportsField { .. } => {
+                // only applies to package internal requests
+            }
         }
     }
 
@@ -1171,21 +1309,16 @@ async fn resolved(
     .into())
 }
 
-fn handle_exports_field(
-    package_path: FileSystemPathVc,
-    package_json: FileSystemPathVc,
-    options: ResolveOptionsVc,
-    exports_field: &ExportsField,
-    path: &str,
+/// Collects the results of the matching [ExportsValue]s according to the
+/// conditions, without duplicates. Shared by the "exports" and "imports"
+/// fields.
+fn conditional_results<'a>(
+    values: &'a [Cow<'_, ExportsValue>],
     conditions: &BTreeMap<String, ConditionValue>,
     unspecified_conditions: &ConditionValue,
-) -> Result<ResolveResultVc> {
+) -> Vec<&'a str> {
     let mut results = Vec::new();
     let mut conditions_state = HashMap::new();
-    let values = exports_field
-        .lookup(path)
-        .map(AliasMatch::try_into_self)
-        .collect::<Result<Vec<Cow<'_, ExportsValue>>>>()?;
     for value in values.iter() {
         if value.add_results(
             conditions,
@@ -1196,10 +1329,25 @@ fn handle_exports_field(
             break;
         }
     }
-    {
-        let mut duplicates_set = HashSet::new();
-        results.retain(|item| duplicates_set.insert(*item));
-    }
+    let mut duplicates_set = HashSet::new();
+    results.retain(|item| duplicates_set.insert(*item));
+    results
+}
+
+fn handle_exports_field(
+    package_path: FileSystemPathVc,
+    package_json: FileSystemPathVc,
+    options: ResolveOptionsVc,
+    exports_field: &ExportsField,
+    path: &str,
+    conditions: &BTreeMap<String, ConditionValue>,
+    unspecified_conditions: &ConditionValue,
+) -> Result<ResolveResultVc> {
+    let values = exports_field
+        .lookup(path)
+        .map(AliasMatch::try_into_self)
+        .collect::<Result<Vec<Cow<'_, ExportsValue>>>>()?;
+    let results = conditional_results(&values, conditions, unspecified_conditions);
     let mut resolved_results = Vec::new();
     for path in results {
         if let Some(path) = normalize_path(path) {
@@ -1214,6 +1362,207 @@ fn handle_exports_field(
     ))
 }
 
+/// Resolves a dynamic subpath `pattern` (e.g. `./locales/${lang}`) against all
+/// keys of an "exports" field. Wildcard keys substitute the part of the pattern
+/// they capture into their targets, which are then resolved as patterns.
+/// Subpaths which belong to a more specific key (e.g. `"./internal/*": null`
+/// next to `"./*"`) are left to that key.
+async fn handle_exports_field_pattern(
+    package_path: FileSystemPathVc,
+    package_json: FileSystemPathVc,
+    options: ResolveOptionsVc,
+    exports_field: &ExportsField,
+    pattern: &Pattern,
+    conditions: &BTreeMap<String, ConditionValue>,
+    unspecified_conditions: &ConditionValue,
+) -> Result<ResolveResultVc> {
+    let mut resolved_results = Vec::new();
+    for (key, value) in exports_field {
+        let values = [Cow::Borrowed(value)];
+        let results = conditional_results(&values, conditions, unspecified_conditions);
+        match key {
+            AliasPattern::Exact(key) => {
+                if !pattern.is_match(&key) {
+                    continue;
+                }
+                for result in results {
+                    if let Some(path) = normalize_path(result) {
+                        let request =
+                            RequestVc::relative(Value::new(format!("./{}", path).into()), false);
+                        resolved_results.push(resolve_internal(package_path, request, options));
+                    }
+                }
+            }
+            AliasPattern::Wildcard { prefix, suffix } => {
+                let Some(capture) = pattern
+                    .strip_prefix(&prefix)
+                    .and_then(|pattern| pattern.strip_suffix(&suffix)) else {
+                    continue;
+                };
+                for result in results {
+                    let mut target = Pattern::Constant(String::new());
+                    for (index, part) in result.split('*').enumerate() {
+                        if index > 0 {
+                            target.push(capture.clone());
+                        }
+                        target.push(part.to_string().into());
+                    }
+                    target.normalize();
+                    let request = RequestVc::relative(Value::new(target), false);
+                    let resolved = resolve_internal(package_path, request, options);
+                    resolved_results.push(
+                        exclude_shadowed_subpaths(
+                            resolved,
+                            package_path,
+                            exports_field,
+                            (&prefix, &suffix),
+                            result,
+                        )
+                        .await?,
+                    );
+                }
+            }
+        }
+    }
+    Ok(merge_results_with_references(
+        resolved_results,
+        vec![AffectingResolvingAssetReferenceVc::new(package_json).into()],
+    ))
+}
+
+/// Removes the assets of a wildcard key's `target` whose subpath is matched by
+/// a more specific key of the "exports" field, as that key takes precedence.
+/// The subpath of an asset is recovered from its path by reversing the
+/// substitution into `target`, which is only possible for a single `*`.
+async fn exclude_shadowed_subpaths(
+    resolved: ResolveResultVc,
+    package_path: FileSystemPathVc,
+    exports_field: &ExportsField,
+    (key_prefix, key_suffix): (&str, &str),
+    target: &str,
+) -> Result<ResolveResultVc> {
+    let Some((target_prefix, target_suffix)) = target.split_once('*') else {
+        return Ok(resolved);
+    };
+    if target_suffix.contains('*') {
+        return Ok(resolved);
+    }
+    let target_prefix = target_prefix.strip_prefix("./").unwrap_or(target_prefix);
+    let package_path = package_path.await?;
+    let resolved = resolved.await?;
+    let mut primary = Vec::with_capacity(resolved.primary.len());
+    for result in resolved.primary.iter() {
+        if let PrimaryResolveResult::Asset(asset) = result {
+            let asset_path = asset.ident().path().await?;
+            let capture = package_path.get_path_to(&asset_path).and_then(|path| {
+                path.strip_prefix(target_prefix)?
+                    .strip_suffix(target_suffix)
+            });
+            if let Some(capture) = capture {
+                let subpath = format!("{key_prefix}{capture}{key_suffix}");
+                if is_shadowed_by_more_specific_key(exports_field, key_prefix, key_suffix, &subpath)
+                {
+                    continue;
+                }
+            }
+        }
+        primary.push(result.clone());
+    }
+    Ok(ResolveResult {
+        primary,
+        references: resolved.references.clone(),
+    }
+    .cell())
+}
+
+/// Checks if `subpath` is matched by a key of the "exports" field which is
+/// more specific than the wildcard key `key_prefix*key_suffix`, using the
+/// ordering of node.js: exact keys first, then longer prefixes, then longer
+/// suffixes.
+fn is_shadowed_by_more_specific_key(
+    exports_field: &ExportsField,
+    key_prefix: &str,
+    key_suffix: &str,
+    subpath: &str,
+) -> bool {
+    exports_field.into_iter().any(|(key, _)| match key {
+        AliasPattern::Exact(key) => key == subpath,
+        AliasPattern::Wildcard { prefix, suffix } => {
+            let more_specific = prefix.len() > key_prefix.len()
+                || (prefix.len() == key_prefix.len() && suffix.len() > key_suffix.len());
+            more_specific
+                && subpath.len() >= prefix.len() + suffix.len()
+                && subpath.starts_with(&prefix)
+                && subpath.ends_with(&suffix)
+        }
+    })
+}
+
+/// Resolves a `#internal` request using the "imports" field of the closest
+/// package.json.
+async fn resolve_package_internal_with_imports_field(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    resolve_options: ResolveOptionsVc,
+    pattern: &Pattern,
+    conditions: &BTreeMap<String, ConditionValue>,
+    unspecified_conditions: &ConditionValue,
+) -> Result<ResolveResultVc> {
+    let Pattern::Constant(specifier) = pattern else {
+        let issue: ResolvingIssueVc = ResolvingIssue {
+            request_type: "package internal import: dynamic".to_string(),
+            request,
+            context,
+            resolve_options,
+            error_message: Some(
+                "package internal imports can only be resolved from constant requests"
+                    .to_string(),
+            ),
+        }
+        .into();
+        issue.as_issue().emit();
+        return Ok(ResolveResult::unresolveable().into());
+    };
+    // https://github.com/nodejs/node/blob/1b177932/lib/internal/modules/esm/resolve.js#L615-L619
+    if specifier == "#" || specifier.starts_with("#/") || specifier.ends_with('/') {
+        return Ok(ResolveResult::unresolveable().into());
+    }
+
+    let imports_result = imports_field(context).await?;
+    let (imports, package_json_path, refs) = match &*imports_result {
+        ImportsFieldResult::Some(imports, package_json_path, refs) => {
+            (imports, package_json_path, refs)
+        }
+        ImportsFieldResult::None(refs) => {
+            return Ok(ResolveResult::unresolveable_with_references(refs.clone()).into());
+        }
+    };
+    let package_path = package_json_path.parent();
+
+    let values = imports
+        .lookup(specifier)
+        .map(AliasMatch::try_into_self)
+        .collect::<Result<Vec<Cow<'_, ExportsValue>>>>()?;
+    let results = conditional_results(&values, conditions, unspecified_conditions);
+    let mut resolved_results = Vec::new();
+    for result in results {
+        if result.starts_with("./") {
+            if let Some(path) = normalize_path(result) {
+                let request = RequestVc::relative(Value::new(format!("./{}", path).into()), false);
+                resolved_results.push(resolve_internal(package_path, request, resolve_options));
+            }
+        } else if !result.starts_with('#') && !result.starts_with('/') {
+            // bare specifiers resolve to other packages from the package directory
+            let request = RequestVc::parse(Value::new(result.to_string().into()));
+            resolved_results.push(resolve_internal(package_path, request, resolve_options));
+        }
+    }
+    Ok(merge_results_with_references(
+        resolved_results,
+        refs.clone(),
+    ))
+}
+
 #[turbo_tasks::value]
 pub struct AffectingResolvingAssetReference {
     path: FileSystemPathVc,
@@ -8,16 +8,13 @@ use turbo_tasks::{
 };
 use turbo_tasks_fs::{glob::GlobVc, FileSystemPathVc};
 
+pub use super::lockfile::{LockedVersions, LockedVersionsVc};
 use super::{
     alias_map::{AliasMap, AliasTemplate},
     AliasPattern, PrimaryResolveResult, ResolveResult, ResolveResultVc,
 };
 use crate::resolve::{parse::RequestVc, plugin::ResolvePluginVc};
 
-#[turbo_tasks::value(shared)]
-#[derive(Hash, Debug)]
-pub struct LockedVersions {}
-
 /// A location where to resolve modules.
 #[derive(
     TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, ValueDebugFormat,
@@ -77,6 +74,13 @@ pub enum ResolveIntoPackage {
 pub enum ResolveInPackage {
     /// Using a alias field which allows to map requests
     AliasField(String),
+    /// Using the [imports] field to resolve `#internal` requests.
+    ///
+    /// [imports]: https://nodejs.org/api/packages.html#imports
+    ImportsField {
+        conditions: BTreeMap<String, ConditionValue>,
+        unspecified_conditions: ConditionValue,
+    },
 }
 
 #[turbo_tasks::value(shared)]
@@ -36,6 +36,11 @@ pub enum Request {
         protocol: String,
         remainder: String,
     },
+    DataUri {
+        media_type: String,
+        encoding: String,
+        data: String,
+    },
     Unknown {
         path: Pattern,
     },
@@ -75,6 +80,11 @@ impl Request {
                 protocol,
                 remainder,
             } => format!("{protocol}{remainder}"),
+            Request::DataUri {
+                media_type,
+                encoding,
+                data,
+            } => format_data_uri(media_type, encoding, data),
             Request::Unknown {
                 path: Pattern::Constant(path),
             } => path.to_string(),
@@ -111,7 +121,11 @@ impl Request {
                     }
                     if let Some(caps) = URI_PATH.captures(r) {
                         if let (Some(protocol), Some(remainder)) = (caps.get(1), caps.get(2)) {
-                            // TODO data uri
+                            if protocol.as_str() == "data:" {
+                                if let Some(request) = Request::parse_data_uri(remainder.as_str()) {
+                                    return request;
+                                }
+                            }
                             return Request::Uri {
                                 protocol: protocol.as_str().to_string(),
                                 remainder: remainder.as_str().to_string(),
@@ -164,7 +178,7 @@ impl Request {
                         Request::PackageInternal { path } => {
                             path.extend(iter);
                         }
-                        Request::Uri { .. } => {
+                        Request::Uri { .. } | Request::DataUri { .. } => {
                             result = Request::Dynamic;
                         }
                         Request::Unknown { path } => {
@@ -186,6 +200,29 @@ impl Request {
             },
         }
     }
+
+    /// Parses the part of a `data:` URI after the protocol, which has the form
+    /// `[<media-type>][;base64],<data>`.
+    fn parse_data_uri(remainder: &str) -> Option<Self> {
+        let (meta, data) = remainder.split_once(',')?;
+        let (media_type, encoding) = match meta.strip_suffix(";base64") {
+            Some(media_type) => (media_type, "base64"),
+            None => (meta, ""),
+        };
+        Some(Request::DataUri {
+            media_type: media_type.to_string(),
+            encoding: encoding.to_string(),
+            data: data.to_string(),
+        })
+    }
+}
+
+fn format_data_uri(media_type: &str, encoding: &str, data: &str) -> String {
+    if encoding.is_empty() {
+        format!("data:{media_type},{data}")
+    } else {
+        format!("data:{media_type};{encoding},{data}")
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -235,6 +272,7 @@ impl RequestVc {
             | Request::Windows { .. }
             | Request::Relative { .. }
             | Request::Uri { .. }
+            | Request::DataUri { .. }
             | Request::Dynamic => self,
             Request::Module {
                 module,
@@ -312,6 +350,21 @@ impl ValueToString for Request {
                 protocol,
                 remainder,
             } => format!("uri \"{protocol}\" \"{remainder}\""),
+            Request::DataUri {
+                media_type,
+                encoding,
+                data,
+            } => {
+                let data = if data.len() > 32 {
+                    format!("{}...", data.chars().take(32).collect::<String>())
+                } else {
+                    data.clone()
+                };
+                format!(
+                    "data uri \"{}\"",
+                    format_data_uri(media_type, encoding, &data)
+                )
+            }
             Request::Unknown { path } => format!("unknown {path}"),
             Request::Dynamic => "dynamic".to_string(),
             Request::Alternatives { requests } => {
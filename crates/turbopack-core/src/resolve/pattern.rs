@@ -325,6 +325,102 @@ impl Pattern {
         }
     }
 
+    /// Removes a constant `prefix` from the start of the pattern. Returns
+    /// `None` when the pattern can't start with the prefix. Dynamic parts are
+    /// assumed to be able to match the remaining prefix.
+    ///
+    /// The pattern must be normalized.
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Pattern> {
+        if let Pattern::Alternatives(list) = self {
+            let list = list
+                .iter()
+                .filter_map(|alt| alt.strip_prefix(prefix))
+                .collect::<Vec<_>>();
+            return if list.is_empty() {
+                None
+            } else {
+                Some(Pattern::alternatives(list))
+            };
+        }
+        let mut parts = match self {
+            Pattern::Concatenation(list) => list.clone(),
+            pattern => vec![pattern.clone()],
+        };
+        let mut prefix = prefix;
+        let mut index = 0;
+        while !prefix.is_empty() {
+            match parts.get_mut(index)? {
+                Pattern::Constant(c) => {
+                    if let Some(rest) = c.strip_prefix(prefix) {
+                        *c = rest.to_string();
+                        prefix = "";
+                    } else if let Some(rest) = prefix.strip_prefix(c.as_str()) {
+                        prefix = rest;
+                        index += 1;
+                    } else {
+                        return None;
+                    }
+                }
+                _ => break,
+            }
+        }
+        parts.drain(..index);
+        Some(Pattern::from_parts(parts))
+    }
+
+    /// Removes a constant `suffix` from the end of the pattern. Returns `None`
+    /// when the pattern can't end with the suffix. Dynamic parts are assumed to
+    /// be able to match the remaining suffix.
+    ///
+    /// The pattern must be normalized.
+    pub fn strip_suffix(&self, suffix: &str) -> Option<Pattern> {
+        if let Pattern::Alternatives(list) = self {
+            let list = list
+                .iter()
+                .filter_map(|alt| alt.strip_suffix(suffix))
+                .collect::<Vec<_>>();
+            return if list.is_empty() {
+                None
+            } else {
+                Some(Pattern::alternatives(list))
+            };
+        }
+        let mut parts = match self {
+            Pattern::Concatenation(list) => list.clone(),
+            pattern => vec![pattern.clone()],
+        };
+        let mut suffix = suffix;
+        let mut len = parts.len();
+        while !suffix.is_empty() {
+            match parts.get_mut(len.checked_sub(1)?)? {
+                Pattern::Constant(c) => {
+                    if let Some(rest) = c.strip_suffix(suffix) {
+                        *c = rest.to_string();
+                        suffix = "";
+                    } else if let Some(rest) = suffix.strip_suffix(c.as_str()) {
+                        suffix = rest;
+                        len -= 1;
+                    } else {
+                        return None;
+                    }
+                }
+                _ => break,
+            }
+        }
+        parts.truncate(len);
+        Some(Pattern::from_parts(parts))
+    }
+
+    /// Creates a normalized pattern from concatenated parts.
+    fn from_parts(parts: Vec<Pattern>) -> Pattern {
+        let mut pattern = Pattern::Concatenation(parts);
+        pattern.normalize();
+        match pattern {
+            Pattern::Concatenation(list) if list.is_empty() => Pattern::Constant(String::new()),
+            pattern => pattern,
+        }
+    }
+
     pub fn is_match(&self, value: &str) -> bool {
         if let Pattern::Alternatives(list) = self {
             list.iter()
@@ -999,6 +1095,45 @@ mod tests {
         assert!(pat.could_match("dir/inner/file.d.ts.map"));
     }
 
+    #[test]
+    fn strip_prefix_and_suffix() {
+        let pat = Pattern::Concatenation(vec![
+            Pattern::Constant("./locales/".to_string()),
+            Pattern::Dynamic,
+            Pattern::Constant(".json".to_string()),
+        ]);
+        assert_eq!(
+            pat.strip_prefix("./locales/")
+                .and_then(|p| p.strip_suffix(".json")),
+            Some(Pattern::Dynamic)
+        );
+        assert_eq!(
+            pat.strip_prefix("./loc"),
+            Some(Pattern::Concatenation(vec![
+                Pattern::Constant("ales/".to_string()),
+                Pattern::Dynamic,
+                Pattern::Constant(".json".to_string()),
+            ]))
+        );
+        assert_eq!(
+            pat.strip_prefix("./locales/en/"),
+            Some(Pattern::Concatenation(vec![
+                Pattern::Dynamic,
+                Pattern::Constant(".json".to_string()),
+            ]))
+        );
+        assert_eq!(pat.strip_prefix("./other/"), None);
+        assert_eq!(pat.strip_suffix(".js"), None);
+        assert_eq!(
+            Pattern::Constant("./index.js".to_string()).strip_prefix("./index.js"),
+            Some(Pattern::Constant(String::new()))
+        );
+        assert_eq!(
+            Pattern::Constant("./index".to_string()).strip_prefix("./index.js"),
+            None
+        );
+    }
+
     #[rstest]
     #[case::dynamic(Pattern::Dynamic, "feijf", None)]
     #[case::dynamic_concat(
@@ -1,9 +1,9 @@
 use std::{io::Write, ops::Deref, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use sourcemap::SourceMap as CrateMap;
+use sourcemap::{SourceMap as CrateMap, SourceMapBuilder};
 use turbo_tasks::TryJoinIterExt;
 use turbo_tasks_fs::rope::{Rope, RopeBuilder, RopeVc};
 
@@ -172,6 +172,45 @@ impl SourceMapVc {
         Ok(rope.cell())
     }
 
+    /// Flattens a (possibly nested) sectioned source map into a single regular
+    /// source map covering the entire file.
+    #[turbo_tasks::function]
+    pub async fn flatten(self) -> Result<SourceMapVc> {
+        let this = self.await?;
+        let sections = match &*this {
+            SourceMap::Regular(_) => return Ok(self),
+            SourceMap::Sectioned(s) => &s.sections,
+        };
+
+        let maps =
+            sections
+                .iter()
+                .map(|SourceMapSection { offset, map }| async move {
+                    Ok((*offset, map.flatten().await?))
+                })
+                .try_join()
+                .await?;
+        let maps = maps
+            .iter()
+            .map(|(offset, map)| Ok((*offset, regular_map(map)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SourceMapVc::new_regular(flatten_sections(&maps)))
+    }
+
+    /// Chains this source map with the source map of its input. This is used
+    /// when the code this map was generated from is itself generated code,
+    /// e.g. when minifying a chunk. The resulting map points from the final
+    /// code straight to the original sources.
+    #[turbo_tasks::function]
+    pub async fn chain(self, input: SourceMapVc) -> Result<SourceMapVc> {
+        let this = self.flatten().await?;
+        let input = input.flatten().await?;
+        let this = regular_map(&this)?;
+        let input = regular_map(&input)?;
+
+        Ok(SourceMapVc::new_regular(chain_maps(this, input)))
+    }
+
     /// Traces a generated line/column into an mapping token representing either
     /// synthetic code or user-authored original code.
     #[turbo_tasks::function]
@@ -225,6 +264,94 @@ impl SourceMapVc {
     }
 }
 
+#[turbo_tasks::value_impl]
+impl GenerateSourceMap for SourceMap {
+    #[turbo_tasks::function]
+    fn generate_source_map(self_vc: SourceMapVc) -> SourceMapVc {
+        self_vc
+    }
+}
+
+fn regular_map(map: &SourceMap) -> Result<&CrateMap> {
+    match map {
+        SourceMap::Regular(map) => {
+            let map: &CrateMap = map;
+            Ok(map)
+        }
+        SourceMap::Sectioned(_) => bail!("expected a flattened source map"),
+    }
+}
+
+/// Merges the maps of sections starting at the given offsets into a single
+/// map.
+fn flatten_sections(sections: &[(SourcePos, &CrateMap)]) -> CrateMap {
+    let mut builder = SourceMapBuilder::new(None);
+    for (offset, map) in sections {
+        for token in map.tokens() {
+            let dst_line = token.get_dst_line();
+            // Only the first line of a section is shifted by the column offset.
+            let dst_col = if dst_line == 0 {
+                token.get_dst_col() + offset.column as u32
+            } else {
+                token.get_dst_col()
+            };
+            let dst_line = dst_line + offset.line as u32;
+            add_token(&mut builder, map, dst_line, dst_col, token);
+        }
+    }
+    builder.into_sourcemap()
+}
+
+/// Maps the tokens of `map` through `input`, the map of the code `map` has
+/// been generated from.
+fn chain_maps(map: &CrateMap, input: &CrateMap) -> CrateMap {
+    let mut builder = SourceMapBuilder::new(None);
+    for token in map.tokens() {
+        let dst_line = token.get_dst_line();
+        let dst_col = token.get_dst_col();
+        let original = token
+            .has_source()
+            .then(|| input.lookup_token(token.get_src_line(), token.get_src_col()))
+            .flatten()
+            // The sourcemap crate incorrectly returns a previous line's token when there's
+            // not a match on this line.
+            .filter(|t| t.get_dst_line() == token.get_src_line());
+        match original {
+            Some(original) => add_token(&mut builder, input, dst_line, dst_col, original),
+            None => {
+                builder.add(dst_line, dst_col, 0, 0, None, None);
+            }
+        }
+    }
+    builder.into_sourcemap()
+}
+
+/// Copies the original position of `token`, which belongs to `map`, into
+/// `builder` at the given generated position.
+fn add_token(
+    builder: &mut SourceMapBuilder,
+    map: &CrateMap,
+    dst_line: u32,
+    dst_col: u32,
+    token: sourcemap::Token<'_>,
+) {
+    if !token.has_source() {
+        builder.add(dst_line, dst_col, 0, 0, None, None);
+        return;
+    }
+    let raw = builder.add(
+        dst_line,
+        dst_col,
+        token.get_src_line(),
+        token.get_src_col(),
+        token.get_source(),
+        token.get_name(),
+    );
+    if builder.get_source_contents(raw.src_id).is_none() {
+        builder.set_source_contents(raw.src_id, map.get_source_contents(token.get_src_id()));
+    }
+}
+
 /// A regular source map covers an entire file.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RegularSourceMap(Arc<CrateMapWrapper>);
@@ -313,3 +440,65 @@ impl SourceMapSection {
         Self { offset, map }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sourcemap::{SourceMap as CrateMap, SourceMapBuilder};
+
+    use super::{chain_maps, flatten_sections};
+    use crate::source_pos::SourcePos;
+
+    /// Builds a map from `(dst_line, dst_col, src_line, src_col)` tokens in
+    /// `source`.
+    fn map(source: &str, tokens: &[(u32, u32, u32, u32)]) -> CrateMap {
+        let mut builder = SourceMapBuilder::new(None);
+        for &(dst_line, dst_col, src_line, src_col) in tokens {
+            builder.add(dst_line, dst_col, src_line, src_col, Some(source), None);
+        }
+        builder.into_sourcemap()
+    }
+
+    fn original(map: &CrateMap, line: u32, col: u32) -> Option<(String, u32, u32)> {
+        let token = map.lookup_token(line, col)?;
+        Some((
+            token.get_source()?.to_string(),
+            token.get_src_line(),
+            token.get_src_col(),
+        ))
+    }
+
+    #[test]
+    fn flatten_offsets_sections() {
+        let a = map("a.js", &[(0, 0, 0, 0)]);
+        let b = map("b.js", &[(0, 0, 1, 2), (1, 3, 2, 0)]);
+        let flattened = flatten_sections(&[
+            (SourcePos { line: 0, column: 0 }, &a),
+            (SourcePos { line: 2, column: 4 }, &b),
+        ]);
+
+        assert_eq!(original(&flattened, 0, 0), Some(("a.js".to_string(), 0, 0)));
+        // Only the first line of a section is shifted by the column offset.
+        assert_eq!(original(&flattened, 2, 4), Some(("b.js".to_string(), 1, 2)));
+        assert_eq!(original(&flattened, 3, 3), Some(("b.js".to_string(), 2, 0)));
+    }
+
+    #[test]
+    fn chain_maps_through_input() {
+        // The input code was generated from orig.js, and the code of `minified`
+        // from the input code.
+        let input = map("orig.js", &[(5, 0, 10, 2), (5, 7, 11, 0)]);
+        let minified = map("input.js", &[(0, 0, 5, 0), (0, 3, 5, 7), (0, 9, 1, 0)]);
+        let chained = chain_maps(&minified, &input);
+
+        assert_eq!(
+            original(&chained, 0, 0),
+            Some(("orig.js".to_string(), 10, 2))
+        );
+        assert_eq!(
+            original(&chained, 0, 3),
+            Some(("orig.js".to_string(), 11, 0))
+        );
+        // The input has no token on line 1, so the code is synthetic.
+        assert_eq!(original(&chained, 0, 9), None);
+    }
+}
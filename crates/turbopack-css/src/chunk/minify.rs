@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use sourcemap::SourceMap as CrateMap;
+use swc_core::{
+    common::{FileName, SourceMap},
+    css::{
+        ast::Stylesheet,
+        codegen::{
+            writer::basic::{BasicCssWriter, BasicCssWriterConfig},
+            CodeGenerator, CodegenConfig, Emit,
+        },
+        parser::{parse_file, parser::ParserConfig},
+        prefixer::{options::Options, prefixer},
+        visit::VisitMutWith,
+    },
+    ecma::preset_env::Targets,
+};
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{rope::Rope, FileSystemPathVc};
+use turbopack_core::{
+    code_builder::{CodeBuilder, CodeVc},
+    environment::EnvironmentVc,
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+    source_map::{GenerateSourceMap, SourceMapVc},
+};
+
+/// Minifies the code of a CSS chunk and adds the vendor prefixes needed by
+/// the browsers of the environment. The source map of the minified code is
+/// chained with the source map of the input code, so it still points to the
+/// original sources.
+///
+/// When the code can't be parsed without errors, it's emitted unminified and
+/// a warning is reported, as the parser drops the rules it can't parse.
+#[turbo_tasks::function]
+pub async fn minify(
+    path: FileSystemPathVc,
+    code: CodeVc,
+    environment: EnvironmentVc,
+) -> Result<CodeVc> {
+    let path_value = path.await?;
+    let original_map = code.generate_source_map();
+    let code_value = code.await?;
+
+    // Only browser environments know which prefixes are needed.
+    let prefixer_options = if environment.browserslist_query().await?.is_some() {
+        let versions = environment.runtime_versions().await?;
+        Some(Options {
+            env: Some(Targets::Versions(*versions)),
+        })
+    } else {
+        None
+    };
+
+    let source = code_value.source_code().to_str()?.into_owned();
+    let (output, map) = match minify_source(&path_value.path, source, prefixer_options) {
+        Ok(minified) => minified,
+        Err(err) => {
+            CssMinifyIssue {
+                path,
+                message: StringVc::cell(format!("{err:#}")),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            return Ok(code);
+        }
+    };
+
+    let map = code_value
+        .has_source_map()
+        .then(|| SourceMapVc::new_regular(map).chain(original_map).into());
+    let mut builder = CodeBuilder::default();
+    builder.push_source(&Rope::from(output), map);
+    Ok(builder.build().cell())
+}
+
+/// Minifies `source` and adds vendor prefixes when `prefixer_options` are
+/// given. Returns the minified code and its source map, which points into
+/// `source`. Fails when `source` contains any syntax error.
+fn minify_source(
+    path: &str,
+    source: String,
+    prefixer_options: Option<Options>,
+) -> Result<(String, CrateMap)> {
+    let cm = Arc::new(SourceMap::default());
+    let fm = cm.new_source_file(FileName::Custom(path.to_string()), source);
+
+    let mut errors = Vec::new();
+    let stylesheet = parse_file::<Stylesheet>(&fm, ParserConfig::default(), &mut errors);
+    let mut stylesheet = match stylesheet {
+        Ok(stylesheet) if errors.is_empty() => stylesheet,
+        Ok(_) => bail!(
+            "failed to parse {path}: {}",
+            errors
+                .iter()
+                .map(|err| err.message())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(err) => bail!("failed to parse {path}: {}", err.message()),
+    };
+
+    if let Some(options) = prefixer_options {
+        stylesheet.visit_mut_with(&mut prefixer(options));
+    }
+
+    let mut output = String::new();
+    let mut mappings = vec![];
+    let mut code_gen = CodeGenerator::new(
+        BasicCssWriter::new(
+            &mut output,
+            Some(&mut mappings),
+            BasicCssWriterConfig {
+                indent_width: 0,
+                ..Default::default()
+            },
+        ),
+        CodegenConfig { minify: true },
+    );
+    code_gen.emit(&stylesheet)?;
+
+    let map = cm.build_source_map(&mappings);
+    Ok((output, map))
+}
+
+#[turbo_tasks::value(shared)]
+struct CssMinifyIssue {
+    path: FileSystemPathVc,
+    message: StringVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for CssMinifyIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("The CSS chunk couldn't be minified".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("css".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minify_source;
+
+    #[test]
+    fn minifies_with_source_map() {
+        let source = ".a {\n  color: #ff0000;\n}\n\n.b {\n  margin: 0px 0px;\n}\n";
+        let (output, map) = minify_source("chunk.css", source.to_string(), None).unwrap();
+
+        assert!(!output.contains('\n'), "{output}");
+        assert!(output.len() < source.len(), "{output}");
+
+        // `.b` still maps to its original position.
+        let column = output.find(".b").unwrap() as u32;
+        let token = map.lookup_token(0, column).unwrap();
+        assert_eq!(token.get_source(), Some("chunk.css"));
+        assert_eq!(token.get_src_line(), 4);
+    }
+
+    #[test]
+    fn fails_on_syntax_errors() {
+        let source = ".a { color: red; }\n.b { color: }\n}";
+        assert!(minify_source("chunk.css", source.to_string(), None).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod minify;
 pub(crate) mod optimize;
 pub mod source_map;
 pub(crate) mod writer;
@@ -6,16 +7,20 @@ use std::fmt::Write;
 
 use anyhow::{anyhow, Result};
 use indexmap::IndexSet;
-use turbo_tasks::{primitives::StringVc, Value, ValueToString};
+use turbo_tasks::{
+    primitives::{StringVc, U64Vc},
+    Value, ValueToString,
+};
 use turbo_tasks_fs::{rope::Rope, File, FileSystemPathOptionVc, FileSystemPathVc};
+use turbo_tasks_hash::Xxh3Hash64Hasher;
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
     chunk::{
-        chunk_content, chunk_content_split,
+        chunk_content, chunk_content_split, content_hash_modifier,
         optimize::{ChunkOptimizerVc, OptimizableChunk, OptimizableChunkVc},
         Chunk, ChunkContentResult, ChunkGroupReferenceVc, ChunkGroupVc, ChunkItem, ChunkItemVc,
         ChunkReferenceVc, ChunkVc, ChunkableAssetVc, ChunkingContext, ChunkingContextVc,
-        FromChunkableAsset, ModuleId, ModuleIdVc,
+        FromChunkableAsset, ModuleIdVc,
     },
     code_builder::{CodeBuilder, CodeVc},
     ident::{AssetIdent, AssetIdentVc},
@@ -29,7 +34,9 @@ use turbopack_core::{
 };
 use writer::expand_imports;
 
-use self::{optimize::CssChunkOptimizerVc, source_map::CssChunkSourceMapAssetReferenceVc};
+use self::{
+    minify::minify, optimize::CssChunkOptimizerVc, source_map::CssChunkSourceMapAssetReferenceVc,
+};
 use crate::{
     embed::{CssEmbed, CssEmbeddable, CssEmbeddableVc},
     parse::ParseResultSourceMapVc,
@@ -95,6 +102,48 @@ impl CssChunkVc {
             chunk_path,
         ))
     }
+
+    /// Hashes the code of all chunk items, to include it in the chunk path when
+    /// the chunking context enables content hashing.
+    #[turbo_tasks::function]
+    async fn content_hash(self) -> Result<U64Vc> {
+        let this = self.await?;
+        let body = chunk_body(this.main_entries, this.context).await?;
+        let mut hasher = Xxh3Hash64Hasher::new();
+        hasher.write_value(*this.context.is_minification_enabled().await?);
+        hasher.write_ref(&body.external_imports);
+        hasher.write_ref(body.code.await?.source_code());
+        Ok(U64Vc::cell(hasher.finish()))
+    }
+}
+
+/// The code of all chunk items of a chunk, without the chunk header.
+#[turbo_tasks::value]
+struct CssChunkBody {
+    code: CodeVc,
+    external_imports: Vec<String>,
+}
+
+#[turbo_tasks::function]
+async fn chunk_body(
+    main_entries: CssChunkPlaceablesVc,
+    context: ChunkingContextVc,
+) -> Result<CssChunkBodyVc> {
+    let mut body = CodeBuilder::default();
+    let mut external_imports = IndexSet::new();
+    for entry in main_entries.await?.iter() {
+        let entry_placeable = CssChunkPlaceableVc::cast_from(entry);
+        let entry_item = entry_placeable.as_chunk_item(context);
+
+        for external_import in expand_imports(&mut body, entry_item).await? {
+            external_imports.insert(external_import.await?.clone_value());
+        }
+    }
+    Ok(CssChunkBody {
+        code: body.build().cell(),
+        external_imports: external_imports.into_iter().collect(),
+    }
+    .cell())
 }
 
 #[turbo_tasks::value]
@@ -126,25 +175,26 @@ impl CssChunkContentVc {
 
         let this = self.await?;
         let chunk_name = this.chunk_path.to_string();
-
-        let mut body = CodeBuilder::default();
-        let mut external_imports = IndexSet::new();
-        for entry in this.main_entries.await?.iter() {
-            let entry_placeable = CssChunkPlaceableVc::cast_from(entry);
-            let entry_item = entry_placeable.as_chunk_item(this.context);
-
-            for external_import in expand_imports(&mut body, entry_item).await? {
-                external_imports.insert(external_import.await?.to_owned());
-            }
-        }
+        let body = chunk_body(this.main_entries, this.context).await?;
 
         let mut code = CodeBuilder::default();
         writeln!(code, "/* chunk {} */", chunk_name.await?)?;
-        for external_import in external_imports {
-            writeln!(code, "@import {};", stringify_js(&external_import))?;
+        for external_import in body.external_imports.iter() {
+            writeln!(code, "@import {};", stringify_js(external_import))?;
         }
 
-        code.push_code(&body.build());
+        code.push_code(&*body.code.await?);
+
+        if *this.context.is_minification_enabled().await? {
+            let minified = minify(
+                this.chunk_path,
+                code.build().cell(),
+                this.context.environment(),
+            )
+            .await?;
+            code = CodeBuilder::default();
+            code.push_code(&minified);
+        }
 
         if code.has_source_map() {
             let chunk_path = this.chunk_path.await?;
@@ -275,8 +325,13 @@ impl Asset for CssChunk {
             .map(|entry| (main_entry_key, entry.ident()))
             .collect::<Vec<_>>();
 
-        let ident = if let [(_, ident)] = assets[..] {
-            ident
+        let mut modifiers = Vec::new();
+        if *this.context.is_content_hashing_enabled().await? {
+            modifiers.push(content_hash_modifier(*self_vc.content_hash().await?));
+        }
+
+        let ident = if let ([(_, ident)], true) = (&assets[..], modifiers.is_empty()) {
+            *ident
         } else {
             let (_, ident) = assets[0];
             AssetIdentVc::new(Value::new(AssetIdent {
@@ -284,7 +339,7 @@ impl Asset for CssChunk {
                 query: None,
                 fragment: None,
                 assets,
-                modifiers: Vec::new(),
+                modifiers,
             }))
         };
 
@@ -347,12 +402,13 @@ impl CssChunkContextVc {
 
     #[turbo_tasks::function]
     pub async fn chunk_item_id(self, chunk_item: CssChunkItemVc) -> Result<ModuleIdVc> {
-        let layer = self.await?.context.layer();
+        let context = self.await?.context;
+        let layer = context.layer();
         let mut ident = chunk_item.asset_ident();
         if !layer.await?.is_empty() {
             ident = ident.with_modifier(layer)
         }
-        Ok(ModuleId::String(ident.to_string().await?.clone_value()).cell())
+        Ok(context.chunk_item_id(ident))
     }
 }
 
@@ -354,10 +354,13 @@ impl EcmascriptChunkItem for ModuleChunkItem {
             inner_code: code.clone().into(),
             // We generate a minimal map for runtime code so that the filename is
             // displayed in dev tools.
-            source_map: Some(generate_minimal_source_map(
-                self.module.ident().to_string().await?.to_string(),
-                code,
-            )),
+            source_map: Some(
+                generate_minimal_source_map(
+                    self.module.ident().to_string().await?.to_string(),
+                    code,
+                )
+                .into(),
+            ),
             ..Default::default()
         }
         .cell())
@@ -33,5 +33,12 @@ const BACKEND = {
     });
   },
 
+  loadWebAssembly(wasmPath, importsObj, _from) {
+    return WebAssembly.instantiateStreaming(
+      fetch(`/${wasmPath}`),
+      importsObj
+    ).then(({ instance }) => instance.exports);
+  },
+
   restart: () => self.location.reload(),
 };
@@ -30,6 +30,25 @@ const BACKEND = {
     });
   },
 
+  loadWebAssembly(wasmPath, importsObj, from) {
+    const fromPath = getFirstModuleChunk(from);
+    if (fromPath == null) {
+      return Promise.reject(
+        `Module ${from} that requested WebAssembly module ${wasmPath} has been removed`
+      );
+    }
+
+    const path = require("path");
+    const fs = require("fs");
+    const resolved = require.resolve(
+      "./" + path.relative(path.dirname(fromPath), wasmPath)
+    );
+    return fs.promises
+      .readFile(resolved)
+      .then((buffer) => WebAssembly.instantiate(buffer, importsObj))
+      .then(({ instance }) => instance.exports);
+  },
+
   restart: () => {
     throw new Error("restart not implemented for the Node.js backend");
   },
@@ -6,6 +6,10 @@ const BACKEND = {
     throw new Error("chunk loading is not supported");
   },
 
+  loadWebAssembly(wasmPath, importsObj, from) {
+    throw new Error("WebAssembly loading is not supported");
+  },
+
   restart: () => {
     throw new Error("restart is not supported");
   },
@@ -0,0 +1,36 @@
+/** @typedef {import('../types/backend').RuntimeBackend} RuntimeBackend */
+
+/** @type {RuntimeBackend} */
+const BACKEND = {
+  loadChunk(chunkPath, _from) {
+    return new Promise((resolve, reject) => {
+      if (chunkPath.endsWith(".css")) {
+        // Web workers have no document to apply styles to.
+        resolve();
+      } else if (chunkPath.endsWith(".js")) {
+        try {
+          // `importScripts` executes the chunk synchronously, so it has been
+          // registered once it returns.
+          importScripts(`/${chunkPath}`);
+          resolve();
+        } catch (error) {
+          // Module workers don't support `importScripts`.
+          import(`/${chunkPath}`).then(() => resolve(), reject);
+        }
+      } else {
+        throw new Error(`can't infer type of chunk from path ${chunkPath}`);
+      }
+    });
+  },
+
+  loadWebAssembly(wasmPath, importsObj, _from) {
+    return WebAssembly.instantiateStreaming(
+      fetch(`/${wasmPath}`),
+      importsObj
+    ).then(({ instance }) => instance.exports);
+  },
+
+  restart: () => {
+    throw new Error("restart is not supported in web workers");
+  },
+};
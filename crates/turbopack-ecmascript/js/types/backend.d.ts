@@ -4,4 +4,6 @@ export { RuntimeBackend } from "types";
 
 declare global {
   declare const getFirstModuleChunk: GetFirstModuleChunk;
+  // Only available in web workers.
+  declare function importScripts(...urls: string[]): void;
 }
@@ -14,14 +14,19 @@ export interface HotState {
   selfAccepted: boolean | Function;
   selfDeclined: boolean;
   selfInvalidated: boolean;
+  acceptedDependencies: Record<
+    ModuleId,
+    { callback: AcceptCallback; errorHandler?: AcceptErrorHandler }
+  >;
+  declinedDependencies: Record<ModuleId, true>;
   disposeHandlers: ((data: object) => void)[];
 }
 
 export type AcceptErrorHandler = (
   err: Error,
-  context: { moduleId: ModuleId; dependencyId: string | number }
+  context: { moduleId: ModuleId; dependencyId: ModuleId }
 ) => void;
-export type AcceptCallback = (outdatedDependencies: string[]) => void;
+export type AcceptCallback = (outdatedDependencies: ModuleId[]) => void;
 
 export interface AcceptFunction {
   // accept updates for self
@@ -31,6 +31,7 @@ interface Module {
   children: ModuleId[];
   parents: ModuleId[];
   interopNamespace?: EsmInteropNamespace;
+  async?: Promise<void>;
 }
 
 type ModuleCache = Record<ModuleId, Module>;
@@ -44,8 +45,14 @@ type EsmImport = (
 ) => EsmInteropNamespace;
 type EsmExport = (exportGetters: Record<string, () => any>) => void;
 type ExportValue = (value: any) => void;
+type AsyncModule = (body: () => Promise<void>) => void;
+type AsyncDependencies = (moduleIds: ModuleId[]) => Promise<any>;
 
 type LoadChunk = (chunkPath: ChunkPath) => Promise<any> | undefined;
+type LoadWebAssembly = (
+  wasmPath: string,
+  importsObj: WebAssembly.Imports
+) => Promise<WebAssembly.Exports>;
 
 interface TurbopackContext {
   e: Module["exports"];
@@ -53,9 +60,12 @@ interface TurbopackContext {
   i: EsmImport;
   s: EsmExport;
   v: ExportValue;
+  a: AsyncModule;
+  w: AsyncDependencies;
   m: Module;
   c: ModuleCache;
   l: LoadChunk;
+  u: LoadWebAssembly;
   p: Partial<NodeJS.Process> & Pick<NodeJS.Process, "env">;
 }
 
@@ -72,11 +82,16 @@ interface Runtime {
   modules: Record<ModuleId, ModuleFactory>;
   cache: Record<string, Module>;
 
-  instantiateRuntimeModule: (moduleId: ModuleId) => Module;
+  instantiateRuntimeModule: (moduleId: ModuleId) => void;
 }
 
 interface RuntimeBackend {
   loadChunk: (chunkPath: ChunkPath, from: ModuleId) => Promise<void>;
+  loadWebAssembly: (
+    wasmPath: string,
+    importsObj: WebAssembly.Imports,
+    from: ModuleId
+  ) => Promise<WebAssembly.Exports>;
 
   restart: () => void;
 }
@@ -17,8 +17,15 @@ export type ModuleEffect =
       dependencyChain: ModuleId[];
       moduleId: ModuleId;
     }
+  | {
+      type: "declined";
+      dependencyChain: ModuleId[];
+      moduleId: ModuleId;
+      parentId: ModuleId;
+    }
   | {
       type: "accepted";
       moduleId: ModuleId;
       outdatedModules: Set<ModuleId>;
+      outdatedDependencies: Map<ModuleId, Set<ModuleId>>;
     };
@@ -159,6 +159,28 @@ pub enum Effect {
         ast_path: Vec<AstParentKind>,
         span: Span,
     },
+    /// A reference to `new Worker(new URL(..., import.meta.url))` or the same
+    /// with `SharedWorker`.
+    Worker {
+        input: JsValue,
+        ast_path: Vec<AstParentKind>,
+        span: Span,
+    },
+    /// A call to `import.meta.glob(...)` or `import.meta.globEager(...)`.
+    ImportMetaGlob {
+        args: Vec<JsValue>,
+        eager: bool,
+        ast_path: Vec<AstParentKind>,
+        span: Span,
+    },
+    /// A call to `module.hot.accept(deps, ...)` or `module.hot.decline(deps)`,
+    /// or the same with `import.meta.hot`, which refers to other modules.
+    HotDependencies {
+        deps: JsValue,
+        esm: bool,
+        ast_path: Vec<AstParentKind>,
+        span: Span,
+    },
 }
 
 impl Effect {
@@ -224,6 +246,31 @@ impl Effect {
             } => {
                 input.normalize();
             }
+            Effect::Worker {
+                input,
+                ast_path: _,
+                span: _,
+            } => {
+                input.normalize();
+            }
+            Effect::ImportMetaGlob {
+                args,
+                eager: _,
+                ast_path: _,
+                span: _,
+            } => {
+                for arg in args.iter_mut() {
+                    arg.normalize();
+                }
+            }
+            Effect::HotDependencies {
+                deps,
+                esm: _,
+                ast_path: _,
+                span: _,
+            } => {
+                deps.normalize();
+            }
         }
     }
 }
@@ -272,9 +319,80 @@ pub fn create_graph(m: &Program, eval_context: &EvalContext) -> VarGraph {
     graph
 }
 
+/// Checks whether a module contains `await` expressions or `for await` loops
+/// outside of functions. Scripts can't use top-level `await`.
+fn has_top_level_await(program: &Program) -> bool {
+    struct TopLevelAwaitFinder {
+        found: bool,
+    }
+
+    impl Visit for TopLevelAwaitFinder {
+        fn visit_await_expr(&mut self, _: &AwaitExpr) {
+            self.found = true;
+        }
+
+        fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+            if stmt.await_token.is_some() {
+                self.found = true;
+            } else {
+                stmt.visit_children_with(self);
+            }
+        }
+
+        // `await` in nested functions doesn't affect the module
+        fn visit_function(&mut self, _: &Function) {}
+        fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+        fn visit_constructor(&mut self, _: &Constructor) {}
+        fn visit_getter_prop(&mut self, _: &GetterProp) {}
+        fn visit_setter_prop(&mut self, _: &SetterProp) {}
+    }
+
+    let Program::Module(module) = program else {
+        return false;
+    };
+    let mut finder = TopLevelAwaitFinder { found: false };
+    module.visit_with(&mut finder);
+    finder.found
+}
+
+/// Checks whether a module accesses `import.meta.hot`.
+fn uses_import_meta_hot(program: &Program) -> bool {
+    struct ImportMetaHotFinder {
+        found: bool,
+    }
+
+    impl Visit for ImportMetaHotFinder {
+        fn visit_member_expr(&mut self, expr: &MemberExpr) {
+            if let (
+                Expr::MetaProp(MetaPropExpr {
+                    kind: MetaPropKind::ImportMeta,
+                    ..
+                }),
+                MemberProp::Ident(prop),
+            ) = (&*expr.obj, &expr.prop)
+            {
+                if &*prop.sym == "hot" {
+                    self.found = true;
+                    return;
+                }
+            }
+            expr.visit_children_with(self);
+        }
+    }
+
+    let Program::Module(module) = program else {
+        return false;
+    };
+    let mut finder = ImportMetaHotFinder { found: false };
+    module.visit_with(&mut finder);
+    finder.found
+}
+
 pub struct EvalContext {
     pub(crate) unresolved_mark: Mark,
     pub(crate) imports: ImportMap,
+    has_top_level_await: bool,
+    uses_import_meta_hot: bool,
 }
 
 impl EvalContext {
@@ -282,6 +400,8 @@ impl EvalContext {
         Self {
             unresolved_mark,
             imports: ImportMap::analyze(module),
+            has_top_level_await: has_top_level_await(module),
+            uses_import_meta_hot: uses_import_meta_hot(module),
         }
     }
 
@@ -289,6 +409,18 @@ impl EvalContext {
         self.imports.is_esm()
     }
 
+    /// Whether the module uses `await` outside of functions, which makes it an
+    /// async module.
+    pub fn has_top_level_await(&self) -> bool {
+        self.has_top_level_await
+    }
+
+    /// Whether the module uses `import.meta.hot`, which is backed by the hot
+    /// API of the module object.
+    pub fn uses_import_meta_hot(&self) -> bool {
+        self.uses_import_meta_hot
+    }
+
     fn eval_prop_name(&self, prop: &PropName) -> JsValue {
         match prop {
             PropName::Ident(ident) => ident.sym.clone().into(),
@@ -623,6 +755,34 @@ struct Analyzer<'a> {
     cur_fn_ident: u32,
 }
 
+/// Returns the input of a `new URL(input, import.meta.url)` expression.
+fn new_url_input(new_expr: &NewExpr, unresolved_mark: Mark) -> Option<&Expr> {
+    let Expr::Ident(callee) = &*new_expr.callee else {
+        return None;
+    };
+    if &*callee.sym != "URL" || !is_unresolved(callee, unresolved_mark) {
+        return None;
+    }
+    let [input, base] = new_expr.args.as_deref()? else {
+        return None;
+    };
+    if let Expr::Member(MemberExpr {
+        obj:
+            box Expr::MetaProp(MetaPropExpr {
+                kind: MetaPropKind::ImportMeta,
+                ..
+            }),
+        prop: MemberProp::Ident(prop),
+        ..
+    }) = &*base.expr
+    {
+        if &*prop.sym == "url" {
+            return Some(&input.expr);
+        }
+    }
+    None
+}
+
 pub fn as_parent_path(ast_path: &AstNodePath<AstParentNodeRef<'_>>) -> Vec<AstParentKind> {
     ast_path.iter().map(|n| n.kind()).collect()
 }
@@ -982,6 +1142,89 @@ impl VisitAstPath for Analyzer<'_> {
         n: &'ast CallExpr,
         ast_path: &mut AstNodePath<AstParentNodeRef<'r>>,
     ) {
+        // import.meta.glob("./pages/*.js", { eager: true })
+        if let Callee::Expr(box Expr::Member(MemberExpr {
+            obj:
+                box Expr::MetaProp(MetaPropExpr {
+                    kind: MetaPropKind::ImportMeta,
+                    ..
+                }),
+            prop: MemberProp::Ident(prop),
+            ..
+        })) = &n.callee
+        {
+            let eager = match &*prop.sym {
+                "glob" => Some(false),
+                "globEager" => Some(true),
+                _ => None,
+            };
+            if let Some(eager) = eager {
+                let args = n
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        if arg.spread.is_none() {
+                            self.eval_context.eval(&arg.expr)
+                        } else {
+                            JsValue::Unknown(None, "spread is not supported in import.meta.glob")
+                        }
+                    })
+                    .collect();
+                self.add_effect(Effect::ImportMetaGlob {
+                    args,
+                    eager,
+                    ast_path: as_parent_path(ast_path),
+                    span: n.span(),
+                });
+                // The call might be left in place when it can't be analysed, so
+                // `import.meta` is still referenced
+                n.visit_children_with_path(self, ast_path);
+                return;
+            }
+        }
+
+        // module.hot.accept("./dep", () => {}) or import.meta.hot.decline(["./a"])
+        if let Callee::Expr(box Expr::Member(MemberExpr {
+            obj:
+                box Expr::Member(MemberExpr {
+                    obj: hot_obj,
+                    prop: MemberProp::Ident(hot),
+                    ..
+                }),
+            prop: MemberProp::Ident(method),
+            ..
+        })) = &n.callee
+        {
+            let esm = match &**hot_obj {
+                Expr::MetaProp(MetaPropExpr {
+                    kind: MetaPropKind::ImportMeta,
+                    ..
+                }) => Some(true),
+                Expr::Ident(ident)
+                    if &*ident.sym == "module"
+                        && is_unresolved(ident, self.eval_context.unresolved_mark) =>
+                {
+                    Some(false)
+                }
+                _ => None,
+            };
+            if let (Some(esm), "hot", "accept" | "decline", Some(first)) =
+                (esm, &*hot.sym, &*method.sym, n.args.first())
+            {
+                // Self-accepting calls only pass callbacks
+                if first.spread.is_none()
+                    && matches!(&*first.expr, Expr::Lit(Lit::Str(_)) | Expr::Array(_))
+                {
+                    self.add_effect(Effect::HotDependencies {
+                        deps: self.eval_context.eval(&first.expr),
+                        esm,
+                        ast_path: as_parent_path(ast_path),
+                        span: n.span(),
+                    });
+                }
+            }
+        }
+
         // We handle `define(function (require) {})` here.
         if let Callee::Expr(callee) = &n.callee {
             if n.args.len() == 1 {
@@ -1088,33 +1331,47 @@ impl VisitAstPath for Analyzer<'_> {
         new_expr: &'ast NewExpr,
         ast_path: &mut AstNodePath<AstParentNodeRef<'r>>,
     ) {
-        // new URL("path", import.meta.url)
+        // new Worker(new URL("path", import.meta.url))
         if let box Expr::Ident(ref callee) = &new_expr.callee {
-            if &*callee.sym == "URL" && is_unresolved(callee, self.eval_context.unresolved_mark) {
+            if matches!(&*callee.sym, "Worker" | "SharedWorker")
+                && is_unresolved(callee, self.eval_context.unresolved_mark)
+            {
                 if let Some(args) = &new_expr.args {
-                    if args.len() == 2 {
-                        if let Expr::Member(MemberExpr {
-                            obj:
-                                box Expr::MetaProp(MetaPropExpr {
-                                    kind: MetaPropKind::ImportMeta,
-                                    ..
-                                }),
-                            prop: MemberProp::Ident(prop),
-                            ..
-                        }) = &*args[1].expr
-                        {
-                            if &*prop.sym == "url" {
-                                self.add_effect(Effect::Url {
-                                    input: self.eval_context.eval(&args[0].expr),
-                                    ast_path: as_parent_path(ast_path),
-                                    span: new_expr.span(),
-                                });
+                    if let Some(ExprOrSpread {
+                        spread: None,
+                        expr: box Expr::New(url),
+                    }) = args.first()
+                    {
+                        if let Some(input) = new_url_input(url, self.eval_context.unresolved_mark) {
+                            self.add_effect(Effect::Worker {
+                                input: self.eval_context.eval(input),
+                                ast_path: as_parent_path(ast_path),
+                                span: new_expr.span(),
+                            });
+                            // The URL is replaced as a whole, so only the options are visited
+                            for (i, arg) in args.iter().enumerate().skip(1) {
+                                ast_path.with(
+                                    AstParentNodeRef::NewExpr(new_expr, NewExprField::Args(i)),
+                                    |ast_path| {
+                                        arg.visit_with_path(self, ast_path);
+                                    },
+                                );
                             }
+                            return;
                         }
                     }
                 }
             }
         }
+
+        // new URL("path", import.meta.url)
+        if let Some(input) = new_url_input(new_expr, self.eval_context.unresolved_mark) {
+            self.add_effect(Effect::Url {
+                input: self.eval_context.eval(input),
+                ast_path: as_parent_path(ast_path),
+                span: new_expr.span(),
+            });
+        }
         new_expr.visit_children_with_path(self, ast_path);
     }
 
@@ -34,6 +34,10 @@ impl ImportAnnotations {
         self.map.clear();
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
     /// Returns the content on the transition annotation
     pub fn transition(&self) -> Option<&str> {
         self.map
@@ -138,6 +142,13 @@ impl ImportMap {
         None
     }
 
+    /// Returns true when the reference at index `i` is only used by named
+    /// imports, i. e. it's neither imported as namespace nor re-exported.
+    pub fn has_only_named_imports(&self, i: usize) -> bool {
+        !self.namespace_imports.values().any(|&j| j == i)
+            && !self.reexports.iter().any(|&(j, _)| j == i)
+    }
+
     pub fn references(&self) -> impl Iterator<Item = (&JsWord, &ImportAnnotations)> {
         self.references.iter().map(|(m, a)| (m, a))
     }
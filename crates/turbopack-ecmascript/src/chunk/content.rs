@@ -1,14 +1,14 @@
-use std::io::Write as _;
+use std::{collections::HashMap, io::Write as _};
 
 use anyhow::{anyhow, bail, Result};
 use indexmap::IndexSet;
 use indoc::{indoc, writedoc};
-use turbo_tasks::TryJoinIterExt;
+use turbo_tasks::{TryJoinIterExt, ValueToString};
 use turbo_tasks_fs::{embed_file, File, FileContent, FileSystemPathReadRef, FileSystemPathVc};
 use turbopack_core::{
     asset::AssetContentVc,
     chunk::{
-        chunk_content, chunk_content_split, ChunkContentResult, ChunkGroupVc, ChunkVc,
+        chunk_content, chunk_content_split, ChunkContentResult, ChunkGroupVc, ChunkItem, ChunkVc,
         ChunkingContext, ChunkingContextVc, ModuleId,
     },
     code_builder::{CodeBuilder, CodeVc},
@@ -22,11 +22,13 @@ use super::{
     evaluate::EcmascriptChunkContentEvaluateVc,
     item::{EcmascriptChunkItemVc, EcmascriptChunkItems, EcmascriptChunkItemsVc},
     placeable::{EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc},
-    snapshot::EcmascriptChunkContentEntriesSnapshotReadRef,
+    snapshot::{
+        EcmascriptChunkContentEntriesSnapshot, EcmascriptChunkContentEntriesSnapshotReadRef,
+    },
     update::update_ecmascript_chunk,
     version::{EcmascriptChunkVersion, EcmascriptChunkVersionVc},
 };
-use crate::utils::stringify_js;
+use crate::{minify::minify, utils::stringify_js};
 
 #[turbo_tasks::value]
 pub struct EcmascriptChunkContentResult {
@@ -138,9 +140,11 @@ async fn ecmascript_chunk_content_single_entry(
 pub(super) struct EcmascriptChunkContent {
     pub(super) module_factories: EcmascriptChunkContentEntriesSnapshotReadRef,
     pub(super) chunk_path: FileSystemPathReadRef,
+    pub(super) chunk_path_vc: FileSystemPathVc,
     pub(super) output_root: FileSystemPathReadRef,
     pub(super) evaluate: Option<EcmascriptChunkContentEvaluateVc>,
     pub(super) environment: EnvironmentVc,
+    pub(super) minify: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -157,20 +161,46 @@ impl EcmascriptChunkContentVc {
         // the risks of values not being strongly consistent with each other.
         let chunk_content = ecmascript_chunk_content(context, main_entries, omit_entries);
         let chunk_content = chunk_content.await?;
+        let chunk_path_vc = chunk_path;
         let chunk_path = chunk_path.await?;
         let module_factories = chunk_content.chunk_items.to_entry_snapshot().await?;
+        check_module_id_collisions(&module_factories).await?;
         let output_root = context.output_root().await?;
         Ok(EcmascriptChunkContent {
             module_factories,
             chunk_path,
+            chunk_path_vc,
             output_root,
             evaluate,
             environment: context.environment(),
+            minify: *context.is_minification_enabled().await?,
         }
         .cell())
     }
 }
 
+/// Fails when different chunk items share a module id, which can happen when
+/// the chunking context derives module ids from hashes.
+async fn check_module_id_collisions(entries: &EcmascriptChunkContentEntriesSnapshot) -> Result<()> {
+    let mut chunk_items = HashMap::new();
+    for entry in entries.iter() {
+        let Some(other) = chunk_items.insert(&*entry.id, entry.chunk_item) else {
+            continue;
+        };
+        let ident = entry.chunk_item.asset_ident().to_string().await?;
+        let other_ident = other.asset_ident().to_string().await?;
+        if ident != other_ident {
+            bail!(
+                "module id {} is used by both {} and {}",
+                entry.id,
+                other_ident,
+                ident
+            );
+        }
+    }
+    Ok(())
+}
+
 #[turbo_tasks::value_impl]
 impl EcmascriptChunkContentVc {
     #[turbo_tasks::function]
@@ -279,6 +309,7 @@ impl EcmascriptChunkContentVc {
                 ChunkLoading::None => embed_file!("js/src/runtime.none.js").await?,
                 ChunkLoading::NodeJs => embed_file!("js/src/runtime.nodejs.js").await?,
                 ChunkLoading::Dom => embed_file!("js/src/runtime.dom.js").await?,
+                ChunkLoading::WebWorker => embed_file!("js/src/runtime.worker.js").await?,
             };
 
             match &*specific_runtime_code {
@@ -298,6 +329,12 @@ impl EcmascriptChunkContentVc {
             "# };
         }
 
+        if this.minify {
+            let minified = minify(this.chunk_path_vc, code.build().cell()).await?;
+            code = CodeBuilder::default();
+            code.push_code(&minified);
+        }
+
         if code.has_source_map() {
             let filename = this.chunk_path.file_name();
             write!(code, "\n\n//# sourceMappingURL={}.map", filename)?;
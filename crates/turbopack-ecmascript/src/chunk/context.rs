@@ -1,6 +1,5 @@
 use anyhow::Result;
-use turbo_tasks::ValueToString;
-use turbopack_core::chunk::{ChunkItem, ChunkingContext, ChunkingContextVc, ModuleId, ModuleIdVc};
+use turbopack_core::chunk::{ChunkItem, ChunkingContext, ChunkingContextVc, ModuleIdVc};
 
 use super::item::EcmascriptChunkItemVc;
 
@@ -18,11 +17,12 @@ impl EcmascriptChunkContextVc {
 
     #[turbo_tasks::function]
     pub async fn chunk_item_id(self, chunk_item: EcmascriptChunkItemVc) -> Result<ModuleIdVc> {
-        let layer = self.await?.context.layer();
+        let context = self.await?.context;
+        let layer = context.layer();
         let mut ident = chunk_item.asset_ident();
         if !layer.await?.is_empty() {
             ident = ident.with_modifier(layer)
         }
-        Ok(ModuleId::String(ident.to_string().await?.clone_value()).cell())
+        Ok(context.chunk_item_id(ident))
     }
 }
@@ -8,6 +8,7 @@ use turbopack_core::{
     chunk::{
         ChunkItem, ChunkItemVc, ChunkableAssetVc, ChunkingContextVc, FromChunkableAsset, ModuleIdVc,
     },
+    source_map::GenerateSourceMapVc,
 };
 
 use super::{
@@ -20,13 +21,12 @@ use super::{
     },
     EcmascriptChunkPlaceable,
 };
-use crate::ParseResultSourceMapVc;
 
 #[turbo_tasks::value(shared)]
 #[derive(Default)]
 pub struct EcmascriptChunkItemContent {
     pub inner_code: Rope,
-    pub source_map: Option<ParseResultSourceMapVc>,
+    pub source_map: Option<GenerateSourceMapVc>,
     pub options: EcmascriptChunkItemOptions,
     pub placeholder_for_future_extensions: (),
 }
@@ -36,6 +36,12 @@ pub struct EcmascriptChunkItemOptions {
     pub module: bool,
     pub exports: bool,
     pub this: bool,
+    /// Whether the module is evaluated asynchronously, see
+    /// `EcmascriptModuleAssetVc::is_async_module`.
+    pub async_module: bool,
+    /// Whether the module instantiates a WebAssembly module, which needs the
+    /// `__turbopack_wasm__` runtime function.
+    pub wasm: bool,
     pub placeholder_for_future_extensions: (),
 }
 
@@ -129,6 +129,17 @@ impl EcmascriptChunkItem for ManifestLoaderItem {
             .await?
             .ok_or_else(|| anyhow!("asset is not placeable in ecmascript chunk"))?;
         let dynamic_id = &*placeable.as_chunk_item(this.context).id().await?;
+        let dynamic_id = stringify_js(dynamic_id);
+
+        // Async modules can only be used once they are evaluated.
+        let import = if *placeable.is_async().await? {
+            format!(
+                "const namespace = __turbopack_import__({dynamic_id});\n        return \
+                 Promise.resolve(__turbopack_cache__[{dynamic_id}].async).then(() => namespace);"
+            )
+        } else {
+            format!("return __turbopack_import__({dynamic_id});")
+        };
 
         // This is the code that will be executed when the dynamic import is reached.
         // It will load the manifest chunk, which will load all the chunks needed by
@@ -145,13 +156,12 @@ impl EcmascriptChunkItem for ManifestLoaderItem {
                     }}).then((chunks_paths) => {{
                         return Promise.all(chunks_paths.map((chunk_path) => __turbopack_load__(chunk_path)));
                     }}).then(() => {{
-                        return __turbopack_import__({dynamic_id});
+                        {import}
                     }});
                 }});
             "#,
             chunk_server_path = stringify_js(chunk_server_path),
             item_id = stringify_js(item_id),
-            dynamic_id = stringify_js(dynamic_id)
         )?;
 
         Ok(EcmascriptChunkItemContent {
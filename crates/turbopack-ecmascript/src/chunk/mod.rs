@@ -16,13 +16,15 @@ use std::fmt::Write;
 use anyhow::{anyhow, Result};
 use indexmap::IndexSet;
 use turbo_tasks::{
-    primitives::{StringReadRef, StringVc, UsizeVc},
+    primitives::{BoolVc, StringReadRef, StringVc, U64Vc, UsizeVc},
     TryJoinIterExt, Value, ValueToString, ValueToStringVc,
 };
 use turbo_tasks_fs::FileSystemPathOptionVc;
+use turbo_tasks_hash::Xxh3Hash64Hasher;
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
     chunk::{
+        content_hash_modifier,
         optimize::{ChunkOptimizerVc, OptimizableChunk, OptimizableChunkVc},
         Chunk, ChunkGroupReferenceVc, ChunkItem, ChunkReferenceVc, ChunkVc, ChunkingContext,
         ChunkingContextVc,
@@ -39,6 +41,7 @@ use turbopack_core::{
 
 use self::{
     content::{ecmascript_chunk_content, EcmascriptChunkContentResultVc, EcmascriptChunkContentVc},
+    manifest::chunk_asset::ManifestChunkAssetVc,
     optimize::EcmascriptChunkOptimizerVc,
     source_map::EcmascriptChunkSourceMapAssetReferenceVc,
 };
@@ -280,6 +283,40 @@ impl EcmascriptChunkVc {
         ))
     }
 
+    /// Hashes the code of all chunk items, to include it in the chunk path when
+    /// the chunking context enables content hashing.
+    #[turbo_tasks::function]
+    async fn content_hash(self) -> Result<U64Vc> {
+        let this = self.await?;
+        let entries = self
+            .chunk_content_result()
+            .await?
+            .chunk_items
+            .to_entry_snapshot()
+            .await?;
+        let mut hasher = Xxh3Hash64Hasher::new();
+        hasher.write_value(*this.context.is_minification_enabled().await?);
+        for entry in entries.iter() {
+            hasher.write_ref(&*entry.id);
+            hasher.write_value(entry.hash);
+        }
+        Ok(U64Vc::cell(hasher.finish()))
+    }
+
+    /// Manifest chunks are loaded by the chunks which import their chunk group.
+    /// Their path must not depend on their content, which lists the chunks of
+    /// the chunk group, as chunk groups can import each other. With content
+    /// hashing they are named `*.manifest.js` instead.
+    #[turbo_tasks::function]
+    async fn is_manifest_chunk(self) -> Result<BoolVc> {
+        for entry in self.await?.main_entries.await?.iter() {
+            if ManifestChunkAssetVc::resolve_from(*entry).await?.is_some() {
+                return Ok(BoolVc::cell(true));
+            }
+        }
+        Ok(BoolVc::cell(false))
+    }
+
     #[turbo_tasks::function]
     async fn chunk_content(self) -> Result<EcmascriptChunkContentVc> {
         let this = self.await?;
@@ -355,6 +392,12 @@ impl Asset for EcmascriptChunk {
             modifiers.extend(evaluate.entry_modules_ids.iter().map(|id| id.to_string()));
         }
 
+        let content_hashing = *this.context.is_content_hashing_enabled().await?;
+        let is_manifest_chunk = *self_vc.is_manifest_chunk().await?;
+        if content_hashing && !is_manifest_chunk {
+            modifiers.push(content_hash_modifier(*self_vc.content_hash().await?));
+        }
+
         // Simplify when it's only a single main entry without extra info
         let ident = if assets.len() == 1 && modifiers.is_empty() {
             assets[0].1
@@ -368,8 +411,16 @@ impl Asset for EcmascriptChunk {
             }))
         };
 
+        // Manifest chunks keep their path when their content changes, so they
+        // can't be cached as immutable like the other content hashed chunks.
+        // Their extension allows to serve them with different cache headers.
+        let extension = if content_hashing && is_manifest_chunk {
+            ".manifest.js"
+        } else {
+            ".js"
+        };
         Ok(AssetIdentVc::from_path(
-            this.context.chunk_path(ident, ".js"),
+            this.context.chunk_path(ident, extension),
         ))
     }
 
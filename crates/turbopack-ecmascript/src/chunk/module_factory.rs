@@ -29,6 +29,13 @@ pub(super) async fn module_factory(content: EcmascriptChunkItemContentVc) -> Res
     if content.options.exports {
         args.push("e: exports");
     }
+    if content.options.async_module {
+        args.push("a: __turbopack_async_module__");
+        args.push("w: __turbopack_async_dependencies__");
+    }
+    if content.options.wasm {
+        args.push("u: __turbopack_wasm__");
+    }
     let mut code = CodeBuilder::default();
     let args = FormatIter(|| args.iter().copied().intersperse(", "));
     if content.options.this {
@@ -37,8 +44,13 @@ pub(super) async fn module_factory(content: EcmascriptChunkItemContentVc) -> Res
         write!(code, "(({{ {} }}) => (() => {{\n\n", args,)?;
     }
 
-    let source_map = content.source_map.map(|sm| sm.as_generate_source_map());
-    code.push_source(&content.inner_code, source_map);
+    if content.options.async_module {
+        code += "__turbopack_async_module__(async () => {\n";
+    }
+    code.push_source(&content.inner_code, content.source_map);
+    if content.options.async_module {
+        code += "\n});";
+    }
     if content.options.this {
         code += "\n}.call(this) })";
     } else {
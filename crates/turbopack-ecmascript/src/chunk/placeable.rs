@@ -1,4 +1,5 @@
 use anyhow::Result;
+use turbo_tasks::primitives::BoolVc;
 use turbopack_core::{
     asset::{Asset, AssetVc},
     chunk::{ChunkableAsset, ChunkableAssetVc, ChunkingContextVc},
@@ -11,6 +12,11 @@ use crate::references::esm::EsmExportsVc;
 pub trait EcmascriptChunkPlaceable: ChunkableAsset + Asset {
     fn as_chunk_item(&self, context: ChunkingContextVc) -> EcmascriptChunkItemVc;
     fn get_exports(&self) -> EcmascriptExportsVc;
+    /// Whether the asset is evaluated asynchronously, so importers need to
+    /// wait for it before they can use its exports.
+    fn is_async(&self) -> BoolVc {
+        BoolVc::cell(false)
+    }
 }
 
 #[turbo_tasks::value(transparent)]
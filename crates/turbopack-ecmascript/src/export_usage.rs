@@ -0,0 +1,573 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use indexmap::{map::Entry, IndexMap};
+use swc_core::ecma::{
+    ast::{
+        Class, ClassMember, Decl, DefaultDecl, Expr, Id, Ident, ImportSpecifier, ModuleDecl,
+        ModuleItem, Pat, Program, Prop, PropName, PropOrSpread, Stmt, UnaryOp,
+    },
+    atoms::JsWord,
+    utils::find_pat_ids,
+    visit::{fields::ModuleField, AstParentKind, Visit, VisitWith},
+};
+use turbo_tasks::ValueToString;
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ExportUsageVc, UsedExports, UsedExportsVc},
+    reference::AssetReference,
+    resolve::{PrimaryResolveResult, ResolveResultVc},
+};
+
+use crate::{
+    chunk::{
+        EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc,
+        EcmascriptExports,
+    },
+    magic_identifier,
+    parse::ParseResult,
+    references::esm::{
+        base::ReferencedAsset, export::EsmExport, EsmAssetReferenceVc, EsmBindingVc,
+    },
+    EcmascriptModuleAssetVc,
+};
+
+/// Computes which exports of the modules reachable from `entries` are used.
+/// All exports of the entries are considered to be used.
+///
+/// Only ESM modules can have unused exports. Modules which are referenced in
+/// any other way (e.g. `require()` or `import()`) and modules without ESM
+/// exports always use all their exports.
+#[turbo_tasks::function]
+pub async fn compute_export_usage(entries: EcmascriptChunkPlaceablesVc) -> Result<ExportUsageVc> {
+    let mut usage = IndexMap::new();
+    let mut queue = VecDeque::with_capacity(32);
+    for entry in entries.await?.iter() {
+        add_usage(&mut usage, &mut queue, *entry, UsedExports::All).await?;
+    }
+    while let Some((module, used)) = queue.pop_front() {
+        for (dependency, dependency_used) in module_dependencies(module, &used).await? {
+            add_usage(&mut usage, &mut queue, dependency, dependency_used).await?;
+        }
+    }
+    Ok(ExportUsageVc::cell(usage))
+}
+
+/// Merges `used` into the usage of `module` and queues the module when its
+/// usage changed.
+async fn add_usage(
+    usage: &mut IndexMap<String, UsedExports>,
+    queue: &mut VecDeque<(EcmascriptChunkPlaceableVc, UsedExports)>,
+    module: EcmascriptChunkPlaceableVc,
+    used: UsedExports,
+) -> Result<()> {
+    let used = if matches!(
+        &*module.get_exports().await?,
+        EcmascriptExports::EsmExports(_)
+    ) {
+        used
+    } else {
+        UsedExports::All
+    };
+    let key = module.ident().to_string().await?.clone_value();
+    let merged = match usage.entry(key) {
+        Entry::Vacant(entry) => entry.insert(used).clone(),
+        Entry::Occupied(mut entry) => {
+            let merged = match (entry.get(), used) {
+                (UsedExports::All, _) => return Ok(()),
+                (UsedExports::Names(_), UsedExports::All) => UsedExports::All,
+                (UsedExports::Names(names), UsedExports::Names(new_names)) => {
+                    if new_names.is_subset(names) {
+                        return Ok(());
+                    }
+                    UsedExports::Names(names.union(&new_names).cloned().collect())
+                }
+            };
+            entry.insert(merged.clone());
+            merged
+        }
+    };
+    queue.push_back((module, merged));
+    Ok(())
+}
+
+/// Returns the modules referenced by `module` together with the exports of
+/// them which are used, given that `used` exports of `module` are used.
+async fn module_dependencies(
+    module: EcmascriptChunkPlaceableVc,
+    used: &UsedExports,
+) -> Result<Vec<(EcmascriptChunkPlaceableVc, UsedExports)>> {
+    let mut dependencies = Vec::new();
+
+    let Some(ecmascript_module) = EcmascriptModuleAssetVc::resolve_from(module).await? else {
+        // We don't know how other modules use their references
+        for reference in module.references().await?.iter() {
+            for placeable in referenced_placeables(reference.resolve_reference()).await? {
+                dependencies.push((placeable, UsedExports::All));
+            }
+        }
+        return Ok(dependencies);
+    };
+    let analysis = ecmascript_module.analyze().await?;
+    let unused_items = unused_module_items(ecmascript_module, used.clone().cell()).await?;
+
+    // Imported bindings which are used in the module
+    for code_gen in analysis.code_generation.await?.iter() {
+        if let Some(binding) = EsmBindingVc::resolve_from(code_gen).await? {
+            let binding = binding.await?;
+            if let Some(index) = module_item_index(&binding.ast_path.await?) {
+                if unused_items.contains(&index) {
+                    continue;
+                }
+            }
+            let export = binding
+                .reference
+                .get_referenced_export(binding.export.clone())
+                .await?;
+            let used = match &*export {
+                Some(export) => used_names([export]),
+                None => UsedExports::All,
+            };
+            add_esm_dependency(&mut dependencies, binding.reference, used).await?;
+        }
+    }
+
+    // Exports which are re-exported from other modules
+    if let EcmascriptExports::EsmExports(exports) = &*analysis.exports.await? {
+        let exports = exports.await?;
+        match used {
+            UsedExports::All => {
+                for export in exports.exports.values() {
+                    match export {
+                        EsmExport::ImportedBinding(reference, name) => {
+                            add_imported_binding(&mut dependencies, *reference, name).await?;
+                        }
+                        EsmExport::ImportedNamespace(reference) => {
+                            add_esm_dependency(&mut dependencies, *reference, UsedExports::All)
+                                .await?;
+                        }
+                        EsmExport::LocalBinding(_) | EsmExport::Error => {}
+                    }
+                }
+                for reference in exports.star_exports.iter() {
+                    add_esm_dependency(&mut dependencies, *reference, UsedExports::All).await?;
+                }
+            }
+            UsedExports::Names(names) => {
+                for name in names {
+                    match exports.exports.get(name) {
+                        Some(EsmExport::ImportedBinding(reference, name)) => {
+                            add_imported_binding(&mut dependencies, *reference, name).await?;
+                        }
+                        Some(EsmExport::ImportedNamespace(reference)) => {
+                            add_esm_dependency(&mut dependencies, *reference, UsedExports::All)
+                                .await?;
+                        }
+                        Some(EsmExport::LocalBinding(_) | EsmExport::Error) => {}
+                        None => {
+                            // The export might come from any of the star exports
+                            for reference in exports.star_exports.iter() {
+                                add_esm_dependency(
+                                    &mut dependencies,
+                                    *reference,
+                                    used_names([name]),
+                                )
+                                .await?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // All other references include the referenced modules, but ESM imports
+    // don't use any exports by themselves
+    for reference in analysis.references.await?.iter() {
+        let used = if EsmAssetReferenceVc::resolve_from(reference)
+            .await?
+            .is_some()
+        {
+            UsedExports::Names(BTreeSet::new())
+        } else {
+            UsedExports::All
+        };
+        for placeable in referenced_placeables(reference.resolve_reference()).await? {
+            dependencies.push((placeable, used.clone()));
+        }
+    }
+
+    Ok(dependencies)
+}
+
+fn used_names<'a>(names: impl IntoIterator<Item = &'a String>) -> UsedExports {
+    UsedExports::Names(names.into_iter().cloned().collect())
+}
+
+async fn add_esm_dependency(
+    dependencies: &mut Vec<(EcmascriptChunkPlaceableVc, UsedExports)>,
+    reference: EsmAssetReferenceVc,
+    used: UsedExports,
+) -> Result<()> {
+    if let ReferencedAsset::Some(placeable) = &*reference.get_referenced_asset().await? {
+        dependencies.push((*placeable, used));
+    }
+    Ok(())
+}
+
+/// Adds the module providing the export `name` of `reference`. Followed
+/// references provide it under the name of the defining module.
+async fn add_imported_binding(
+    dependencies: &mut Vec<(EcmascriptChunkPlaceableVc, UsedExports)>,
+    reference: EsmAssetReferenceVc,
+    name: &str,
+) -> Result<()> {
+    let used = match &*reference
+        .get_referenced_export(Some(name.to_string()))
+        .await?
+    {
+        Some(export) => used_names([export]),
+        None => UsedExports::All,
+    };
+    add_esm_dependency(dependencies, reference, used).await
+}
+
+async fn referenced_placeables(result: ResolveResultVc) -> Result<Vec<EcmascriptChunkPlaceableVc>> {
+    let mut placeables = Vec::new();
+    for result in result.await?.primary.iter() {
+        if let PrimaryResolveResult::Asset(asset) = result {
+            if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? {
+                placeables.push(placeable);
+            }
+        }
+    }
+    Ok(placeables)
+}
+
+/// The indices of the top-level items of a module which can be omitted.
+#[turbo_tasks::value(transparent)]
+pub struct UnusedModuleItems(BTreeSet<usize>);
+
+/// Computes which top-level declarations of `module` are not needed when only
+/// `used_exports` of it are used. Only declarations without side effects are
+/// considered, so omitting them doesn't change the behavior of the module.
+#[turbo_tasks::function]
+pub async fn unused_module_items(
+    module: EcmascriptModuleAssetVc,
+    used_exports: UsedExportsVc,
+) -> Result<UnusedModuleItemsVc> {
+    let UsedExports::Names(names) = &*used_exports.await? else {
+        return Ok(UnusedModuleItemsVc::cell(BTreeSet::new()));
+    };
+    let EcmascriptExports::EsmExports(exports) = &*module.analyze().await?.exports.await? else {
+        return Ok(UnusedModuleItemsVc::cell(BTreeSet::new()));
+    };
+    let exports = exports.await?;
+    let used_locals = names
+        .iter()
+        .filter_map(|name| match exports.exports.get(name) {
+            Some(EsmExport::LocalBinding(local)) => Some(JsWord::from(local.as_str())),
+            _ => None,
+        })
+        .collect();
+    let parsed = module.parse().await?;
+    let ParseResult::Ok { program: Program::Module(program), .. } = &*parsed else {
+        return Ok(UnusedModuleItemsVc::cell(BTreeSet::new()));
+    };
+    Ok(UnusedModuleItemsVc::cell(find_unused_items(
+        &program.body,
+        used_locals,
+    )))
+}
+
+/// Returns the index of the top-level item which contains the node at
+/// `ast_path`.
+fn module_item_index(ast_path: &[AstParentKind]) -> Option<usize> {
+    ast_path.iter().find_map(|kind| match kind {
+        AstParentKind::Module(ModuleField::Body(index)) => Some(*index),
+        _ => None,
+    })
+}
+
+/// Returns the indices of the declarations in `items` which can be omitted
+/// when only the local bindings `used_locals` are used from outside of the
+/// module.
+fn find_unused_items(items: &[ModuleItem], used_locals: HashSet<JsWord>) -> BTreeSet<usize> {
+    let top_level: HashSet<JsWord> = items.iter().flat_map(declared_names).collect();
+
+    let mut removable = BTreeSet::new();
+    let mut declared_by: HashMap<JsWord, Vec<usize>> = HashMap::new();
+    let mut queue: Vec<JsWord> = used_locals.iter().cloned().collect();
+    let mut seen = used_locals;
+    for (index, item) in items.iter().enumerate() {
+        if is_removable(item, &top_level) {
+            removable.insert(index);
+            for name in declared_names(item) {
+                declared_by.entry(name).or_default().push(index);
+            }
+        } else {
+            queue.extend(
+                referenced_names(item)
+                    .into_iter()
+                    .filter(|name| seen.insert(name.clone())),
+            );
+        }
+    }
+
+    while let Some(name) = queue.pop() {
+        let Some(indices) = declared_by.remove(&name) else {
+            continue;
+        };
+        for index in indices {
+            if removable.remove(&index) {
+                queue.extend(
+                    referenced_names(&items[index])
+                        .into_iter()
+                        .filter(|name| seen.insert(name.clone())),
+                );
+            }
+        }
+    }
+    removable
+}
+
+/// Returns the names of the top-level bindings declared by `item`.
+fn declared_names(item: &ModuleItem) -> Vec<JsWord> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => import
+            .specifiers
+            .iter()
+            .map(|specifier| match specifier {
+                ImportSpecifier::Named(specifier) => specifier.local.sym.clone(),
+                ImportSpecifier::Default(specifier) => specifier.local.sym.clone(),
+                ImportSpecifier::Namespace(specifier) => specifier.local.sym.clone(),
+            })
+            .collect(),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => decl_names(&export.decl),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+            let ident = match &export.decl {
+                DefaultDecl::Class(class) => class.ident.as_ref(),
+                DefaultDecl::Fn(function) => function.ident.as_ref(),
+                DefaultDecl::TsInterfaceDecl(_) => return Vec::new(),
+            };
+            vec![ident.map_or_else(
+                || magic_identifier::encode("default export").into(),
+                |ident| ident.sym.clone(),
+            )]
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(_)) => {
+            vec![magic_identifier::encode("default export").into()]
+        }
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl_names(decl),
+        _ => Vec::new(),
+    }
+}
+
+fn decl_names(decl: &Decl) -> Vec<JsWord> {
+    match decl {
+        Decl::Class(class) => vec![class.ident.sym.clone()],
+        Decl::Fn(function) => vec![function.ident.sym.clone()],
+        Decl::Var(var) => var
+            .decls
+            .iter()
+            .flat_map(|decl| find_pat_ids::<_, Id>(&decl.name))
+            .map(|(name, _)| name)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `item` only declares bindings and evaluating it has no side
+/// effects.
+fn is_removable(item: &ModuleItem, top_level: &HashSet<JsWord>) -> bool {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+            is_removable_decl(&export.decl, top_level)
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => match &export.decl {
+            DefaultDecl::Class(class) => is_pure_class(&class.class),
+            DefaultDecl::Fn(_) => true,
+            DefaultDecl::TsInterfaceDecl(_) => false,
+        },
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+            is_pure_expr(&export.expr, top_level)
+        }
+        ModuleItem::Stmt(Stmt::Decl(decl)) => is_removable_decl(decl, top_level),
+        _ => false,
+    }
+}
+
+fn is_removable_decl(decl: &Decl, top_level: &HashSet<JsWord>) -> bool {
+    match decl {
+        Decl::Class(class) => is_pure_class(&class.class),
+        Decl::Fn(_) => true,
+        Decl::Var(var) => var.decls.iter().all(|decl| {
+            matches!(decl.name, Pat::Ident(_))
+                && decl
+                    .init
+                    .as_ref()
+                    .map_or(true, |init| is_pure_expr(init, top_level))
+        }),
+        _ => false,
+    }
+}
+
+/// Whether defining `class` has no side effects. Super classes, decorators,
+/// computed keys and static members are evaluated when the class is defined.
+fn is_pure_class(class: &Class) -> bool {
+    class.decorators.is_empty()
+        && class.super_class.is_none()
+        && class.body.iter().all(|member| match member {
+            ClassMember::Constructor(_) | ClassMember::Empty(_) => true,
+            ClassMember::Method(method) => {
+                !matches!(method.key, PropName::Computed(_))
+                    && method.function.decorators.is_empty()
+            }
+            ClassMember::PrivateMethod(method) => method.function.decorators.is_empty(),
+            ClassMember::ClassProp(prop) => {
+                !prop.is_static
+                    && !matches!(prop.key, PropName::Computed(_))
+                    && prop.decorators.is_empty()
+            }
+            ClassMember::PrivateProp(prop) => !prop.is_static && prop.decorators.is_empty(),
+            _ => false,
+        })
+}
+
+/// Whether evaluating `expr` has no side effects. Identifiers are only
+/// considered pure when they refer to top-level bindings of the module.
+fn is_pure_expr(expr: &Expr, top_level: &HashSet<JsWord>) -> bool {
+    match expr {
+        Expr::Lit(_) | Expr::Fn(_) | Expr::Arrow(_) => true,
+        Expr::Class(class) => is_pure_class(&class.class),
+        Expr::Ident(ident) => top_level.contains(&ident.sym) || &*ident.sym == "undefined",
+        Expr::Paren(paren) => is_pure_expr(&paren.expr, top_level),
+        Expr::Tpl(tpl) => tpl.exprs.iter().all(|expr| is_pure_expr(expr, top_level)),
+        Expr::Array(array) => array.elems.iter().all(|elem| match elem {
+            Some(elem) => elem.spread.is_none() && is_pure_expr(&elem.expr, top_level),
+            None => true,
+        }),
+        Expr::Object(object) => object.props.iter().all(|prop| match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(ident) => top_level.contains(&ident.sym),
+                Prop::KeyValue(prop) => {
+                    !matches!(prop.key, PropName::Computed(_))
+                        && is_pure_expr(&prop.value, top_level)
+                }
+                Prop::Method(method) => !matches!(method.key, PropName::Computed(_)),
+                Prop::Getter(getter) => !matches!(getter.key, PropName::Computed(_)),
+                Prop::Setter(setter) => !matches!(setter.key, PropName::Computed(_)),
+                Prop::Assign(_) => false,
+            },
+            PropOrSpread::Spread(_) => false,
+        }),
+        // Other operators might call `valueOf` on objects
+        Expr::Unary(unary) => match unary.op {
+            UnaryOp::Bang | UnaryOp::Void | UnaryOp::TypeOf => is_pure_expr(&unary.arg, top_level),
+            UnaryOp::Minus | UnaryOp::Plus | UnaryOp::Tilde => matches!(&*unary.arg, Expr::Lit(_)),
+            UnaryOp::Delete => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns all names which are referenced by `item`. This includes names
+/// which only refer to inner bindings or properties, which is fine as it only
+/// keeps more declarations. Imports and exports are handled by their
+/// references instead.
+fn referenced_names(item: &ModuleItem) -> HashSet<JsWord> {
+    struct NamesCollector(HashSet<JsWord>);
+
+    impl Visit for NamesCollector {
+        fn visit_ident(&mut self, ident: &Ident) {
+            self.0.insert(ident.sym.clone());
+        }
+    }
+
+    match item {
+        ModuleItem::ModuleDecl(
+            ModuleDecl::Import(_) | ModuleDecl::ExportNamed(_) | ModuleDecl::ExportAll(_),
+        ) => HashSet::new(),
+        item => {
+            let mut collector = NamesCollector(HashSet::new());
+            item.visit_with(&mut collector);
+            collector.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use swc_core::{
+        common::{errors::HANDLER, FileName},
+        ecma::{ast::EsVersion, atoms::JsWord, parser::parse_file_as_module},
+        testing::run_test,
+    };
+
+    use super::find_unused_items;
+
+    fn unused_items(source: &str, used_locals: &[&str]) -> BTreeSet<usize> {
+        let mut unused = BTreeSet::new();
+        run_test(false, |cm, _handler| {
+            let fm = cm.new_source_file(FileName::Anon, source.to_string());
+            let module = parse_file_as_module(
+                &fm,
+                Default::default(),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .map_err(|err| HANDLER.with(|handler| err.into_diagnostic(handler).emit()))
+            .unwrap();
+            let used_locals: HashSet<JsWord> =
+                used_locals.iter().map(|name| JsWord::from(*name)).collect();
+            unused = find_unused_items(&module.body, used_locals);
+            Ok(())
+        })
+        .unwrap();
+        unused
+    }
+
+    #[test]
+    fn unused_declarations() {
+        let source = r#"
+            import { helper } from "./helper";
+            export function used() { return format(); }
+            export function unused() { return helper(); }
+            function format() { return "used"; }
+            const table = { unused };
+            export default class {}
+        "#;
+        assert_eq!(unused_items(source, &["used"]), BTreeSet::from([2, 4, 5]));
+        assert_eq!(unused_items(source, &["table"]), BTreeSet::from([1, 3, 5]));
+    }
+
+    #[test]
+    fn declarations_with_side_effects() {
+        let source = r#"
+            export const value = compute();
+            export class Component extends Base {}
+            export class Counter { static count = increment(); }
+            export const config = { name: "config", [key]: 1 };
+            export const object = { ...other };
+            register(used);
+            function used() {}
+        "#;
+        assert_eq!(unused_items(source, &[]), BTreeSet::new());
+    }
+
+    #[test]
+    fn pure_declarations() {
+        let source = r#"
+            import def from "./def";
+            export const number = -1;
+            export const flags = [true, !false, void 0, typeof def];
+            export const options = { def, name: `options`, method() {} };
+            export let uninitialized;
+            export class Point { x = 0; static origin() { return new Point(); } }
+        "#;
+        assert_eq!(unused_items(source, &[]), BTreeSet::from([1, 2, 3, 4, 5]));
+    }
+}
@@ -12,18 +12,25 @@ pub mod chunk;
 pub mod chunk_group_files_asset;
 pub mod code_gen;
 mod errors;
+pub mod export_usage;
 pub mod magic_identifier;
+mod minify;
 pub mod parse;
 mod path_visitor;
 pub(crate) mod references;
 pub mod resolve;
+pub mod scope_hoisting;
+pub(crate) mod side_effects;
 pub(crate) mod special_cases;
 pub(crate) mod transform;
 pub mod typescript;
 pub mod utils;
 pub mod webpack;
 
-use anyhow::Result;
+use std::{collections::HashSet, sync::Arc};
+
+use analyzer::graph::EvalContext;
+use anyhow::{bail, Result};
 use chunk::{
     EcmascriptChunkItem, EcmascriptChunkItemVc, EcmascriptChunkPlaceablesVc, EcmascriptChunkVc,
 };
@@ -34,8 +41,9 @@ pub use parse::{ParseResultSourceMap, ParseResultSourceMapVc};
 use path_visitor::ApplyVisitors;
 use references::AnalyzeEcmascriptModuleResult;
 use swc_core::{
-    common::GLOBALS,
+    common::{SourceMap, GLOBALS},
     ecma::{
+        ast::{Module, Program},
         codegen::{text_writer::JsWriter, Emitter},
         visit::{VisitMutWith, VisitMutWithPath},
     },
@@ -43,22 +51,32 @@ use swc_core::{
 pub use transform::{
     EcmascriptInputTransform, EcmascriptInputTransformsVc, NextJsPageExportFilter,
 };
-use turbo_tasks::{primitives::StringVc, TryJoinIterExt, Value, ValueToString};
+use turbo_tasks::{
+    primitives::{BoolVc, StringVc, U64Vc},
+    TryJoinIterExt, Value, ValueToString,
+};
 use turbo_tasks_fs::FileSystemPathVc;
+use turbo_tasks_hash::hash_xxh3_hash64;
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetOptionVc, AssetVc},
-    chunk::{ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetVc, ChunkingContextVc},
+    chunk::{
+        ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetVc, ChunkingContext,
+        ChunkingContextVc,
+    },
     compile_time_info::CompileTimeInfoVc,
     context::AssetContextVc,
     ident::AssetIdentVc,
-    reference::AssetReferencesVc,
+    reference::{AssetReference, AssetReferencesVc},
     resolve::{
         origin::{ResolveOrigin, ResolveOriginVc},
         parse::RequestVc,
     },
 };
 
-pub use self::references::AnalyzeEcmascriptModuleResultVc;
+pub use self::references::{
+    esm::{EsmExport, EsmExports, EsmExportsVc},
+    AnalyzeEcmascriptModuleResultVc,
+};
 use self::{
     chunk::{
         EcmascriptChunkItemContent, EcmascriptChunkItemContentVc, EcmascriptChunkItemOptions,
@@ -69,7 +87,12 @@ use self::{
 use crate::{
     chunk::{EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc},
     code_gen::CodeGenerateable,
-    references::analyze_ecmascript_module,
+    export_usage::unused_module_items,
+    references::{
+        analyze_ecmascript_module,
+        esm::{base::ReferencedAsset, EsmAssetReferenceVc, ImportMetaGlobAssetReferenceVc},
+    },
+    scope_hoisting::{concatenated_module_content, concatenated_modules},
     transform::remove_shebang,
 };
 
@@ -174,6 +197,92 @@ impl EcmascriptModuleAssetVc {
         let this = self.await?;
         Ok(parse(this.source, Value::new(this.ty), this.transforms))
     }
+
+    /// Whether the module is an async module, i.e. it uses top-level `await`
+    /// or imports async modules. Importers of async modules need to wait for
+    /// their evaluation.
+    ///
+    /// The results of the imported modules are reused, but a module must not
+    /// wait for the result of a module which imports it in a cycle. So results
+    /// are only requested from modules with a lower [rank](Self::async_rank),
+    /// the other imported modules are traversed directly.
+    #[turbo_tasks::function]
+    pub async fn is_async_module(self) -> Result<BoolVc> {
+        let rank = *self.async_rank().await?;
+        let mut visited = HashSet::new();
+        visited.insert(self);
+        let mut queue = vec![self];
+        while let Some(module) = queue.pop() {
+            if *module.has_top_level_await().await? {
+                return Ok(BoolVc::cell(true));
+            }
+            for imported in esm_imported_modules(module).await? {
+                match EcmascriptModuleAssetVc::resolve_from(imported).await? {
+                    Some(imported) => {
+                        if !visited.insert(imported) {
+                            continue;
+                        }
+                        if *imported.async_rank().await? < rank {
+                            if *imported.is_async_module().await? {
+                                return Ok(BoolVc::cell(true));
+                            }
+                        } else {
+                            queue.push(imported);
+                        }
+                    }
+                    // Other assets (e.g. WebAssembly modules) know whether they are async
+                    None => {
+                        if *imported.is_async().await? {
+                            return Ok(BoolVc::cell(true));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(BoolVc::cell(false))
+    }
+
+    /// Orders the modules for [Self::is_async_module]. As modules only wait
+    /// for modules with a lower rank, they never wait for themselves.
+    #[turbo_tasks::function]
+    async fn async_rank(self) -> Result<U64Vc> {
+        Ok(U64Vc::cell(hash_xxh3_hash64(
+            &*self.ident().to_string().await?,
+        )))
+    }
+
+    #[turbo_tasks::function]
+    async fn has_top_level_await(self) -> Result<BoolVc> {
+        Ok(BoolVc::cell(match &*self.parse().await? {
+            ParseResult::Ok { eval_context, .. } => eval_context.has_top_level_await(),
+            _ => false,
+        }))
+    }
+}
+
+/// Returns the assets which are statically imported by `module`, including
+/// the modules matched by eager `import.meta.glob` calls.
+async fn esm_imported_modules(
+    module: EcmascriptModuleAssetVc,
+) -> Result<Vec<EcmascriptChunkPlaceableVc>> {
+    let mut modules = Vec::new();
+    for &reference in module.analyze().await?.references.await?.iter() {
+        if let Some(reference) = EsmAssetReferenceVc::resolve_from(reference).await? {
+            if let ReferencedAsset::Some(placeable) = &*reference.get_referenced_asset().await? {
+                modules.push(*placeable);
+            }
+        } else if let Some(glob) = ImportMetaGlobAssetReferenceVc::resolve_from(reference).await? {
+            if !glob.await?.eager {
+                continue;
+            }
+            for &asset in glob.resolve_reference().primary_assets().await?.iter() {
+                if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? {
+                    modules.push(placeable);
+                }
+            }
+        }
+    }
+    Ok(modules)
 }
 
 #[turbo_tasks::value_impl]
@@ -229,6 +338,11 @@ impl EcmascriptChunkPlaceable for EcmascriptModuleAsset {
     async fn get_exports(self_vc: EcmascriptModuleAssetVc) -> Result<EcmascriptExportsVc> {
         Ok(self_vc.analyze().await?.exports)
     }
+
+    #[turbo_tasks::function]
+    fn is_async(self_vc: EcmascriptModuleAssetVc) -> BoolVc {
+        self_vc.is_async_module()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -273,8 +387,17 @@ impl ChunkItem for ModuleChunkItem {
     }
 
     #[turbo_tasks::function]
-    fn references(&self) -> AssetReferencesVc {
-        self.module.references()
+    async fn references(&self) -> Result<AssetReferencesVc> {
+        let inner_modules = concatenated_modules(self.module, self.context).await?;
+        if inner_modules.is_empty() {
+            return Ok(self.module.references());
+        }
+        // The references of concatenated modules are references of this chunk item
+        let mut references = self.module.references().await?.clone_value();
+        for module in inner_modules.iter() {
+            references.extend(module.references().await?.iter().copied());
+        }
+        Ok(AssetReferencesVc::cell(references))
     }
 }
 
@@ -287,104 +410,34 @@ impl EcmascriptChunkItem for ModuleChunkItem {
 
     #[turbo_tasks::function]
     async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
-        let AnalyzeEcmascriptModuleResult {
-            references,
-            code_generation,
-            ..
-        } = &*self.module.analyze().await?;
-        let context = self.context;
-        let mut code_gens = Vec::new();
-        for r in references.await?.iter() {
-            if let Some(code_gen) = CodeGenerateableVc::resolve_from(r).await? {
-                code_gens.push(code_gen.code_generation(context));
-            }
-        }
-        for c in code_generation.await?.iter() {
-            let c = c.resolve().await?;
-            code_gens.push(c.code_generation(context));
-        }
-        // need to keep that around to allow references into that
-        let code_gens = code_gens.into_iter().try_join().await?;
-        let code_gens = code_gens.iter().map(|cg| &**cg).collect::<Vec<_>>();
-        // TOOD use interval tree with references into "code_gens"
-        let mut visitors = Vec::new();
-        let mut root_visitors = Vec::new();
-        for code_gen in code_gens {
-            for (path, visitor) in code_gen.visitors.iter() {
-                if path.is_empty() {
-                    root_visitors.push(&**visitor);
-                } else {
-                    visitors.push((path, &**visitor));
-                }
-            }
+        let inner_modules = concatenated_modules(self.module, self.context);
+        if !inner_modules.await?.is_empty() {
+            return Ok(concatenated_module_content(
+                self.module,
+                self.context,
+                inner_modules,
+            ));
         }
 
-        let module = self.module.await?;
-        let parsed = parse(module.source, Value::new(module.ty), module.transforms).await?;
+        let parsed = self.module.parse().await?;
 
         if let ParseResult::Ok {
-            program,
             source_map,
             globals,
             eval_context,
             ..
         } = &*parsed
         {
-            let mut program = program.clone();
-
+            let mut program = generate_program(self.module, self.context, &parsed, None).await?;
             GLOBALS.set(globals, || {
-                if !visitors.is_empty() {
-                    program.visit_mut_with_path(
-                        &mut ApplyVisitors::new(visitors),
-                        &mut Default::default(),
-                    );
-                }
-                for visitor in root_visitors {
-                    program.visit_mut_with(&mut visitor.create());
-                }
-                program.visit_mut_with(&mut swc_core::ecma::transforms::base::hygiene::hygiene());
                 program.visit_mut_with(&mut swc_core::ecma::transforms::base::fixer::fixer(None));
-
-                // we need to remove any shebang before bundling as it's only valid as the first
-                // line in a js file (not in a chunk item wrapped in the runtime)
-                remove_shebang(&mut program);
             });
-
-            let mut bytes: Vec<u8> = vec![];
-            // TODO: Insert this as a sourceless segment so that sourcemaps aren't affected.
-            // = format!("/* {} */\n", self.module.path().to_string().await?).into_bytes();
-
-            let mut srcmap = vec![];
-
-            let mut emitter = Emitter {
-                cfg: swc_core::ecma::codegen::Config {
-                    ..Default::default()
-                },
-                cm: source_map.clone(),
-                comments: None,
-                wr: JsWriter::new(source_map.clone(), "\n", &mut bytes, Some(&mut srcmap)),
-            };
-
-            emitter.emit_program(&program)?;
-
-            let srcmap = ParseResultSourceMap::new(source_map.clone(), srcmap).cell();
+            let (code, srcmap) = emit_program(&program, source_map)?;
 
             Ok(EcmascriptChunkItemContent {
-                inner_code: bytes.into(),
-                source_map: Some(srcmap),
-                options: if eval_context.is_esm() {
-                    EcmascriptChunkItemOptions {
-                        ..Default::default()
-                    }
-                } else {
-                    EcmascriptChunkItemOptions {
-                        // These things are not available in ESM
-                        module: true,
-                        exports: true,
-                        this: true,
-                        ..Default::default()
-                    }
-                },
+                inner_code: code.into(),
+                source_map: Some(srcmap.into()),
+                options: chunk_item_options(eval_context, *self.module.is_async_module().await?),
                 ..Default::default()
             }
             .into())
@@ -403,6 +456,142 @@ impl EcmascriptChunkItem for ModuleChunkItem {
     }
 }
 
+/// Applies the code generation of `module` to its program and makes it
+/// hygienic. When `namespace` is set, the exports of the module are defined on
+/// a local object with that name.
+pub(crate) async fn generate_program(
+    module: EcmascriptModuleAssetVc,
+    context: ChunkingContextVc,
+    parsed: &ParseResult,
+    namespace: Option<String>,
+) -> Result<Program> {
+    let ParseResult::Ok {
+        program, globals, ..
+    } = parsed else {
+        bail!(
+            "module {} can't be parsed",
+            module.ident().to_string().await?
+        );
+    };
+
+    let AnalyzeEcmascriptModuleResult {
+        references,
+        code_generation,
+        ..
+    } = &*module.analyze().await?;
+    let mut code_gens = Vec::new();
+    for r in references.await?.iter() {
+        if let Some(code_gen) = CodeGenerateableVc::resolve_from(r).await? {
+            code_gens.push(code_gen.code_generation(context));
+        }
+    }
+    for c in code_generation.await?.iter() {
+        let c = c.resolve().await?;
+        if let Some(esm_exports) = EsmExportsVc::resolve_from(c).await? {
+            let used_exports = context.used_exports(module.ident());
+            code_gens.push(esm_exports.code_generation_with_usage(
+                context,
+                used_exports,
+                namespace.clone(),
+            ));
+        } else {
+            code_gens.push(c.code_generation(context));
+        }
+    }
+    // need to keep that around to allow references into that
+    let code_gens = code_gens.into_iter().try_join().await?;
+    let code_gens = code_gens.iter().map(|cg| &**cg).collect::<Vec<_>>();
+    // TOOD use interval tree with references into "code_gens"
+    let mut visitors = Vec::new();
+    let mut root_visitors = Vec::new();
+    for code_gen in code_gens {
+        for (path, visitor) in code_gen.visitors.iter() {
+            if path.is_empty() {
+                root_visitors.push(&**visitor);
+            } else {
+                visitors.push((path, &**visitor));
+            }
+        }
+    }
+
+    let unused_items = unused_module_items(module, context.used_exports(module.ident())).await?;
+
+    let mut program = program.clone();
+
+    GLOBALS.set(globals, || {
+        if !visitors.is_empty() {
+            program.visit_mut_with_path(&mut ApplyVisitors::new(visitors), &mut Default::default());
+        }
+        // Items are removed after the path visitors are applied, as removing
+        // them changes the paths of the following items
+        if let Program::Module(Module { body, .. }) = &mut program {
+            let mut index = 0;
+            body.retain(|_| {
+                let used = !unused_items.contains(&index);
+                index += 1;
+                used
+            });
+        }
+        for visitor in root_visitors {
+            program.visit_mut_with(&mut visitor.create());
+        }
+        program.visit_mut_with(&mut swc_core::ecma::transforms::base::hygiene::hygiene());
+
+        // we need to remove any shebang before bundling as it's only valid as the first
+        // line in a js file (not in a chunk item wrapped in the runtime)
+        remove_shebang(&mut program);
+    });
+
+    Ok(program)
+}
+
+pub(crate) fn emit_program(
+    program: &Program,
+    source_map: &Arc<SourceMap>,
+) -> Result<(Vec<u8>, ParseResultSourceMapVc)> {
+    let mut bytes: Vec<u8> = vec![];
+    // TODO: Insert this as a sourceless segment so that sourcemaps aren't affected.
+    // = format!("/* {} */\n", self.module.path().to_string().await?).into_bytes();
+
+    let mut srcmap = vec![];
+
+    let mut emitter = Emitter {
+        cfg: swc_core::ecma::codegen::Config {
+            ..Default::default()
+        },
+        cm: source_map.clone(),
+        comments: None,
+        wr: JsWriter::new(source_map.clone(), "\n", &mut bytes, Some(&mut srcmap)),
+    };
+
+    emitter.emit_program(program)?;
+
+    let srcmap = ParseResultSourceMap::new(source_map.clone(), srcmap).cell();
+    Ok((bytes, srcmap))
+}
+
+pub(crate) fn chunk_item_options(
+    eval_context: &EvalContext,
+    async_module: bool,
+) -> EcmascriptChunkItemOptions {
+    if eval_context.is_esm() {
+        EcmascriptChunkItemOptions {
+            // `import.meta.hot` refers to the hot API of the module object
+            module: eval_context.uses_import_meta_hot(),
+            async_module,
+            ..Default::default()
+        }
+    } else {
+        EcmascriptChunkItemOptions {
+            // These things are not available in ESM
+            module: true,
+            exports: true,
+            this: true,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
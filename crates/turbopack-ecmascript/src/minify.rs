@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use sourcemap::SourceMap as CrateMap;
+use swc_core::{
+    common::{input::StringInput, FileName, Globals, Mark, SourceMap, GLOBALS},
+    ecma::{
+        ast::EsVersion,
+        codegen::{text_writer::JsWriter, Emitter},
+        minifier::{
+            optimize,
+            option::{CompressOptions, ExtraOptions, MangleOptions, MinifyOptions},
+        },
+        parser::{lexer::Lexer, Parser, Syntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::FoldWith,
+    },
+};
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{rope::Rope, FileSystemPathVc};
+use turbopack_core::{
+    code_builder::{CodeBuilder, CodeVc},
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+    source_map::{GenerateSourceMap, SourceMapVc},
+};
+
+/// Minifies the code of a chunk. The source map of the minified code is
+/// chained with the source map of the input code, so it still points to the
+/// original sources.
+///
+/// When the code can't be parsed, it's emitted unminified and a warning is
+/// reported.
+#[turbo_tasks::function]
+pub async fn minify(path: FileSystemPathVc, code: CodeVc) -> Result<CodeVc> {
+    let path_value = path.await?;
+    let original_map = code.generate_source_map();
+    let code_value = code.await?;
+
+    let source = code_value.source_code().to_str()?.into_owned();
+    let (minified, map) = match minify_source(&path_value.path, source) {
+        Ok(minified) => minified,
+        Err(err) => {
+            EcmascriptMinifyIssue {
+                path,
+                message: StringVc::cell(format!("{err:#}")),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            return Ok(code);
+        }
+    };
+
+    let map = code_value
+        .has_source_map()
+        .then(|| SourceMapVc::new_regular(map).chain(original_map).into());
+    let mut builder = CodeBuilder::default();
+    builder.push_source(&Rope::from(minified), map);
+    Ok(builder.build().cell())
+}
+
+/// Mangles and compresses `source`. Returns the minified code and its source
+/// map, which points into `source`. Fails when `source` contains any syntax
+/// error.
+fn minify_source(path: &str, source: String) -> Result<(Vec<u8>, CrateMap)> {
+    let cm = Arc::new(SourceMap::default());
+    let fm = cm.new_source_file(FileName::Real(path.into()), source);
+
+    let lexer = Lexer::new(
+        Syntax::default(),
+        EsVersion::latest(),
+        StringInput::from(&*fm),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let program = parser
+        .parse_program()
+        .map_err(|err| anyhow!("{}", err.kind().msg()))?;
+    // The parser recovers from some errors, which would change the code
+    if let Some(err) = parser.take_errors().into_iter().next() {
+        bail!("{}", err.kind().msg());
+    }
+
+    let globals = Globals::new();
+    let program = GLOBALS.set(&globals, || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        let program = program.fold_with(&mut resolver(unresolved_mark, top_level_mark, false));
+        let program = optimize(
+            program,
+            cm.clone(),
+            None,
+            None,
+            &MinifyOptions {
+                compress: Some(CompressOptions {
+                    ..Default::default()
+                }),
+                mangle: Some(MangleOptions {
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            &ExtraOptions {
+                unresolved_mark,
+                top_level_mark,
+            },
+        );
+        program
+            .fold_with(&mut hygiene())
+            .fold_with(&mut fixer(None))
+    });
+
+    let mut bytes: Vec<u8> = vec![];
+    let mut mappings = vec![];
+    let mut emitter = Emitter {
+        cfg: swc_core::ecma::codegen::Config {
+            minify: true,
+            ..Default::default()
+        },
+        cm: cm.clone(),
+        comments: None,
+        wr: JsWriter::new(cm.clone(), "\n", &mut bytes, Some(&mut mappings)),
+    };
+    emitter.emit_program(&program)?;
+
+    let map = cm.build_source_map(&mappings);
+    Ok((bytes, map))
+}
+
+#[turbo_tasks::value(shared)]
+struct EcmascriptMinifyIssue {
+    path: FileSystemPathVc,
+    message: StringVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for EcmascriptMinifyIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("The Ecmascript chunk couldn't be minified".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("code generation".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minify_source;
+
+    #[test]
+    fn minifies_with_source_map() {
+        let source = "function add(first, second) {\n  return first + second;\n}\nif (false)                       {\n  unreachable();\n}\nconsole.log(add(1, 2));\n";
+        let (minified, map) = minify_source("input.js", source.to_string()).unwrap();
+        let minified = String::from_utf8(minified).unwrap();
+
+        // Parameters are mangled and dead branches are dropped.
+        assert!(!minified.contains("second"), "{minified}");
+        assert!(!minified.contains("unreachable"), "{minified}");
+        assert!(minified.len() < source.len());
+
+        // `console` still maps to its original position.
+        let column = minified.find("console").unwrap() as u32;
+        let token = map.lookup_token(0, column).unwrap();
+        assert_eq!(token.get_source(), Some("input.js"));
+        assert_eq!(token.get_src_line(), 6);
+    }
+
+    #[test]
+    fn fails_on_syntax_errors() {
+        let source = "console.log(1;\n";
+        assert!(minify_source("input.js", source.to_string()).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use indexmap::IndexSet;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{ArrayLit, AwaitExpr, CallExpr, Callee, Expr, ExprOrSpread, ExprStmt, Ident, Stmt},
+};
+use turbopack_core::{
+    chunk::{ChunkableAssetReference, ChunkingContextVc, ChunkingType, ModuleId},
+    reference::AssetReference,
+};
+
+use super::{
+    base::{insert_hoisted_stmt, ReferencedAsset},
+    EsmAssetReferenceVc, ImportMetaGlobAssetReferenceVc,
+};
+use crate::{
+    chunk::{EcmascriptChunkItem, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc},
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+};
+
+/// Waits for the async modules imported by an ESM module. The imports of all
+/// modules are evaluated first, so that async modules are evaluated in
+/// parallel, before the module waits for them.
+#[turbo_tasks::value(shared)]
+#[derive(Hash, Debug)]
+pub struct AsyncModule {
+    pub references: Vec<EsmAssetReferenceVc>,
+    /// The eager `import.meta.glob` calls, which import the matched modules
+    /// like import declarations.
+    pub eager_globs: Vec<ImportMetaGlobAssetReferenceVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl AsyncModuleVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        references: Vec<EsmAssetReferenceVc>,
+        eager_globs: Vec<ImportMetaGlobAssetReferenceVc>,
+    ) -> Self {
+        AsyncModule {
+            references,
+            eager_globs,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for AsyncModule {
+    #[turbo_tasks::function]
+    async fn code_generation(&self, context: ChunkingContextVc) -> Result<CodeGenerationVc> {
+        let mut visitors = Vec::new();
+
+        let mut async_ids = IndexSet::new();
+        for reference in self.references.iter() {
+            // Modules which aren't imported separately don't need to be awaited
+            let chunking_type = reference.chunking_type(context).await?;
+            if matches!(*chunking_type, None | Some(ChunkingType::Separate)) {
+                continue;
+            }
+            let referenced_asset = reference.get_referenced_asset().await?;
+            let ReferencedAsset::Some(placeable) = &*referenced_asset else {
+                continue;
+            };
+            if *placeable.is_async().await? {
+                async_ids.insert(placeable.as_chunk_item(context).id().await?.clone_value());
+            }
+        }
+
+        for glob in self.eager_globs.iter() {
+            for &asset in glob.resolve_reference().primary_assets().await?.iter() {
+                let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? else {
+                    continue;
+                };
+                if *placeable.is_async().await? {
+                    async_ids.insert(placeable.as_chunk_item(context).id().await?.clone_value());
+                }
+            }
+        }
+
+        if !async_ids.is_empty() {
+            let ids = async_ids
+                .iter()
+                .map(|id| {
+                    Some(ExprOrSpread {
+                        spread: None,
+                        expr: box Expr::Lit(match id {
+                            ModuleId::String(s) => s.clone().into(),
+                            ModuleId::Number(n) => (*n as f64).into(),
+                        }),
+                    })
+                })
+                .collect::<Vec<_>>();
+            visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
+                let stmt = Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr: box Expr::Await(AwaitExpr {
+                        span: DUMMY_SP,
+                        arg: box Expr::Call(CallExpr {
+                            span: DUMMY_SP,
+                            callee: Callee::Expr(box Expr::Ident(Ident::new(
+                                "__turbopack_async_dependencies__".into(),
+                                DUMMY_SP,
+                            ))),
+                            args: vec![ExprOrSpread {
+                                spread: None,
+                                expr: box Expr::Array(ArrayLit {
+                                    span: DUMMY_SP,
+                                    elems: ids.clone(),
+                                }),
+                            }],
+                            type_args: None,
+                        }),
+                    }),
+                });
+                insert_hoisted_stmt(program, stmt);
+            }));
+        }
+
+        Ok(CodeGeneration { visitors }.into())
+    }
+}
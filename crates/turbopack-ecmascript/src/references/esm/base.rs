@@ -5,15 +5,21 @@ use swc_core::{
     ecma::ast::{Expr, ExprStmt, Ident, Lit, Module, ModuleItem, Program, Script, Stmt},
     quote,
 };
-use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbo_tasks::{
+    primitives::{BoolVc, OptionStringVc, StringVc},
+    Value, ValueToString, ValueToStringVc,
+};
 use turbopack_core::{
     asset::Asset,
     chunk::{
-        ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkingContextVc, ChunkingType,
-        ChunkingTypeOptionVc, ModuleId,
+        ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkingContext, ChunkingContextVc,
+        ChunkingType, ChunkingTypeOptionVc, ModuleId, UsedExports,
     },
     reference::{AssetReference, AssetReferenceVc},
-    resolve::{origin::ResolveOriginVc, parse::RequestVc, PrimaryResolveResult, ResolveResultVc},
+    resolve::{
+        origin::ResolveOriginVc, parse::RequestVc, PrimaryResolveResult, ResolveResult,
+        ResolveResultVc,
+    },
 };
 
 use crate::{
@@ -23,6 +29,7 @@ use crate::{
     create_visitor, magic_identifier,
     references::util::{request_to_string, throw_module_not_found_expr},
     resolve::esm_resolve,
+    side_effects::{follow_reexports, is_marked_side_effect_free},
 };
 
 #[turbo_tasks::value]
@@ -95,6 +102,10 @@ pub struct EsmAssetReference {
     pub origin: ResolveOriginVc,
     pub request: RequestVc,
     pub annotations: ImportAnnotations,
+    /// When set, only this export is imported. For modules which are marked
+    /// as side effect free, the reference then points directly to the module
+    /// which defines the export instead of re-exporting modules.
+    pub export_name: Option<String>,
 }
 
 impl EsmAssetReference {
@@ -110,12 +121,77 @@ impl EsmAssetReference {
 #[turbo_tasks::value_impl]
 impl EsmAssetReferenceVc {
     #[turbo_tasks::function]
-    pub(super) async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
+    pub(crate) async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
         let this = self.await?;
-        Ok(ReferencedAssetVc::from_resolve_result(
+        let referenced_asset = ReferencedAssetVc::from_resolve_result(
             esm_resolve(this.get_origin(), this.request),
             this.request,
-        ))
+        );
+        if let Some(export_name) = &this.export_name {
+            if let ReferencedAsset::Some(asset) = &*referenced_asset.await? {
+                let followed = follow_reexports(*asset, export_name.clone()).await?;
+                return Ok(ReferencedAsset::Some(followed.module).cell());
+            }
+        }
+        Ok(referenced_asset)
+    }
+
+    /// Returns the name of the export in the module returned by
+    /// [EsmAssetReferenceVc::get_referenced_asset], when `export` is imported
+    /// from the requested module.
+    #[turbo_tasks::function]
+    pub(crate) async fn get_referenced_export(
+        self,
+        export: Option<String>,
+    ) -> Result<OptionStringVc> {
+        let this = self.await?;
+        if let Some(export_name) = &this.export_name {
+            let referenced_asset = ReferencedAssetVc::from_resolve_result(
+                esm_resolve(this.get_origin(), this.request),
+                this.request,
+            );
+            if let ReferencedAsset::Some(asset) = &*referenced_asset.await? {
+                let followed = follow_reexports(*asset, export_name.clone()).await?;
+                return Ok(OptionStringVc::cell(followed.export_name.clone()));
+            }
+        }
+        Ok(OptionStringVc::cell(export))
+    }
+
+    /// Checks whether the requested module is marked as side effect free.
+    #[turbo_tasks::function]
+    pub(crate) async fn is_side_effect_free(self) -> Result<BoolVc> {
+        Ok(match &*self.get_referenced_asset().await? {
+            ReferencedAsset::Some(asset) => is_marked_side_effect_free(asset.ident().path()),
+            _ => BoolVc::cell(false),
+        })
+    }
+
+    /// Checks whether the requested module is side effect free and none of its
+    /// exports are used, so it doesn't need to be included at all.
+    #[turbo_tasks::function]
+    pub(crate) async fn is_unused(self, context: ChunkingContextVc) -> Result<BoolVc> {
+        let ReferencedAsset::Some(asset) = &*self.get_referenced_asset().await? else {
+            return Ok(BoolVc::cell(false));
+        };
+        let unused = match &*context.used_exports(asset.ident()).await? {
+            UsedExports::Names(names) => names.is_empty() && *self.is_side_effect_free().await?,
+            UsedExports::All => false,
+        };
+        Ok(BoolVc::cell(unused))
+    }
+
+    /// Creates a reference which only imports `export_name` from the requested
+    /// module.
+    #[turbo_tasks::function]
+    pub(crate) async fn followed(self, export_name: String) -> Result<Self> {
+        let this = self.await?;
+        Ok(Self::cell(EsmAssetReference {
+            origin: this.origin,
+            request: this.request,
+            annotations: this.annotations.clone(),
+            export_name: Some(export_name),
+        }))
     }
 
     #[turbo_tasks::function]
@@ -123,11 +199,13 @@ impl EsmAssetReferenceVc {
         origin: ResolveOriginVc,
         request: RequestVc,
         annotations: Value<ImportAnnotations>,
+        export_name: Option<String>,
     ) -> Self {
         Self::cell(EsmAssetReference {
             origin,
             request,
             annotations: annotations.into_value(),
+            export_name,
         })
     }
 }
@@ -135,8 +213,19 @@ impl EsmAssetReferenceVc {
 #[turbo_tasks::value_impl]
 impl AssetReference for EsmAssetReference {
     #[turbo_tasks::function]
-    fn resolve_reference(&self) -> ResolveResultVc {
-        esm_resolve(self.get_origin(), self.request)
+    async fn resolve_reference(self_vc: EsmAssetReferenceVc) -> Result<ResolveResultVc> {
+        let this = self_vc.await?;
+        let result = esm_resolve(this.get_origin(), this.request);
+        if this.export_name.is_some() {
+            if let ReferencedAsset::Some(asset) = &*self_vc.get_referenced_asset().await? {
+                return Ok(ResolveResult::asset_with_references(
+                    (*asset).into(),
+                    result.await?.references.clone(),
+                )
+                .cell());
+            }
+        }
+        Ok(result)
     }
 }
 
@@ -145,9 +234,13 @@ impl ValueToString for EsmAssetReference {
     #[turbo_tasks::function]
     async fn to_string(&self) -> Result<StringVc> {
         Ok(StringVc::cell(format!(
-            "import {} {}",
+            "import {} {}{}",
             self.request.to_string().await?,
-            self.annotations
+            self.annotations,
+            self.export_name
+                .as_ref()
+                .map(|export_name| format!(" (export {export_name})"))
+                .unwrap_or_default()
         )))
     }
 }
@@ -155,19 +248,31 @@ impl ValueToString for EsmAssetReference {
 #[turbo_tasks::value_impl]
 impl ChunkableAssetReference for EsmAssetReference {
     #[turbo_tasks::function]
-    fn chunking_type(&self, _context: ChunkingContextVc) -> Result<ChunkingTypeOptionVc> {
-        Ok(ChunkingTypeOptionVc::cell(
-            if let Some(chunking_type) = self.annotations.chunking_type() {
-                match chunking_type {
-                    "separate" => Some(ChunkingType::Separate),
-                    "parallel" => Some(ChunkingType::Parallel),
-                    "none" => None,
-                    _ => return Err(anyhow!("unknown chunking_type: {}", chunking_type)),
-                }
-            } else {
-                Some(ChunkingType::default())
-            },
-        ))
+    async fn chunking_type(
+        self_vc: EsmAssetReferenceVc,
+        context: ChunkingContextVc,
+    ) -> Result<ChunkingTypeOptionVc> {
+        let this = self_vc.await?;
+        if let Some(chunking_type) = this.annotations.chunking_type() {
+            return Ok(ChunkingTypeOptionVc::cell(match chunking_type {
+                "separate" => Some(ChunkingType::Separate),
+                "parallel" => Some(ChunkingType::Parallel),
+                "none" => None,
+                _ => return Err(anyhow!("unknown chunking_type: {}", chunking_type)),
+            }));
+        }
+
+        if *self_vc.is_unused(context).await? {
+            return Ok(ChunkingTypeOptionVc::cell(None));
+        }
+        // Modules which are concatenated into another module are part of the
+        // chunk item of that module
+        if let ReferencedAsset::Some(asset) = &*self_vc.get_referenced_asset().await? {
+            if context.concatenation_root(asset.ident()).await?.is_some() {
+                return Ok(ChunkingTypeOptionVc::cell(None));
+            }
+        }
+        Ok(ChunkingTypeOptionVc::cell(Some(ChunkingType::default())))
     }
 }
 
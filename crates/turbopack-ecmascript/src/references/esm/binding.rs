@@ -75,6 +75,10 @@ impl CodeGenerateable for EsmBinding {
 
         let mut ast_path = this.ast_path.await?.clone_value();
         let imported_module = imported_module.await?.get_ident().await?;
+        let export = this
+            .reference
+            .get_referenced_export(this.export.clone())
+            .await?;
 
         loop {
             match ast_path.last() {
@@ -83,7 +87,7 @@ impl CodeGenerateable for EsmBinding {
                     visitors.push(
                         create_visitor!(exact ast_path, visit_mut_expr(expr: &mut Expr) {
                             if let Some(ident) = imported_module.as_deref() {
-                              *expr = make_expr(ident, this.export.as_deref());
+                              *expr = make_expr(ident, export.as_deref());
                             }
                             // If there's no identifier for the imported module,
                             // resolution failed and will insert code that throws
@@ -99,7 +103,7 @@ impl CodeGenerateable for EsmBinding {
                             if let Prop::Shorthand(ident) = prop {
                                 // TODO: Merge with the above condition when https://rust-lang.github.io/rfcs/2497-if-let-chains.html lands.
                                 if let Some(imported_ident) = imported_module.as_deref() {
-                                    *prop = Prop::KeyValue(KeyValueProp { key: PropName::Ident(ident.clone()), value: box make_expr(imported_ident, this.export.as_deref())});
+                                    *prop = Prop::KeyValue(KeyValueProp { key: PropName::Ident(ident.clone()), value: box make_expr(imported_ident, export.as_deref())});
                                 }
                             }
                         }),
@@ -8,15 +8,16 @@ use serde::{Deserialize, Serialize};
 use swc_core::{
     common::DUMMY_SP,
     ecma::ast::{
-        ComputedPropName, Expr, ExprStmt, Ident, KeyValueProp, Lit, MemberExpr, MemberProp, Module,
-        ModuleItem, ObjectLit, Program, Prop, PropName, PropOrSpread, Script, Stmt, Str,
+        BlockStmt, BlockStmtOrExpr, CallExpr, Callee, ComputedPropName, Expr, ExprStmt, GetterProp,
+        Ident, KeyValueProp, Lit, MemberExpr, MemberProp, Module, ModuleItem, ObjectLit, ParenExpr,
+        Program, Prop, PropName, PropOrSpread, ReturnStmt, Script, Stmt, Str,
     },
     quote, quote_expr,
 };
 use turbo_tasks::{primitives::StringVc, trace::TraceRawVcs, ValueToString};
 use turbopack_core::{
     asset::Asset,
-    chunk::ChunkingContextVc,
+    chunk::{ChunkingContextVc, UsedExports, UsedExportsVc},
     issue::{analyze::AnalyzeIssue, IssueSeverity},
 };
 
@@ -37,13 +38,15 @@ pub enum EsmExport {
 }
 
 #[turbo_tasks::value]
-struct ExpandResults {
-    star_exports: Vec<String>,
-    has_cjs_exports: bool,
+pub(crate) struct ExpandResults {
+    pub(crate) star_exports: Vec<String>,
+    pub(crate) has_cjs_exports: bool,
 }
 
 #[turbo_tasks::function]
-async fn expand_star_exports(root_asset: EcmascriptChunkPlaceableVc) -> Result<ExpandResultsVc> {
+pub(crate) async fn expand_star_exports(
+    root_asset: EcmascriptChunkPlaceableVc,
+) -> Result<ExpandResultsVc> {
     let mut set = HashSet::new();
     let mut has_cjs_exports = false;
     let mut checked_assets = HashSet::new();
@@ -133,13 +136,20 @@ pub struct EsmExports {
 }
 
 #[turbo_tasks::value_impl]
-impl CodeGenerateable for EsmExports {
+impl EsmExportsVc {
+    /// Generates the code for the exports, omitting all exports which are not
+    /// in `used_exports`. When `namespace` is set, the exports are defined on a
+    /// local object with that name instead of the exports of the module, which
+    /// is used when the module is concatenated into another module.
     #[turbo_tasks::function]
-    async fn code_generation(
-        self_vc: EsmExportsVc,
+    pub async fn code_generation_with_usage(
+        self,
         _context: ChunkingContextVc,
+        used_exports: UsedExportsVc,
+        namespace: Option<String>,
     ) -> Result<CodeGenerationVc> {
-        let this = self_vc.await?;
+        let this = self.await?;
+        let used_exports = used_exports.await?;
         let mut visitors = Vec::new();
 
         let mut all_exports: BTreeMap<Cow<str>, Cow<EsmExport>> = this
@@ -174,6 +184,9 @@ impl CodeGenerateable for EsmExports {
             }
         }
         for (exported, local) in all_exports.into_iter() {
+            if !used_exports.is_used(&exported) {
+                continue;
+            }
             let expr = match local.as_ref() {
                 EsmExport::Error => Some(quote!(
                     "(() => { throw new Error(\"Failed binding. See build errors!\"); })" as Expr,
@@ -184,21 +197,28 @@ impl CodeGenerateable for EsmExports {
                 )),
                 EsmExport::ImportedBinding(esm_ref, name) => {
                     let referenced_asset = esm_ref.get_referenced_asset().await?;
+                    // Followed references point to the module defining the export,
+                    // which might export it under another name or as namespace
+                    let name = esm_ref.get_referenced_export(Some(name.clone())).await?;
                     referenced_asset.get_ident().await?.map(|ident| {
+                        let imported = Expr::Ident(Ident::new(ident.into(), DUMMY_SP));
                         quote!(
                             "(() => $expr)" as Expr,
-                            expr: Expr = Expr::Member(MemberExpr {
-                                span: DUMMY_SP,
-                                obj: box Expr::Ident(Ident::new(ident.into(), DUMMY_SP)),
-                                prop: MemberProp::Computed(ComputedPropName {
+                            expr: Expr = match name.as_deref() {
+                                Some(name) => Expr::Member(MemberExpr {
                                     span: DUMMY_SP,
-                                    expr: box Expr::Lit(Lit::Str(Str {
+                                    obj: box imported,
+                                    prop: MemberProp::Computed(ComputedPropName {
                                         span: DUMMY_SP,
-                                        value: (name as &str).into(),
-                                        raw: None,
-                                    }))
-                                })
-                            })
+                                        expr: box Expr::Lit(Lit::Str(Str {
+                                            span: DUMMY_SP,
+                                            value: name.into(),
+                                            raw: None,
+                                        }))
+                                    })
+                                }),
+                                None => imported,
+                            }
                         )
                     })
                 }
@@ -213,14 +233,19 @@ impl CodeGenerateable for EsmExports {
                 }
             };
             if let Some(expr) = expr {
-                props.push(PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp {
-                    key: PropName::Str(Str {
-                        span: DUMMY_SP,
-                        value: exported.as_ref().into(),
-                        raw: None,
-                    }),
-                    value: box expr,
-                })));
+                let key = PropName::Str(Str {
+                    span: DUMMY_SP,
+                    value: exported.as_ref().into(),
+                    raw: None,
+                });
+                props.push(PropOrSpread::Prop(box if namespace.is_some() {
+                    getter_prop(key, expr)
+                } else {
+                    Prop::KeyValue(KeyValueProp {
+                        key,
+                        value: box expr,
+                    })
+                }));
             }
         }
         let getters = Expr::Object(ObjectLit {
@@ -237,9 +262,16 @@ impl CodeGenerateable for EsmExports {
         };
 
         visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
-            let stmt = quote!("__turbopack_esm__($getters);" as Stmt,
-                getters: Expr = getters.clone()
-            );
+            let stmt = if let Some(namespace) = &namespace {
+                quote!("var $namespace = $getters;" as Stmt,
+                    namespace = Ident::new(namespace.as_str().into(), DUMMY_SP),
+                    getters: Expr = getters.clone()
+                )
+            } else {
+                quote!("__turbopack_esm__($getters);" as Stmt,
+                    getters: Expr = getters.clone()
+                )
+            };
             match program {
                 Program::Module(Module { body, .. }) => {
                     body.insert(0, ModuleItem::Stmt(stmt));
@@ -256,3 +288,47 @@ impl CodeGenerateable for EsmExports {
         Ok(CodeGeneration { visitors }.into())
     }
 }
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for EsmExports {
+    #[turbo_tasks::function]
+    fn code_generation(self_vc: EsmExportsVc, context: ChunkingContextVc) -> CodeGenerationVc {
+        self_vc.code_generation_with_usage(context, UsedExports::All.cell(), None)
+    }
+}
+
+/// Turns a getter function like `() => expr` into a getter property.
+fn getter_prop(key: PropName, getter: Expr) -> Prop {
+    let body = match getter {
+        Expr::Paren(ParenExpr {
+            expr: box Expr::Arrow(arrow),
+            ..
+        })
+        | Expr::Arrow(arrow) => match arrow.body {
+            BlockStmtOrExpr::BlockStmt(block) => block,
+            BlockStmtOrExpr::Expr(expr) => return_block(expr),
+        },
+        getter => return_block(box Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: Callee::Expr(box getter),
+            args: vec![],
+            type_args: None,
+        })),
+    };
+    Prop::Getter(GetterProp {
+        span: DUMMY_SP,
+        key,
+        type_ann: None,
+        body: Some(body),
+    })
+}
+
+fn return_block(expr: Box<Expr>) -> BlockStmt {
+    BlockStmt {
+        span: DUMMY_SP,
+        stmts: vec![Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(expr),
+        })],
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{Expr, KeyValueProp, Lit, ObjectLit, Prop, PropName, PropOrSpread},
+    quote_expr,
+};
+use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbo_tasks_fs::{glob::GlobVc, DirectoryEntry, FileSystemPathVc};
+use turbopack_core::{
+    chunk::{
+        ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkingContextVc, ChunkingType,
+        ChunkingTypeOptionVc,
+    },
+    reference::{AssetReference, AssetReferenceVc},
+    resolve::{
+        origin::{ResolveOrigin, ResolveOriginVc},
+        parse::RequestVc,
+        pattern::Pattern,
+        ResolveResultVc,
+    },
+};
+
+use super::super::pattern_mapping::{
+    PatternMappingVc,
+    ResolveType::{Cjs, EsmAsync},
+};
+use crate::{
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::AstPathVc,
+    resolve::esm_resolve,
+};
+
+/// Splits a relative glob pattern like `./pages/**/*.tsx` into the directory
+/// without glob characters (`./pages`) and the glob relative to it
+/// (`**/*.tsx`). Returns [None] for unsupported patterns, which includes
+/// patterns using `?` or `[...]` as [GlobVc] doesn't implement them.
+pub(crate) fn split_glob(pattern: &str) -> Option<(&str, &str)> {
+    if !pattern.starts_with("./") && !pattern.starts_with("../") {
+        return None;
+    }
+    if pattern.contains(['?', '[']) {
+        return None;
+    }
+    let glob_start = pattern
+        .find(|c| matches!(c, '*' | '{'))
+        .unwrap_or(pattern.len());
+    let base_end = pattern[..glob_start].rfind('/')?;
+    Some((&pattern[..base_end], &pattern[base_end + 1..]))
+}
+
+/// The requests matched by an `import.meta.glob` call, keyed by the path as
+/// written in the importing module (e.g. `./pages/index.tsx`).
+#[turbo_tasks::value(transparent)]
+pub struct ImportMetaGlobMap(IndexMap<String, RequestVc>);
+
+#[turbo_tasks::value_impl]
+impl ImportMetaGlobMapVc {
+    /// Matches the `patterns` against the files relative to `dir`. Reading
+    /// the directories makes the map update when files are added or removed.
+    #[turbo_tasks::function]
+    pub async fn generate(dir: FileSystemPathVc, patterns: Vec<String>) -> Result<Self> {
+        // The directory listing is unordered, sort it to keep the output stable
+        let mut map = BTreeMap::new();
+        for pattern in patterns.iter() {
+            let Some((base, glob)) = split_glob(pattern) else {
+                continue;
+            };
+            let mut queue = vec![dir.join(base).read_glob(GlobVc::new(glob), false)];
+            while let Some(result) = queue.pop() {
+                let result = result.await?;
+                for (path, entry) in result.results.iter() {
+                    if let DirectoryEntry::File(_) = entry {
+                        let key = format!("{base}/{path}");
+                        let request = RequestVc::parse(Value::new(Pattern::Constant(key.clone())));
+                        map.insert(key, request);
+                    }
+                }
+                queue.extend(result.inner.values().copied());
+            }
+        }
+
+        Ok(Self::cell(map.into_iter().collect()))
+    }
+}
+
+/// A reference to all modules matched by an `import.meta.glob` call. The call
+/// is replaced with an object which maps the matched paths to the modules, or
+/// to functions which load them when the glob isn't `eager`.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct ImportMetaGlobAssetReference {
+    pub origin: ResolveOriginVc,
+    pub patterns: Vec<String>,
+    pub eager: bool,
+    /// The export to select from every module instead of the namespace.
+    pub import: Option<String>,
+    pub path: AstPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMetaGlobAssetReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolveOriginVc,
+        patterns: Vec<String>,
+        eager: bool,
+        import: Option<String>,
+        path: AstPathVc,
+    ) -> Self {
+        Self::cell(ImportMetaGlobAssetReference {
+            origin,
+            patterns,
+            eager,
+            import,
+            path,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn map(self) -> Result<ImportMetaGlobMapVc> {
+        let this = self.await?;
+        Ok(ImportMetaGlobMapVc::generate(
+            this.origin.origin_path().parent(),
+            this.patterns.clone(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(self_vc: ImportMetaGlobAssetReferenceVc) -> Result<ResolveResultVc> {
+        let origin = self_vc.await?.origin;
+        Ok(ResolveResultVc::alternatives(
+            self_vc
+                .map()
+                .await?
+                .values()
+                .map(|request| esm_resolve(origin, *request))
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(format!(
+            "import.meta.glob {}{}",
+            self.patterns.join(", "),
+            if self.eager { " (eager)" } else { "" }
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAssetReference for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    fn chunking_type(&self, _context: ChunkingContextVc) -> ChunkingTypeOptionVc {
+        ChunkingTypeOptionVc::cell(Some(if self.eager {
+            ChunkingType::default()
+        } else {
+            ChunkingType::SeparateAsync
+        }))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        self_vc: ImportMetaGlobAssetReferenceVc,
+        context: ChunkingContextVc,
+    ) -> Result<CodeGenerationVc> {
+        let this = self_vc.await?;
+        let mut props = Vec::new();
+        for (key, request) in self_vc.map().await?.iter() {
+            let pm = PatternMappingVc::resolve_request(
+                *request,
+                this.origin,
+                context,
+                esm_resolve(this.origin, *request),
+                Value::new(if this.eager { Cjs } else { EsmAsync }),
+            )
+            .await?;
+            // Matched files are local modules, they are never external
+            if !pm.is_internal_import() {
+                continue;
+            }
+            let id = pm.create();
+            let value = match (this.eager, &this.import) {
+                (true, None) => quote_expr!("__turbopack_import__($id)", id: Expr = id),
+                (true, Some(import)) => quote_expr!(
+                    "__turbopack_import__($id)[$import]",
+                    id: Expr = id,
+                    import: Expr = Expr::Lit(Lit::Str(import.as_str().into())),
+                ),
+                (false, None) => quote_expr!(
+                    "() => __turbopack_require__($id)(__turbopack_import__)",
+                    id: Expr = id,
+                ),
+                (false, Some(import)) => quote_expr!(
+                    "() => __turbopack_require__($id)(__turbopack_import__).then((m) => \
+                     m[$import])",
+                    id: Expr = id,
+                    import: Expr = Expr::Lit(Lit::Str(import.as_str().into())),
+                ),
+            };
+            props.push(PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp {
+                key: PropName::Str(key.as_str().into()),
+                value,
+            })));
+        }
+        let object = Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        });
+
+        let path = &this.path.await?;
+        let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+            *expr = object.clone();
+        });
+
+        Ok(CodeGeneration {
+            visitors: vec![visitor],
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_glob;
+
+    #[test]
+    fn splits_globs() {
+        assert_eq!(
+            split_glob("./pages/**/*.tsx"),
+            Some(("./pages", "**/*.tsx"))
+        );
+        assert_eq!(split_glob("./*.js"), Some((".", "*.js")));
+        assert_eq!(
+            split_glob("../locales/{en,de}.json"),
+            Some(("../locales", "{en,de}.json"))
+        );
+        assert_eq!(split_glob("./dir/file.js"), Some(("./dir", "file.js")));
+    }
+
+    #[test]
+    fn rejects_unsupported_globs() {
+        assert_eq!(split_glob("pages/*.tsx"), None);
+        assert_eq!(split_glob("/pages/*.tsx"), None);
+        assert_eq!(split_glob("./pages/?.tsx"), None);
+        assert_eq!(split_glob("./pages/[id].tsx"), None);
+    }
+}
@@ -24,13 +24,16 @@ use crate::{
 #[derive(Hash, Debug)]
 pub struct ImportMetaBinding {
     path: FileSystemPathVc,
+    /// Whether `import.meta.hot` is used, which is the hot API of the module
+    /// object.
+    hot: bool,
 }
 
 #[turbo_tasks::value_impl]
 impl ImportMetaBindingVc {
     #[turbo_tasks::function]
-    pub fn new(path: FileSystemPathVc) -> Self {
-        ImportMetaBinding { path }.cell()
+    pub fn new(path: FileSystemPathVc, hot: bool) -> Self {
+        ImportMetaBinding { path, hot }.cell()
     }
 }
 
@@ -48,12 +51,21 @@ impl CodeGenerateable for ImportMetaBinding {
             |path| format!("file://{}", encode_path(path)).into(),
         );
 
+        let hot = self.hot;
         let visitor = create_visitor!(visit_mut_program(program: &mut Program) {
-            let meta = quote!(
-                "const $name = { url: $path };" as Stmt,
-                name = meta_ident(),
-                path: Expr = path.clone(),
-            );
+            let meta = if hot {
+                quote!(
+                    "const $name = { url: $path, hot: module.hot };" as Stmt,
+                    name = meta_ident(),
+                    path: Expr = path.clone(),
+                )
+            } else {
+                quote!(
+                    "const $name = { url: $path };" as Stmt,
+                    name = meta_ident(),
+                    path: Expr = path.clone(),
+                )
+            };
             insert_hoisted_stmt(program, meta);
         });
 
@@ -1,17 +1,21 @@
+pub(crate) mod async_module;
 pub(crate) mod base;
 pub(crate) mod binding;
 pub(crate) mod dynamic;
 pub(crate) mod export;
+pub(crate) mod glob;
 pub(crate) mod meta;
 pub(crate) mod module_id;
 pub(crate) mod module_item;
 pub(crate) mod url;
 
 pub use self::{
+    async_module::{AsyncModule, AsyncModuleVc},
     base::{EsmAssetReference, EsmAssetReferenceVc},
     binding::{EsmBinding, EsmBindingVc},
     dynamic::{EsmAsyncAssetReference, EsmAsyncAssetReferenceVc},
-    export::{EsmExports, EsmExportsVc},
+    export::{EsmExport, EsmExports, EsmExportsVc},
+    glob::{ImportMetaGlobAssetReference, ImportMetaGlobAssetReferenceVc},
     meta::{ImportMetaBinding, ImportMetaBindingVc, ImportMetaRef, ImportMetaRefVc},
     module_item::{EsmModuleItem, EsmModuleItemVc},
     url::{UrlAssetReference, UrlAssetReferenceVc},
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrayLit, CallExpr, Callee, Expr, ExprOrSpread, Ident, Lit, MemberExpr, MemberProp, SeqExpr,
+    },
+    quote,
+};
+use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbopack_core::{
+    chunk::{ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkingContextVc},
+    reference::{AssetReference, AssetReferenceVc},
+    resolve::{origin::ResolveOriginVc, parse::RequestVc, pattern::Pattern, ResolveResultVc},
+};
+
+use super::{
+    esm::base::{EsmAssetReferenceVc, ReferencedAsset},
+    pattern_mapping::{PatternMapping, PatternMappingVc, ResolveType::Cjs},
+};
+use crate::{
+    chunk::{EcmascriptChunkItem, EcmascriptChunkPlaceable},
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::AstPathVc,
+    resolve::{cjs_resolve, esm_resolve},
+    utils::module_id_to_lit,
+};
+
+/// The dependencies passed to `module.hot.accept(deps, ...)` or
+/// `module.hot.decline(deps)`. The requests are replaced with the ids of the
+/// modules, which the runtime uses to find the accepting module of an update.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct HotDependenciesAssetReference {
+    pub origin: ResolveOriginVc,
+    pub requests: Vec<String>,
+    /// Whether the dependencies are resolved like imports (`import.meta.hot`)
+    /// or like requires (`module.hot`).
+    pub esm: bool,
+    /// The imports of the module which refer to accepted dependencies. Like
+    /// webpack does for ESM, they are imported again before the accept
+    /// callback is called, so the module uses the updated exports.
+    pub imports: Vec<EsmAssetReferenceVc>,
+    pub path: AstPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl HotDependenciesAssetReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolveOriginVc,
+        requests: Vec<String>,
+        esm: bool,
+        imports: Vec<EsmAssetReferenceVc>,
+        path: AstPathVc,
+    ) -> Self {
+        Self::cell(HotDependenciesAssetReference {
+            origin,
+            requests,
+            esm,
+            imports,
+            path,
+        })
+    }
+}
+
+impl HotDependenciesAssetReference {
+    fn resolve(&self, request: &str) -> (RequestVc, ResolveResultVc) {
+        let request = RequestVc::parse(Value::new(Pattern::Constant(request.to_string())));
+        let result = if self.esm {
+            esm_resolve(self.origin, request)
+        } else {
+            cjs_resolve(self.origin, request)
+        };
+        (request, result)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for HotDependenciesAssetReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(&self) -> ResolveResultVc {
+        ResolveResultVc::alternatives(
+            self.requests
+                .iter()
+                .map(|request| self.resolve(request).1)
+                .collect(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for HotDependenciesAssetReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(format!("module.hot {}", self.requests.join(", ")))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAssetReference for HotDependenciesAssetReference {}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for HotDependenciesAssetReference {
+    #[turbo_tasks::function]
+    async fn code_generation(&self, context: ChunkingContextVc) -> Result<CodeGenerationVc> {
+        let mut ids = Vec::new();
+        for request in self.requests.iter() {
+            let (request_vc, result) = self.resolve(request);
+            let pm = PatternMappingVc::resolve_request(
+                request_vc,
+                self.origin,
+                context,
+                result,
+                Value::new(Cjs),
+            )
+            .await?;
+            // Unresolveable dependencies are left in place, they never match
+            // the id of an updated module
+            ids.push(match &*pm {
+                PatternMapping::Single(_) => pm.create(),
+                _ => Expr::Lit(Lit::Str(request.as_str().into())),
+            });
+        }
+
+        let array = Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: ids
+                .iter()
+                .map(|id| {
+                    Some(ExprOrSpread {
+                        spread: None,
+                        expr: box id.clone(),
+                    })
+                })
+                .collect(),
+        });
+
+        let mut reimports = Vec::new();
+        for import in self.imports.iter() {
+            if let ReferencedAsset::Some(asset) = &*import.get_referenced_asset().await? {
+                let ident = ReferencedAsset::get_ident_from_placeable(asset).await?;
+                let id = asset.as_chunk_item(context).id().await?;
+                reimports.push(box quote!(
+                    "$name = __turbopack_import__($id)" as Expr,
+                    name = Ident::new(ident.into(), DUMMY_SP),
+                    id: Expr = module_id_to_lit(&id),
+                ));
+            }
+        }
+        let reimport = (!reimports.is_empty()).then(|| {
+            Expr::Seq(SeqExpr {
+                span: DUMMY_SP,
+                exprs: reimports,
+            })
+        });
+
+        let path = &self.path.await?;
+        let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+            if let Expr::Call(CallExpr { callee, args, .. }) = expr {
+                if let Some(ExprOrSpread { expr: first, spread: None }) = args.first_mut() {
+                    *first = match &**first {
+                        Expr::Array(_) => box array.clone(),
+                        _ => box ids[0].clone(),
+                    };
+                }
+                let is_accept = matches!(
+                    callee,
+                    Callee::Expr(box Expr::Member(MemberExpr { prop: MemberProp::Ident(method), .. }))
+                        if &*method.sym == "accept"
+                );
+                if let (true, Some(reimport)) = (is_accept, &reimport) {
+                    // Wraps the callback like webpack's harmony accept, so the
+                    // updated dependencies are imported before it is called
+                    let callback = match args.get(1) {
+                        Some(ExprOrSpread { expr: callback, spread: None }) => Some(quote!(
+                            "(function() { $reimport; return ($callback).apply(this, arguments); })"
+                                as Expr,
+                            reimport: Expr = reimport.clone(),
+                            callback: Expr = *callback.clone(),
+                        )),
+                        None => Some(quote!(
+                            "(function() { $reimport; })" as Expr,
+                            reimport: Expr = reimport.clone(),
+                        )),
+                        Some(_) => None,
+                    };
+                    if let Some(callback) = callback {
+                        let callback = ExprOrSpread { spread: None, expr: box callback };
+                        if args.len() > 1 {
+                            args[1] = callback;
+                        } else {
+                            args.push(callback);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(CodeGeneration {
+            visitors: vec![visitor],
+        }
+        .into())
+    }
+}
@@ -2,12 +2,15 @@ pub mod amd;
 pub mod cjs;
 pub mod constant_condition;
 pub mod esm;
+pub mod hot;
 pub mod node;
 pub mod pattern_mapping;
 pub mod raw;
+pub mod require_context;
 pub mod typescript;
 pub mod unreachable;
 pub mod util;
+pub mod worker;
 
 use std::{
     borrow::Cow,
@@ -20,6 +23,7 @@ use std::{
 
 use anyhow::Result;
 use constant_condition::{ConstantConditionValue, ConstantConditionVc};
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use parking_lot::Mutex;
 use regex::Regex;
@@ -32,7 +36,10 @@ use swc_core::{
     },
     ecma::{
         ast::*,
-        visit::{AstParentKind, AstParentNodeRef, VisitAstPath, VisitWithPath},
+        visit::{
+            fields::{ExprField, ExprOrSpreadField, NewExprField},
+            AstParentKind, AstParentNodeRef, VisitAstPath, VisitWithPath,
+        },
     },
 };
 use turbo_tasks::{TryJoinIterExt, Value};
@@ -61,8 +68,9 @@ use self::{
     },
     cjs::CjsAssetReferenceVc,
     esm::{
-        export::EsmExport, EsmAssetReferenceVc, EsmAsyncAssetReferenceVc, EsmExports,
-        EsmModuleItemVc, ImportMetaBindingVc, ImportMetaRefVc, UrlAssetReferenceVc,
+        export::EsmExport, glob::split_glob, AsyncModuleVc, EsmAssetReferenceVc,
+        EsmAsyncAssetReferenceVc, EsmExports, EsmModuleItemVc, ImportMetaBindingVc,
+        ImportMetaGlobAssetReferenceVc, ImportMetaRefVc, UrlAssetReferenceVc,
     },
     node::{DirAssetReferenceVc, PackageJsonReferenceVc},
     raw::SourceAssetReferenceVc,
@@ -76,7 +84,8 @@ use super::{
         graph::{create_graph, Effect},
         linker::link,
         well_known::replace_well_known,
-        FreeVarKind, JsValue, ObjectPart, WellKnownFunctionKind, WellKnownObjectKind,
+        ConstantValue, FreeVarKind, JsValue, ObjectPart, WellKnownFunctionKind,
+        WellKnownObjectKind,
     },
     errors,
     parse::{parse, ParseResult},
@@ -104,9 +113,13 @@ use crate::{
             CjsRequireAssetReferenceVc, CjsRequireCacheAccess, CjsRequireResolveAssetReferenceVc,
         },
         esm::{module_id::EsmModuleIdAssetReferenceVc, EsmBindingVc, EsmExportsVc},
+        hot::HotDependenciesAssetReferenceVc,
+        require_context::{regex_from_js, RequireContextAssetReferenceVc},
+        worker::WorkerAssetReferenceVc,
     },
+    resolve::esm_resolve,
     typescript::resolve::tsconfig,
-    EcmascriptInputTransformsVc,
+    EcmascriptInputTransformsVc, EcmascriptModuleAssetVc,
 };
 
 #[turbo_tasks::value]
@@ -303,22 +316,33 @@ pub(crate) async fn analyze_ecmascript_module(
                     origin,
                     RequestVc::parse(Value::new(src.to_string().into())),
                     Value::new(annotations.clone()),
+                    None,
                 );
                 import_references.push(r);
             }
-            for r in import_references.iter_mut() {
+            // Named imports of side effect free modules reference the modules
+            // defining the exports directly, so the imported module itself
+            // isn't needed.
+            let mut followed_imports = Vec::new();
+            for (i, r) in import_references.iter_mut().enumerate() {
                 // Resolving these references here avoids many resolve wrapper tasks when
                 // passing that to other turbo tasks functions later.
                 *r = r.resolve().await?;
-                analysis.add_reference(*r);
+                let followed = eval_context.imports.has_only_named_imports(i)
+                    && *r.is_side_effect_free().await?;
+                if !followed {
+                    analysis.add_reference(*r);
+                }
+                followed_imports.push(followed);
             }
+            let mut followed_references = IndexMap::new();
 
             let (
                 mut var_graph,
                 webpack_runtime,
                 webpack_entry,
                 webpack_chunks,
-                esm_exports,
+                mut esm_exports,
                 esm_star_exports,
             ) = HANDLER.set(&handler, || {
                 GLOBALS.set(globals, || {
@@ -408,6 +432,26 @@ pub(crate) async fn analyze_ecmascript_module(
                 }
             }
 
+            // Followed imports which are exported again (e.g. `import { a } from
+            // "./a"; export { a };`) need to be exported from the module
+            // defining them, as the imported module isn't referenced.
+            for export in esm_exports.values_mut() {
+                if let EsmExport::ImportedBinding(r, name) = export {
+                    let Some(index) = import_references.iter().position(|i| *i == *r) else {
+                        continue;
+                    };
+                    if followed_imports[index] {
+                        *r = *followed_references
+                            .entry((index, name.clone()))
+                            .or_insert_with(|| {
+                                let followed = r.followed(name.clone());
+                                analysis.add_reference(followed);
+                                followed
+                            });
+                    }
+                }
+            }
+
             let exports = if !esm_exports.is_empty() || !esm_star_exports.is_empty() {
                 let esm_exports: EsmExportsVc = EsmExports {
                     exports: esm_exports,
@@ -626,6 +670,60 @@ pub(crate) async fn analyze_ecmascript_module(
                             ),
                         )
                     }
+                    JsValue::WellKnownFunction(WellKnownFunctionKind::RequireContext) => {
+                        let args = linked_args(args).await?;
+                        let dir = args.first().and_then(|arg| arg.as_str());
+                        let include_subdirs = match args.get(1) {
+                            None => Some(true),
+                            Some(arg) => arg.is_truthy(),
+                        };
+                        let filter = match args.get(2) {
+                            None => Some(r"^\./.*$".to_string()),
+                            Some(JsValue::Constant(ConstantValue::Regex(exp, flags))) => {
+                                regex_from_js(exp, flags)
+                            }
+                            Some(_) => None,
+                        };
+                        if let (Some(dir), Some(include_subdirs), Some(filter), 1..=3) =
+                            (dir, include_subdirs, filter, args.len())
+                        {
+                            if dir.starts_with('.') {
+                                analysis.add_reference(RequireContextAssetReferenceVc::new(
+                                    origin,
+                                    dir.to_string(),
+                                    include_subdirs,
+                                    filter,
+                                    AstPathVc::cell(ast_path.to_vec()),
+                                ));
+                                return Ok(());
+                            }
+                            // Bare and absolute directories would need to be resolved first,
+                            // which isn't supported
+                            let (args, hints) = explain_args(&args);
+                            handler.span_warn_with_code(
+                                span,
+                                &format!(
+                                    "require.context({args}) only supports directories relative \
+                                     to the module (starting with `.`){hints}",
+                                ),
+                                DiagnosticId::Error(
+                                    errors::failed_to_analyse::ecmascript::REQUIRE_CONTEXT
+                                        .to_string(),
+                                ),
+                            );
+                            return Ok(());
+                        }
+                        let (args, hints) = explain_args(&args);
+                        handler.span_warn_with_code(
+                            span,
+                            &format!(
+                                "require.context({args}) is not statically analyse-able{hints}",
+                            ),
+                            DiagnosticId::Error(
+                                errors::failed_to_analyse::ecmascript::REQUIRE_CONTEXT.to_string(),
+                            ),
+                        )
+                    }
 
                     JsValue::WellKnownFunction(WellKnownFunctionKind::FsReadMethod(name)) => {
                         let args = linked_args(args).await?;
@@ -1113,6 +1211,8 @@ pub(crate) async fn analyze_ecmascript_module(
             // There can be many references to import.meta, but only the first should hoist
             // the object allocation.
             let mut first_import_meta = true;
+            // Eager globs import modules like import declarations
+            let mut eager_globs = Vec::new();
 
             while let Some(action) = queue_stack.get_mut().pop() {
                 match action {
@@ -1359,8 +1459,22 @@ pub(crate) async fn analyze_ecmascript_module(
                                             AstPathVc::cell(ast_path),
                                         ))
                                     } else {
+                                        let r = match &export {
+                                            Some(export)
+                                                if followed_imports[esm_reference_index] =>
+                                            {
+                                                *followed_references
+                                                    .entry((esm_reference_index, export.clone()))
+                                                    .or_insert_with(|| {
+                                                        let r = r.followed(export.clone());
+                                                        analysis.add_reference(r);
+                                                        r
+                                                    })
+                                            }
+                                            _ => *r,
+                                        };
                                         analysis.add_code_gen(EsmBindingVc::new(
-                                            *r,
+                                            r,
                                             export,
                                             AstPathVc::cell(ast_path),
                                         ));
@@ -1372,6 +1486,7 @@ pub(crate) async fn analyze_ecmascript_module(
                                     first_import_meta = false;
                                     analysis.add_code_gen(ImportMetaBindingVc::new(
                                         source.ident().path(),
+                                        eval_context.uses_import_meta_hot(),
                                     ));
                                 }
 
@@ -1401,10 +1516,173 @@ pub(crate) async fn analyze_ecmascript_module(
                                     AstPathVc::cell(ast_path),
                                 ));
                             }
+                            Effect::Worker {
+                                input,
+                                ast_path,
+                                span,
+                            } => {
+                                let pat = js_value_to_pattern(&input);
+                                if !pat.has_constant_parts() {
+                                    handler.span_warn_with_code(
+                                        span,
+                                        &format!(
+                                            "new Worker(new URL({input}, import.meta.url)) is \
+                                             very dynamic"
+                                        ),
+                                        DiagnosticId::Lint(
+                                            errors::failed_to_analyse::ecmascript::NEW_URL_IMPORT_META
+                                                .to_string(),
+                                        ),
+                                    )
+                                }
+                                let request = RequestVc::parse(Value::new(pat));
+                                // Only JavaScript modules can be bundled as worker entry,
+                                // other assets are referenced by their URL
+                                let is_module =
+                                    match *esm_resolve(origin, request).first_asset().await? {
+                                        Some(asset) => EcmascriptModuleAssetVc::resolve_from(asset)
+                                            .await?
+                                            .is_some(),
+                                        None => true,
+                                    };
+                                if is_module {
+                                    analysis.add_reference(WorkerAssetReferenceVc::new(
+                                        origin,
+                                        request,
+                                        AstPathVc::cell(ast_path),
+                                    ));
+                                } else {
+                                    handler.span_warn_with_code(
+                                        span,
+                                        &format!(
+                                            "new Worker(new URL({input}, import.meta.url)) \
+                                             doesn't reference a JavaScript module, so it \
+                                             can't be bundled as worker"
+                                        ),
+                                        DiagnosticId::Lint(
+                                            errors::failed_to_analyse::ecmascript::NEW_URL_IMPORT_META
+                                                .to_string(),
+                                        ),
+                                    );
+                                    // The path of the `new URL(...)` argument
+                                    let mut ast_path = ast_path;
+                                    ast_path.extend([
+                                        AstParentKind::NewExpr(NewExprField::Args(0)),
+                                        AstParentKind::ExprOrSpread(ExprOrSpreadField::Expr),
+                                        AstParentKind::Expr(ExprField::New),
+                                    ]);
+                                    analysis.add_reference(UrlAssetReferenceVc::new(
+                                        origin,
+                                        request,
+                                        compile_time_info.environment().rendering(),
+                                        AstPathVc::cell(ast_path),
+                                    ));
+                                }
+                            }
+                            Effect::ImportMetaGlob {
+                                args,
+                                eager,
+                                ast_path,
+                                span,
+                            } => {
+                                let mut linked_args = Vec::with_capacity(args.len());
+                                for arg in args {
+                                    linked_args.push(link_value(arg).await?);
+                                }
+                                if let Some((patterns, eager, import)) =
+                                    import_meta_glob_options(&linked_args, eager)
+                                {
+                                    let reference = ImportMetaGlobAssetReferenceVc::new(
+                                        origin,
+                                        patterns,
+                                        eager,
+                                        import,
+                                        AstPathVc::cell(ast_path),
+                                    );
+                                    if eager {
+                                        eager_globs.push(reference);
+                                    }
+                                    analysis.add_reference(reference);
+                                } else {
+                                    let (args, hints) = JsValue::explain_args(&linked_args, 10, 2);
+                                    handler.span_warn_with_code(
+                                        span,
+                                        &format!(
+                                            "import.meta.glob({args}) is not statically \
+                                             analyse-able{hints}",
+                                        ),
+                                        DiagnosticId::Error(
+                                            errors::failed_to_analyse::ecmascript::IMPORT_META_GLOB
+                                                .to_string(),
+                                        ),
+                                    )
+                                }
+                            }
+                            Effect::HotDependencies {
+                                deps,
+                                esm,
+                                ast_path,
+                                span,
+                            } => {
+                                let deps = link_value(deps).await?;
+                                let requests = match &deps {
+                                    JsValue::Array { items, .. } => items
+                                        .iter()
+                                        .map(|item| item.as_str().map(ToString::to_string))
+                                        .collect::<Option<Vec<_>>>(),
+                                    value => value.as_str().map(|s| vec![s.to_string()]),
+                                };
+                                if let Some(requests) = requests {
+                                    // Imports of accepted dependencies are updated before the
+                                    // accept callback is called
+                                    let imports = eval_context
+                                        .imports
+                                        .references()
+                                        .zip(import_references.iter().zip(followed_imports.iter()))
+                                        .filter(|((src, _), (_, followed))| {
+                                            !**followed
+                                                && requests.iter().any(|request| request == &***src)
+                                        })
+                                        .map(|(_, (r, _))| *r)
+                                        .collect();
+                                    analysis.add_reference(HotDependenciesAssetReferenceVc::new(
+                                        origin,
+                                        requests,
+                                        esm,
+                                        imports,
+                                        AstPathVc::cell(ast_path),
+                                    ));
+                                } else {
+                                    handler.span_warn_with_code(
+                                        span,
+                                        &format!(
+                                            "module.hot dependencies {deps} are not statically \
+                                             analyse-able",
+                                        ),
+                                        DiagnosticId::Error(
+                                            errors::failed_to_analyse::ecmascript::HOT_DEPENDENCIES
+                                                .to_string(),
+                                        ),
+                                    )
+                                }
+                            }
                         }
                     }
                 }
             }
+
+            if eval_context.is_esm() {
+                // All references which are imported by the module, including
+                // the references to the modules defining followed exports
+                let esm_references = import_references
+                    .iter()
+                    .zip(followed_imports.iter())
+                    .filter(|(_, followed)| !**followed)
+                    .map(|(r, _)| *r)
+                    .chain(followed_references.values().copied())
+                    .collect();
+                analysis.add_code_gen(AsyncModuleVc::new(esm_references, eager_globs));
+            }
         }
         ParseResult::Unparseable | ParseResult::NotFound => {}
     };
@@ -1725,6 +2003,43 @@ async fn value_visitor_inner(
     Ok((value, true))
 }
 
+/// Extracts the patterns, the `eager` and the `import` option from the
+/// arguments of an `import.meta.glob` call. Only constant, relative patterns
+/// are supported.
+fn import_meta_glob_options(
+    args: &[JsValue],
+    mut eager: bool,
+) -> Option<(Vec<String>, bool, Option<String>)> {
+    let patterns = match args.first()? {
+        JsValue::Array { items, .. } => items
+            .iter()
+            .map(|item| item.as_str().map(ToString::to_string))
+            .collect::<Option<Vec<_>>>()?,
+        value => vec![value.as_str()?.to_string()],
+    };
+    if patterns.iter().any(|pattern| split_glob(pattern).is_none()) {
+        return None;
+    }
+    let mut import = None;
+    match args {
+        [_] => {}
+        [_, JsValue::Object { parts, .. }] => {
+            for part in parts {
+                let ObjectPart::KeyValue(key, value) = part else {
+                    return None;
+                };
+                match key.as_str()? {
+                    "eager" => eager = value.is_truthy()?,
+                    "import" => import = Some(value.as_str()?.to_string()),
+                    _ => return None,
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some((patterns, eager, import))
+}
+
 #[derive(Debug)]
 enum StaticExpr {
     String(String),
@@ -0,0 +1,470 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use regex::Regex;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{Callee, Expr, ExprOrSpread, Ident},
+};
+use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbo_tasks_fs::{DirectoryContent, DirectoryEntry, FileSystemPathVc};
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    chunk::{
+        ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetReference,
+        ChunkableAssetReferenceVc, ChunkableAssetVc, ChunkingContextVc, ModuleId,
+    },
+    ident::AssetIdentVc,
+    reference::{AssetReference, AssetReferenceVc, AssetReferencesVc},
+    resolve::{
+        origin::{ResolveOrigin, ResolveOriginVc},
+        parse::RequestVc,
+        pattern::Pattern,
+        ResolveResult, ResolveResultVc,
+    },
+};
+
+use super::{
+    cjs::CjsAssetReferenceVc,
+    pattern_mapping::{PatternMapping, PatternMappingVc, ResolveType::Cjs},
+};
+use crate::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc,
+        EcmascriptChunkVc, EcmascriptExports, EcmascriptExportsVc,
+    },
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::AstPathVc,
+    resolve::cjs_resolve,
+};
+
+/// Converts a JavaScript regular expression literal into an equivalent
+/// pattern for the [regex] crate. Returns [None] when the flags or the
+/// expression aren't supported.
+pub(crate) fn regex_from_js(exp: &str, flags: &str) -> Option<String> {
+    let mut inline_flags = String::new();
+    for flag in flags.chars() {
+        match flag {
+            'i' | 'm' | 's' => inline_flags.push(flag),
+            // Global, sticky and unicode matching don't affect a single `test` call
+            'g' | 'y' | 'u' => {}
+            _ => return None,
+        }
+    }
+    // Slashes are escaped in regex literals, but the regex crate rejects
+    // escapes that aren't needed.
+    let mut exp_chars = exp.chars();
+    let mut pattern = if inline_flags.is_empty() {
+        String::new()
+    } else {
+        format!("(?{inline_flags})")
+    };
+    while let Some(c) = exp_chars.next() {
+        match c {
+            '\\' => match exp_chars.next() {
+                Some('/') => pattern.push('/'),
+                Some(escaped) => {
+                    pattern.push(c);
+                    pattern.push(escaped);
+                }
+                None => pattern.push(c),
+            },
+            _ => pattern.push(c),
+        }
+    }
+    Regex::new(&pattern).ok()?;
+    Some(pattern)
+}
+
+/// The requests of a `require.context` call, keyed by the context key (e.g.
+/// `./components/button.js`).
+#[turbo_tasks::value(transparent)]
+pub struct RequireContextMap(IndexMap<String, RequestVc>);
+
+#[turbo_tasks::value_impl]
+impl RequireContextMapVc {
+    /// Lists the files in `dir` whose context key matches `filter`. `request`
+    /// is the directory request as written in the source, the requests of the
+    /// files are relative to it.
+    #[turbo_tasks::function]
+    pub async fn generate(
+        dir: FileSystemPathVc,
+        request: String,
+        include_subdirs: bool,
+        filter: String,
+    ) -> Result<Self> {
+        let filter = Regex::new(&filter)?;
+        let request = request.trim_end_matches('/');
+
+        let mut map = IndexMap::new();
+        let mut queue = VecDeque::from([(dir, ".".to_string())]);
+        while let Some((dir, prefix)) = queue.pop_front() {
+            let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
+                continue;
+            };
+            // The directory listing is unordered, sort it to keep module ids stable
+            let mut entries = entries.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, entry) in entries {
+                let key = format!("{prefix}/{name}");
+                match entry {
+                    DirectoryEntry::File(_) => {
+                        if filter.is_match(&key) {
+                            let request = format!("{request}{}", &key[1..]);
+                            map.insert(
+                                key,
+                                RequestVc::parse(Value::new(Pattern::Constant(request))),
+                            );
+                        }
+                    }
+                    DirectoryEntry::Directory(path) if include_subdirs => {
+                        queue.push_back((*path, key));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self::cell(map))
+    }
+}
+
+/// A reference to the context module created by a `require.context(dir,
+/// includeSubdirs, filter)` call.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct RequireContextAssetReference {
+    pub origin: ResolveOriginVc,
+    pub dir: String,
+    pub include_subdirs: bool,
+    pub filter: String,
+    pub path: AstPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl RequireContextAssetReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolveOriginVc,
+        dir: String,
+        include_subdirs: bool,
+        filter: String,
+        path: AstPathVc,
+    ) -> Self {
+        Self::cell(RequireContextAssetReference {
+            origin,
+            dir,
+            include_subdirs,
+            filter,
+            path,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn context_asset(self) -> Result<RequireContextAssetVc> {
+        let this = self.await?;
+        Ok(RequireContextAssetVc::new(
+            this.origin,
+            this.dir.clone(),
+            this.include_subdirs,
+            this.filter.clone(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for RequireContextAssetReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(self_vc: RequireContextAssetReferenceVc) -> ResolveResultVc {
+        ResolveResult::asset(self_vc.context_asset().into()).cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for RequireContextAssetReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(format!(
+            "require.context {} {} {}",
+            self.dir, self.include_subdirs, self.filter
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAssetReference for RequireContextAssetReference {}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for RequireContextAssetReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        self_vc: RequireContextAssetReferenceVc,
+        context: ChunkingContextVc,
+    ) -> Result<CodeGenerationVc> {
+        let this = self_vc.await?;
+        let id = self_vc
+            .context_asset()
+            .as_chunk_item(context)
+            .id()
+            .await?
+            .clone_value();
+
+        let mut visitors = Vec::new();
+
+        let path = &this.path.await?;
+        visitors.push(
+            create_visitor!(exact path, visit_mut_call_expr(call_expr: &mut CallExpr) {
+                call_expr.callee = Callee::Expr(
+                    box Expr::Ident(Ident::new("__turbopack_require__".into(), DUMMY_SP))
+                );
+                call_expr.args = vec![ExprOrSpread {
+                    spread: None,
+                    expr: box Expr::Lit(match &id {
+                        ModuleId::String(s) => s.clone().into(),
+                        ModuleId::Number(n) => (*n as f64).into(),
+                    }),
+                }];
+            }),
+        );
+
+        Ok(CodeGeneration { visitors }.into())
+    }
+}
+
+#[turbo_tasks::function]
+fn modifier(include_subdirs: bool, filter: String) -> StringVc {
+    StringVc::cell(format!(
+        "require.context {} {}",
+        if include_subdirs {
+            "recursive"
+        } else {
+            "non-recursive"
+        },
+        filter
+    ))
+}
+
+/// A virtual module which maps the context keys of a `require.context` call
+/// to the matching modules and exports the `context` function.
+#[turbo_tasks::value]
+pub struct RequireContextAsset {
+    origin: ResolveOriginVc,
+    dir: String,
+    include_subdirs: bool,
+    filter: String,
+}
+
+#[turbo_tasks::value_impl]
+impl RequireContextAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolveOriginVc,
+        dir: String,
+        include_subdirs: bool,
+        filter: String,
+    ) -> Self {
+        Self::cell(RequireContextAsset {
+            origin,
+            dir,
+            include_subdirs,
+            filter,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn dir_path(self) -> Result<FileSystemPathVc> {
+        let this = self.await?;
+        Ok(this.origin.origin_path().parent().join(&this.dir))
+    }
+
+    #[turbo_tasks::function]
+    async fn map(self) -> Result<RequireContextMapVc> {
+        let this = self.await?;
+        Ok(RequireContextMapVc::generate(
+            self.dir_path(),
+            this.dir.clone(),
+            this.include_subdirs,
+            this.filter.clone(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for RequireContextAsset {
+    #[turbo_tasks::function]
+    async fn ident(self_vc: RequireContextAssetVc) -> Result<AssetIdentVc> {
+        let this = self_vc.await?;
+        Ok(AssetIdentVc::from_path(self_vc.dir_path())
+            .with_modifier(modifier(this.include_subdirs, this.filter.clone())))
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        unimplemented!()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self_vc: RequireContextAssetVc) -> Result<AssetReferencesVc> {
+        let origin = self_vc.await?.origin;
+        Ok(AssetReferencesVc::cell(
+            self_vc
+                .map()
+                .await?
+                .values()
+                .map(|request| CjsAssetReferenceVc::new(origin, *request).into())
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAsset for RequireContextAsset {
+    #[turbo_tasks::function]
+    fn as_chunk(self_vc: RequireContextAssetVc, context: ChunkingContextVc) -> ChunkVc {
+        EcmascriptChunkVc::new(context, self_vc.as_ecmascript_chunk_placeable()).into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for RequireContextAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: RequireContextAssetVc,
+        context: ChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        RequireContextChunkItem {
+            context,
+            inner: self_vc,
+        }
+        .cell()
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> EcmascriptExportsVc {
+        EcmascriptExports::Value.cell()
+    }
+}
+
+#[turbo_tasks::value]
+struct RequireContextChunkItem {
+    context: ChunkingContextVc,
+    inner: RequireContextAssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for RequireContextChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> ChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
+        let origin = self.inner.await?.origin;
+        let mut map = serde_json::Map::new();
+        for (key, request) in self.inner.map().await?.iter() {
+            let pm = PatternMappingVc::resolve_request(
+                *request,
+                origin,
+                self.context,
+                cjs_resolve(origin, *request),
+                Value::new(Cjs),
+            )
+            .await?;
+            // Unresolvable files are left out, requiring them throws like for an
+            // unknown key
+            if let PatternMapping::Single(id) = &*pm {
+                map.insert(key.clone(), serde_json::to_value(id)?);
+            }
+        }
+
+        let code = format!(
+            r#"const map = {map:#};
+
+function resolve(key) {{
+    if (!Object.prototype.hasOwnProperty.call(map, key)) {{
+        const e = new Error(`Cannot find module '${{key}}'`);
+        e.code = "MODULE_NOT_FOUND";
+        throw e;
+    }}
+    return map[key];
+}}
+
+function context(key) {{
+    return __turbopack_require__(resolve(key));
+}}
+context.keys = () => Object.keys(map);
+context.resolve = resolve;
+
+__turbopack_export_value__(context);
+"#,
+            map = serde_json::Value::Object(map)
+        );
+
+        Ok(EcmascriptChunkItemContent {
+            inner_code: code.into(),
+            ..Default::default()
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for RequireContextChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.inner.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        self.inner.references()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::regex_from_js;
+
+    #[test]
+    fn converts_js_regexes() {
+        assert_eq!(regex_from_js(r"\.js$", "").as_deref(), Some(r"\.js$"));
+        assert_eq!(
+            regex_from_js(r"^\./button", "i").as_deref(),
+            Some(r"(?i)^\./button")
+        );
+        assert_eq!(
+            regex_from_js(r"^\./a", "gimsuy").as_deref(),
+            Some(r"(?ims)^\./a")
+        );
+        assert_eq!(
+            regex_from_js(r"^\.\/en\\\/", "").as_deref(),
+            Some(r"^\./en\\/")
+        );
+    }
+
+    #[test]
+    fn converted_regexes_match_context_keys() {
+        let pattern = regex_from_js(r"\.JSX?$", "i").unwrap();
+        let regex = Regex::new(&pattern).unwrap();
+        assert!(regex.is_match("./components/button.jsx"));
+        assert!(regex.is_match("./index.js"));
+        assert!(!regex.is_match("./style.css"));
+    }
+
+    #[test]
+    fn rejects_unsupported_regexes() {
+        // Unknown flags
+        assert_eq!(regex_from_js(r"\.js$", "x"), None);
+        // Lookarounds aren't supported by the regex crate
+        assert_eq!(regex_from_js(r"^(?!\./test).*\.js$", ""), None);
+        // Invalid expressions
+        assert_eq!(regex_from_js(r"(\.js", ""), None);
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use swc_core::{
+    ecma::ast::{Expr, ExprOrSpread, NewExpr},
+    quote,
+};
+use turbo_tasks::{primitives::StringVc, ValueToString, ValueToStringVc};
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContent, AssetContentVc, AssetVc},
+    chunk::{
+        Chunk, ChunkItem, ChunkItemVc, ChunkReferenceVc, ChunkVc, ChunkableAsset,
+        ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkableAssetVc, ChunkingContext,
+        ChunkingContextVc,
+    },
+    ident::AssetIdentVc,
+    reference::{AssetReference, AssetReferenceVc, AssetReferencesVc},
+    resolve::{origin::ResolveOriginVc, parse::RequestVc, ResolveResult, ResolveResultVc},
+};
+
+use crate::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc,
+        EcmascriptChunkVc, EcmascriptExports, EcmascriptExportsVc,
+    },
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::AstPathVc,
+    resolve::esm_resolve,
+    utils::module_id_to_lit,
+    EcmascriptModuleAssetVc,
+};
+
+/// A reference to the entry module of a web worker, which is found in a
+/// (statically analyzable) `new Worker(new URL("path", import.meta.url))`.
+///
+/// The worker module is bundled into a separate chunk group, and the URL
+/// argument is rewritten to the URL of its entry chunk.
+#[turbo_tasks::value]
+pub struct WorkerAssetReference {
+    origin: ResolveOriginVc,
+    request: RequestVc,
+    ast_path: AstPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerAssetReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(origin: ResolveOriginVc, request: RequestVc, ast_path: AstPathVc) -> Self {
+        WorkerAssetReference {
+            origin,
+            request,
+            ast_path,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(&self) -> Result<ResolveResultVc> {
+        let result = esm_resolve(self.origin, self.request);
+        let Some(asset) = *result.first_asset().await? else {
+            return Ok(result);
+        };
+        let Some(module) = EcmascriptModuleAssetVc::resolve_from(asset).await? else {
+            return Ok(result);
+        };
+        Ok(ResolveResult::asset(WorkerLoaderAssetVc::new(module).into()).cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "new Worker({})",
+            self.request.to_string().await?,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAssetReference for WorkerAssetReference {}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        self_vc: WorkerAssetReferenceVc,
+        context: ChunkingContextVc,
+    ) -> Result<CodeGenerationVc> {
+        let this = self_vc.await?;
+        let mut visitors = vec![];
+
+        let asset = *self_vc.resolve_reference().first_asset().await?;
+        let loader = match asset {
+            Some(asset) => WorkerLoaderAssetVc::resolve_from(asset).await?,
+            None => None,
+        };
+        if let Some(loader) = loader {
+            // We rewrite the URL argument to be a require() of the loader chunk item,
+            // which exports the URL of the worker's entry chunk.
+            let id = loader.as_chunk_item(context).id().await?;
+            let ast_path = this.ast_path.await?;
+            visitors.push(
+                create_visitor!(ast_path, visit_mut_expr(new_expr: &mut Expr) {
+                    if let Expr::New(NewExpr { args: Some(args), .. }) = new_expr {
+                        if let Some(ExprOrSpread { box expr, spread: None }) = args.get_mut(0) {
+                            *expr = quote!(
+                                "__turbopack_require__($id)" as Expr,
+                                id: Expr = module_id_to_lit(&id),
+                            );
+                        }
+                    }
+                }),
+            );
+        }
+
+        Ok(CodeGeneration { visitors }.into())
+    }
+}
+
+#[turbo_tasks::function]
+fn modifier() -> StringVc {
+    StringVc::cell("worker loader".to_string())
+}
+
+/// An asset that exports the URL of the entry chunk of a web worker. The
+/// worker is placed into a separate chunk group, which is created with a
+/// chunking context for the web worker environment.
+#[turbo_tasks::value]
+pub struct WorkerLoaderAsset {
+    module: EcmascriptModuleAssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerLoaderAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(module: EcmascriptModuleAssetVc) -> Self {
+        Self::cell(WorkerLoaderAsset { module })
+    }
+
+    #[turbo_tasks::function]
+    async fn entry_chunk(self, context: ChunkingContextVc) -> Result<ChunkVc> {
+        let worker_context = context.with_environment(context.environment().web_worker());
+        Ok(self.await?.module.as_evaluated_chunk(worker_context, None))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WorkerLoaderAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.module.ident().with_modifier(modifier())
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        // The loader has no source of its own. Its code depends on the chunking
+        // context and is generated by the chunk item instead.
+        AssetContent::File(FileContent::NotFound.cell()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        AssetReferencesVc::empty()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAsset for WorkerLoaderAsset {
+    #[turbo_tasks::function]
+    fn as_chunk(self_vc: WorkerLoaderAssetVc, context: ChunkingContextVc) -> ChunkVc {
+        EcmascriptChunkVc::new(context, self_vc.as_ecmascript_chunk_placeable()).into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for WorkerLoaderAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: WorkerLoaderAssetVc,
+        context: ChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        WorkerLoaderChunkItem {
+            context,
+            inner: self_vc,
+        }
+        .cell()
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> EcmascriptExportsVc {
+        EcmascriptExports::Value.cell()
+    }
+}
+
+#[turbo_tasks::value]
+struct WorkerLoaderChunkItem {
+    context: ChunkingContextVc,
+    inner: WorkerLoaderAssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for WorkerLoaderChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> ChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
+        let chunk_path = self.inner.entry_chunk(self.context).path();
+        let url = self.context.asset_url(chunk_path).await?;
+        Ok(EcmascriptChunkItemContent {
+            inner_code: format!(
+                "__turbopack_export_value__({});\n",
+                JsonValue::String(url.clone_value())
+            )
+            .into(),
+            ..Default::default()
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for WorkerLoaderChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.inner.ident()
+    }
+
+    /// The entry chunk is the script the worker is started with, so it's
+    /// referenced directly instead of the optimized chunks of its chunk group,
+    /// which can have different paths. It references the chunks it depends on
+    /// itself.
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        AssetReferencesVc::cell(vec![ChunkReferenceVc::new(
+            self.inner.entry_chunk(self.context),
+        )
+        .into()])
+    }
+}
@@ -0,0 +1,727 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    mem::take,
+};
+
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use swc_core::{
+    common::{DUMMY_SP, GLOBALS},
+    ecma::{
+        ast::{
+            AssignPat, AssignPatProp, BindingIdent, Callee, Decl, Expr, Id, Ident, KeyValuePatProp,
+            KeyValueProp, MemberProp, Module, ModuleItem, ObjectPatProp, Pat, Program, Prop,
+            PropName, Stmt, SuperProp, VarDeclarator,
+        },
+        atoms::JsWord,
+        transforms::base::fixer::fixer,
+        utils::find_pat_ids,
+        visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+};
+use turbo_tasks::ValueToString;
+use turbo_tasks_fs::rope::Rope;
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ChunkingContext, ChunkingContextVc, ModuleConcatenationVc},
+    code_builder::CodeBuilder,
+    reference::AssetReference,
+    resolve::{PrimaryResolveResult, ResolveResultVc},
+};
+
+use crate::{
+    chunk::{
+        EcmascriptChunkItemContent, EcmascriptChunkItemContentVc, EcmascriptChunkPlaceable,
+        EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc, EcmascriptExports,
+    },
+    chunk_item_options, emit_program, generate_program,
+    parse::ParseResult,
+    references::esm::{
+        base::ReferencedAsset,
+        export::{expand_star_exports, EsmExport},
+        EsmAssetReferenceVc, EsmBindingVc,
+    },
+    EcmascriptModuleAssetVc,
+};
+
+/// Computes which modules of the module graph reachable from `entries` are
+/// concatenated into the modules importing them.
+///
+/// An ESM module is concatenated when it's only imported by named imports
+/// from modules which are concatenated into the same module. All other
+/// modules, e.g. CommonJS modules or modules which are imported dynamically
+/// or as namespace, keep their own module factory.
+#[turbo_tasks::function]
+pub async fn compute_module_concatenation(
+    entries: EcmascriptChunkPlaceablesVc,
+) -> Result<ModuleConcatenationVc> {
+    let entries = entries.await?;
+
+    // The importers of every module, and whether the module could be
+    // concatenated into the importer
+    let mut importers: IndexMap<_, Vec<(EcmascriptChunkPlaceableVc, bool)>> = IndexMap::new();
+    let mut queue = VecDeque::with_capacity(32);
+    for entry in entries.iter() {
+        importers.insert(*entry, Vec::new());
+        queue.push_back(*entry);
+    }
+    while let Some(module) = queue.pop_front() {
+        for (imported, concatenable) in module_imports(module).await? {
+            if !importers.contains_key(&imported) {
+                queue.push_back(imported);
+            }
+            importers
+                .entry(imported)
+                .or_default()
+                .push((module, concatenable));
+        }
+    }
+
+    let mut roots = IndexMap::new();
+    let mut pending = Vec::new();
+    for (module, module_importers) in importers.iter() {
+        if !entries.contains(module)
+            && module_importers
+                .iter()
+                .all(|(_, concatenable)| *concatenable)
+            && is_concatenable(*module).await?
+        {
+            pending.push(*module);
+        } else {
+            roots.insert(*module, *module);
+        }
+    }
+
+    // A module is concatenated into the root of its importers once all of them
+    // are assigned to the same root.
+    while !pending.is_empty() {
+        let mut still_pending = Vec::new();
+        for module in pending.iter() {
+            let module_roots = importers[module]
+                .iter()
+                .map(|(importer, _)| roots.get(importer).copied())
+                .collect::<Option<IndexSet<_>>>();
+            match module_roots {
+                None => still_pending.push(*module),
+                Some(module_roots) if module_roots.len() == 1 => {
+                    roots.insert(*module, module_roots[0]);
+                }
+                Some(_) => {
+                    roots.insert(*module, *module);
+                }
+            }
+        }
+        if still_pending.len() == pending.len() {
+            // Modules which import each other can't be concatenated into each
+            // other, so one of them needs to keep its own module factory.
+            let module = still_pending.remove(0);
+            roots.insert(module, module);
+        }
+        pending = still_pending;
+    }
+
+    let mut concatenation = IndexMap::new();
+    for (module, root) in roots {
+        if module != root {
+            concatenation.insert(
+                module.ident().to_string().await?.clone_value(),
+                root.ident().to_string().await?.clone_value(),
+            );
+        }
+    }
+    Ok(ModuleConcatenationVc::cell(concatenation))
+}
+
+/// Returns the modules imported by `module`, and whether they could be
+/// concatenated into `module`.
+async fn module_imports(
+    module: EcmascriptChunkPlaceableVc,
+) -> Result<Vec<(EcmascriptChunkPlaceableVc, bool)>> {
+    let mut imports = Vec::new();
+
+    let Some(ecmascript_module) = EcmascriptModuleAssetVc::resolve_from(module).await? else {
+        // We don't know how other modules access their references
+        for reference in module.references().await?.iter() {
+            for placeable in referenced_placeables(reference.resolve_reference()).await? {
+                imports.push((placeable, false));
+            }
+        }
+        return Ok(imports);
+    };
+    let analysis = ecmascript_module.analyze().await?;
+
+    // Imports which are used as namespace object need a real module
+    let mut namespace_references = HashSet::new();
+    for code_gen in analysis.code_generation.await?.iter() {
+        if let Some(binding) = EsmBindingVc::resolve_from(code_gen).await? {
+            let binding = binding.await?;
+            let export = binding
+                .reference
+                .get_referenced_export(binding.export.clone())
+                .await?;
+            if export.is_none() {
+                namespace_references.insert(binding.reference.resolve().await?);
+            }
+        }
+    }
+    if let EcmascriptExports::EsmExports(exports) = &*analysis.exports.await? {
+        for export in exports.await?.exports.values() {
+            if let EsmExport::ImportedNamespace(reference) = export {
+                namespace_references.insert(reference.resolve().await?);
+            }
+        }
+    }
+
+    for reference in analysis.references.await?.iter() {
+        let concatenable = match EsmAssetReferenceVc::resolve_from(reference).await? {
+            Some(reference) => {
+                reference.await?.annotations.is_empty()
+                    && !namespace_references.contains(&reference)
+            }
+            None => false,
+        };
+        for placeable in referenced_placeables(reference.resolve_reference()).await? {
+            imports.push((placeable, concatenable));
+        }
+    }
+
+    Ok(imports)
+}
+
+async fn is_concatenable(module: EcmascriptChunkPlaceableVc) -> Result<bool> {
+    let Some(ecmascript_module) = EcmascriptModuleAssetVc::resolve_from(module).await? else {
+        return Ok(false);
+    };
+    // Async modules are awaited by their importers
+    if *ecmascript_module.is_async_module().await? {
+        return Ok(false);
+    }
+    let ParseResult::Ok { eval_context, .. } = &*ecmascript_module.parse().await? else {
+        return Ok(false);
+    };
+    // Modules using `import.meta.hot` need their own module object
+    if eval_context.uses_import_meta_hot() {
+        return Ok(false);
+    }
+    match &*module.get_exports().await? {
+        EcmascriptExports::EsmExports(exports) => {
+            // Exports of CommonJS modules are re-exported at runtime
+            for reference in exports.await?.star_exports.iter() {
+                if let ReferencedAsset::Some(asset) = &*reference.get_referenced_asset().await? {
+                    if expand_star_exports(*asset).await?.has_cjs_exports {
+                        return Ok(false);
+                    }
+                }
+            }
+            Ok(true)
+        }
+        // ESM modules without exports, which are only imported for their side
+        // effects. Their imports can be side effect imports, too, which
+        // `is_esm` doesn't consider.
+        EcmascriptExports::None => Ok(eval_context.imports.references().next().is_some()),
+        _ => Ok(false),
+    }
+}
+
+async fn referenced_placeables(result: ResolveResultVc) -> Result<Vec<EcmascriptChunkPlaceableVc>> {
+    let mut placeables = Vec::new();
+    for result in result.await?.primary.iter() {
+        if let PrimaryResolveResult::Asset(asset) = result {
+            if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? {
+                placeables.push(placeable);
+            }
+        }
+    }
+    Ok(placeables)
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct ConcatenatedModules(Vec<EcmascriptModuleAssetVc>);
+
+/// Returns the modules which are concatenated into `root`, ordered so that
+/// every module comes after the modules it imports.
+#[turbo_tasks::function]
+pub async fn concatenated_modules(
+    root: EcmascriptModuleAssetVc,
+    context: ChunkingContextVc,
+) -> Result<ConcatenatedModulesVc> {
+    let modules = evaluation_order(root, context)
+        .await?
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Body(module) if module != root => Some(module),
+            _ => None,
+        })
+        .collect();
+    Ok(ConcatenatedModulesVc::cell(modules))
+}
+
+/// A part of the code of a module which is concatenated with other modules.
+enum Segment {
+    /// The hoisted import of a module which isn't concatenated, identified by
+    /// the variable it's assigned to.
+    Import(EcmascriptModuleAssetVc, JsWord),
+    /// The remaining code of a module.
+    Body(EcmascriptModuleAssetVc),
+}
+
+/// Returns the segments of `root` and the modules concatenated into it in
+/// the order they are evaluated. Like with real ESM imports, the imports of
+/// a module are evaluated in order before the module itself.
+async fn evaluation_order(
+    root: EcmascriptModuleAssetVc,
+    context: ChunkingContextVc,
+) -> Result<Vec<Segment>> {
+    let root_ident = root.ident().to_string().await?;
+    let mut segments = Vec::new();
+    let mut visited = HashSet::new();
+    visited.insert(root);
+    let mut stack = vec![(
+        root,
+        inner_imports(root, &root_ident, context).await?.into_iter(),
+    )];
+    while let Some((module, imports)) = stack.last_mut() {
+        let module = *module;
+        match imports.next() {
+            Some(InnerImport::Concatenated(import)) => {
+                if visited.insert(import) {
+                    let imports = inner_imports(import, &root_ident, context).await?;
+                    stack.push((import, imports.into_iter()));
+                }
+            }
+            Some(InnerImport::Hoisted(ident)) => segments.push(Segment::Import(module, ident)),
+            None => {
+                stack.pop();
+                segments.push(Segment::Body(module));
+            }
+        }
+    }
+    Ok(segments)
+}
+
+enum InnerImport {
+    /// A module which is concatenated into the same module.
+    Concatenated(EcmascriptModuleAssetVc),
+    /// A module which keeps its own module factory and is imported by a
+    /// hoisted statement assigning it to a variable with this name.
+    Hoisted(JsWord),
+}
+
+/// Returns the ESM imports of `module` in order, distinguishing the modules
+/// which are concatenated into the module with the ident `root_ident`.
+async fn inner_imports(
+    module: EcmascriptModuleAssetVc,
+    root_ident: &str,
+    context: ChunkingContextVc,
+) -> Result<Vec<InnerImport>> {
+    let mut imports = Vec::new();
+    for reference in module.analyze().await?.references.await?.iter() {
+        let Some(reference) = EsmAssetReferenceVc::resolve_from(reference).await? else {
+            continue;
+        };
+        if *reference.is_unused(context).await? {
+            continue;
+        }
+        let referenced_asset = reference.get_referenced_asset().await?;
+        if let ReferencedAsset::Some(asset) = &*referenced_asset {
+            if let Some(imported) = EcmascriptModuleAssetVc::resolve_from(asset).await? {
+                let concatenation_root = context.concatenation_root(imported.ident()).await?;
+                if concatenation_root.as_deref() == Some(root_ident) {
+                    imports.push(InnerImport::Concatenated(imported));
+                    continue;
+                }
+            }
+        }
+        if let Some(ident) = referenced_asset.get_ident().await? {
+            imports.push(InnerImport::Hoisted(ident.as_str().into()));
+        }
+    }
+    Ok(imports)
+}
+
+/// Generates the code of `root` together with the code of the modules which
+/// are concatenated into it, in a single scope. Top level bindings which
+/// conflict with identifiers of other modules are renamed. The exports of the
+/// concatenated modules are defined on local namespace objects, which are
+/// accessed in place of the imported modules.
+#[turbo_tasks::function]
+pub async fn concatenated_module_content(
+    root: EcmascriptModuleAssetVc,
+    context: ChunkingContextVc,
+    inner_modules: ConcatenatedModulesVc,
+) -> Result<EcmascriptChunkItemContentVc> {
+    let mut modules = Vec::new();
+    // Namespace objects are shared by all modules and must not be renamed
+    let mut namespaces = HashSet::new();
+    for module in inner_modules.await?.iter() {
+        let placeable = module.as_ecmascript_chunk_placeable();
+        let namespace = ReferencedAsset::get_ident_from_placeable(&placeable).await?;
+        namespaces.insert(JsWord::from(namespace.as_str()));
+        modules.push((*module, Some(namespace)));
+    }
+    modules.push((root, None));
+
+    let mut programs = Vec::new();
+    for (module, namespace) in modules.iter() {
+        let parsed = module.parse().await?;
+        let program = generate_program(*module, context, &parsed, namespace.clone()).await?;
+        programs.push((parsed, program));
+    }
+
+    let symbols = programs
+        .iter()
+        .map(|(_, program)| {
+            let mut collector = SymbolCollector::default();
+            program.visit_with(&mut collector);
+            collector.symbols
+        })
+        .collect::<Vec<_>>();
+    let mut used_symbols = symbols.iter().flatten().cloned().collect::<HashSet<_>>();
+
+    let segments = evaluation_order(root, context).await?;
+    let mut hoisted_idents: HashMap<_, HashSet<JsWord>> = HashMap::new();
+    for segment in segments.iter() {
+        if let Segment::Import(module, ident) = segment {
+            hoisted_idents
+                .entry(*module)
+                .or_default()
+                .insert(ident.clone());
+        }
+    }
+
+    let mut parts = HashMap::new();
+    for (i, (parsed, program)) in programs.into_iter().enumerate() {
+        let mut renames = HashMap::new();
+        for id in top_level_ids(&program) {
+            if namespaces.contains(&id.0) {
+                continue;
+            }
+            let conflicts = symbols
+                .iter()
+                .enumerate()
+                .any(|(j, symbols)| j != i && symbols.contains(&id.0));
+            if conflicts && !renames.contains_key(&id) {
+                let name = (1..)
+                    .map(|n| JsWord::from(format!("{}_{}", id.0, n)))
+                    .find(|name| !used_symbols.contains(name))
+                    .unwrap();
+                used_symbols.insert(name.clone());
+                renames.insert(id, name);
+            }
+        }
+        let module = modules[i].0;
+        let (hoisted_imports, body) = split_hoisted_imports(
+            program,
+            hoisted_idents.get(&module).unwrap_or(&HashSet::new()),
+        );
+        parts.insert(module, (parsed, renames, hoisted_imports, body));
+    }
+
+    let mut code = CodeBuilder::default();
+    for segment in segments {
+        let (module, import) = match segment {
+            Segment::Import(module, ident) => (module, Some(ident)),
+            Segment::Body(module) => (module, None),
+        };
+        let Some((parsed, renames, hoisted_imports, body)) = parts.get_mut(&module) else {
+            continue;
+        };
+        let items = match import {
+            Some(ident) => match hoisted_imports.remove(&ident) {
+                Some(item) => vec![item],
+                None => continue,
+            },
+            None => take(body),
+        };
+        let ParseResult::Ok {
+            source_map,
+            globals,
+            ..
+        } = &**parsed else {
+            unreachable!("generate_program fails for unparseable modules");
+        };
+        let mut program = Program::Module(Module {
+            span: DUMMY_SP,
+            body: items,
+            shebang: None,
+        });
+        GLOBALS.set(globals, || {
+            if !renames.is_empty() {
+                program.visit_mut_with(&mut Renamer { renames });
+            }
+            program.visit_mut_with(&mut fixer(None));
+        });
+        let (bytes, source_map) = emit_program(&program, source_map)?;
+        code.push_source(&Rope::from(bytes), Some(source_map.into()));
+        code += "\n";
+    }
+
+    let ParseResult::Ok { eval_context, .. } = &*parts[&root].0 else {
+        unreachable!("generate_program fails for unparseable modules");
+    };
+    let options = chunk_item_options(eval_context, *root.is_async_module().await?);
+
+    let code = code.build().cell();
+    Ok(EcmascriptChunkItemContent {
+        inner_code: code.await?.source_code().clone(),
+        source_map: Some(code.into()),
+        options,
+        ..Default::default()
+    }
+    .cell())
+}
+
+/// Splits the hoisted imports assigned to one of `idents` from the remaining
+/// code of `program`. Hoisted imports are keyed by the variable they are
+/// assigned to.
+fn split_hoisted_imports(
+    program: Program,
+    idents: &HashSet<JsWord>,
+) -> (HashMap<JsWord, ModuleItem>, Vec<ModuleItem>) {
+    let items = match program {
+        Program::Module(module) => module.body,
+        Program::Script(script) => script.body.into_iter().map(ModuleItem::Stmt).collect(),
+    };
+    let mut hoisted_imports = HashMap::new();
+    let mut body = Vec::new();
+    for item in items {
+        match hoisted_import_ident(&item) {
+            Some(ident) if idents.contains(&ident) => {
+                hoisted_imports.insert(ident, item);
+            }
+            _ => body.push(item),
+        }
+    }
+    (hoisted_imports, body)
+}
+
+/// Returns the name of the variable which a hoisted import like
+/// `var ident = __turbopack_import__(id);` is assigned to.
+fn hoisted_import_ident(item: &ModuleItem) -> Option<JsWord> {
+    let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) = item else {
+        return None;
+    };
+    let [VarDeclarator {
+        name: Pat::Ident(binding),
+        init: Some(box Expr::Call(call)),
+        ..
+    }] = &var.decls[..] else {
+        return None;
+    };
+    let Callee::Expr(box Expr::Ident(callee)) = &call.callee else {
+        return None;
+    };
+    callee
+        .sym
+        .starts_with("__turbopack_")
+        .then(|| binding.id.sym.clone())
+}
+
+/// Returns the ids of all bindings declared at the top level of `program`.
+fn top_level_ids(program: &Program) -> Vec<Id> {
+    let stmts: Box<dyn Iterator<Item = &Stmt>> = match program {
+        Program::Module(module) => box module.body.iter().filter_map(|item| match item {
+            ModuleItem::Stmt(stmt) => Some(stmt),
+            ModuleItem::ModuleDecl(_) => None,
+        }),
+        Program::Script(script) => box script.body.iter(),
+    };
+    let mut ids = Vec::new();
+    for stmt in stmts {
+        match stmt {
+            Stmt::Decl(Decl::Var(var)) => {
+                for decl in var.decls.iter() {
+                    ids.extend(find_pat_ids::<_, Id>(&decl.name));
+                }
+            }
+            Stmt::Decl(Decl::Fn(f)) => ids.push(f.ident.to_id()),
+            Stmt::Decl(Decl::Class(c)) => ids.push(c.ident.to_id()),
+            _ => {}
+        }
+    }
+    ids
+}
+
+/// Collects the symbols of all identifiers, including property names.
+#[derive(Default)]
+struct SymbolCollector {
+    symbols: HashSet<JsWord>,
+}
+
+impl Visit for SymbolCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.symbols.insert(ident.sym.clone());
+    }
+}
+
+/// Renames the bindings with the given ids.
+struct Renamer<'a> {
+    renames: &'a HashMap<Id, JsWord>,
+}
+
+impl Renamer<'_> {
+    fn renamed(&self, ident: &Ident) -> Option<Ident> {
+        self.renames.get(&ident.to_id()).map(|sym| Ident {
+            sym: sym.clone(),
+            ..ident.clone()
+        })
+    }
+}
+
+impl VisitMut for Renamer<'_> {
+    fn visit_mut_ident(&mut self, ident: &mut Ident) {
+        if let Some(renamed) = self.renamed(ident) {
+            *ident = renamed;
+        }
+    }
+
+    fn visit_mut_member_prop(&mut self, prop: &mut MemberProp) {
+        if let MemberProp::Computed(computed) = prop {
+            computed.visit_mut_with(self);
+        }
+    }
+
+    fn visit_mut_super_prop(&mut self, prop: &mut SuperProp) {
+        if let SuperProp::Computed(computed) = prop {
+            computed.visit_mut_with(self);
+        }
+    }
+
+    fn visit_mut_prop_name(&mut self, name: &mut PropName) {
+        if let PropName::Computed(computed) = name {
+            computed.visit_mut_with(self);
+        }
+    }
+
+    fn visit_mut_prop(&mut self, prop: &mut Prop) {
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(renamed) = self.renamed(ident) {
+                *prop = Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(ident.clone()),
+                    value: box Expr::Ident(renamed),
+                });
+            }
+            return;
+        }
+        prop.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_pat_prop(&mut self, prop: &mut ObjectPatProp) {
+        if let ObjectPatProp::Assign(AssignPatProp { span, key, value }) = prop {
+            value.visit_mut_with(self);
+            if let Some(renamed) = self.renamed(key) {
+                let binding = Pat::Ident(BindingIdent::from(renamed));
+                *prop = ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(key.clone()),
+                    value: box match value.take() {
+                        Some(default) => Pat::Assign(AssignPat {
+                            span: *span,
+                            left: box binding,
+                            right: default,
+                            type_ann: None,
+                        }),
+                        None => binding,
+                    },
+                });
+            }
+            return;
+        }
+        prop.visit_mut_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use swc_core::{
+        common::{errors::HANDLER, FileName, Mark, SourceMap},
+        ecma::{
+            ast::{EsVersion, Program},
+            atoms::JsWord,
+            codegen::{text_writer::JsWriter, Emitter},
+            parser::parse_file_as_module,
+            transforms::base::resolver,
+            visit::VisitMutWith,
+        },
+        testing::run_test,
+    };
+
+    use super::{top_level_ids, Renamer};
+
+    fn parse(cm: &Arc<SourceMap>, source: &str) -> Program {
+        let fm = cm.new_source_file(FileName::Anon, source.to_string());
+        let module = parse_file_as_module(
+            &fm,
+            Default::default(),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .map_err(|err| HANDLER.with(|handler| err.into_diagnostic(handler).emit()))
+        .unwrap();
+        let mut program = Program::Module(module);
+        program.visit_mut_with(&mut resolver(Mark::new(), Mark::new(), false));
+        program
+    }
+
+    fn to_js(program: &Program, cm: &Arc<SourceMap>) -> String {
+        let mut bytes = Vec::new();
+        let mut emitter = Emitter {
+            cfg: swc_core::ecma::codegen::Config {
+                minify: true,
+                ..Default::default()
+            },
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut bytes, None),
+        };
+        emitter.emit_program(program).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn top_level_bindings() {
+        run_test(false, |cm, _handler| {
+            let program = parse(
+                &cm,
+                "import x from 'x';\nvar a = 1, { b, c: [d] } = x;\nfunction f() { var inner; \
+                 }\nclass C {}\nexport const e = 1;\n{ let block; }",
+            );
+            let names = top_level_ids(&program)
+                .into_iter()
+                .map(|(sym, _)| sym.to_string())
+                .collect::<Vec<_>>();
+            // Exports are turned into statements by the code generation
+            // before the ids are collected
+            assert_eq!(names, ["a", "b", "d", "f", "C"]);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn renames_bindings() {
+        run_test(false, |cm, _handler| {
+            let mut program = parse(
+                &cm,
+                "var a = 1;\nvar { a = 2 } = {};\nconst obj = { a, b: a, [a]: a };\nobj.a = \
+                 a;\nfunction g() { const a = 3; return a; }",
+            );
+            let id = top_level_ids(&program).remove(0);
+            assert_eq!(&*id.0, "a");
+            let renames = HashMap::from([(id, JsWord::from("a_1"))]);
+            program.visit_mut_with(&mut Renamer { renames: &renames });
+
+            let code = to_js(&program, &cm);
+            assert!(code.contains("var a_1=1"), "{code}");
+            assert!(code.contains("{a:a_1=2}"), "{code}");
+            assert!(code.contains("{a:a_1,b:a_1,[a_1]:a_1}"), "{code}");
+            assert!(code.contains("obj.a=a_1"), "{code}");
+            // Shadowing bindings keep their names
+            assert!(code.contains("const a=3;return a"), "{code}");
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use turbo_tasks::primitives::BoolVc;
+use turbo_tasks_fs::{glob::Glob, FileJsonContent, FileSystemPathVc};
+use turbopack_core::{
+    asset::Asset,
+    resolve::{find_context_file, package_json, FindContextFileResult},
+};
+
+use crate::{
+    chunk::{EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptExports},
+    references::esm::{
+        base::ReferencedAsset,
+        export::{expand_star_exports, EsmExport},
+        EsmAssetReferenceVc,
+    },
+};
+
+/// Checks whether the module at `path` is marked as free of side effects by
+/// the `sideEffects` field of the package.json of its package. Modules without
+/// a `sideEffects` field are assumed to have side effects.
+#[turbo_tasks::function]
+pub async fn is_marked_side_effect_free(path: FileSystemPathVc) -> Result<BoolVc> {
+    let FindContextFileResult::Found(package_json, _) =
+        &*find_context_file(path.parent(), package_json()).await? else {
+        return Ok(BoolVc::cell(false));
+    };
+    let FileJsonContent::Content(content) = &*package_json.read_json().await? else {
+        return Ok(BoolVc::cell(false));
+    };
+    let side_effect_free = match content.get("sideEffects") {
+        Some(JsonValue::Bool(side_effects)) => !side_effects,
+        Some(JsonValue::Array(patterns)) => {
+            let package_dir = package_json.parent().await?;
+            let path = path.await?;
+            match package_dir.get_path_to(&path) {
+                Some(path) => {
+                    !patterns
+                        .iter()
+                        .filter_map(|pattern| pattern.as_str())
+                        .any(|pattern| {
+                            // An invalid or unsupported pattern might match anything
+                            normalize_side_effects_pattern(pattern)
+                                .and_then(|pattern| Glob::parse(&pattern).ok())
+                                .map_or(true, |glob| glob.execute(path))
+                        })
+                }
+                None => false,
+            }
+        }
+        _ => false,
+    };
+    Ok(BoolVc::cell(side_effect_free))
+}
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct FollowExportsResult {
+    pub module: EcmascriptChunkPlaceableVc,
+    /// The name of the export in `module`, or `None` when the whole namespace
+    /// of `module` is exported.
+    pub export_name: Option<String>,
+}
+
+/// Follows the re-exports of `export_name` from `module` to the module which
+/// defines the export. Only modules which are marked as side effect free are
+/// skipped, as skipping a module means it's never evaluated.
+#[turbo_tasks::function]
+pub async fn follow_reexports(
+    module: EcmascriptChunkPlaceableVc,
+    export_name: String,
+) -> Result<FollowExportsResultVc> {
+    let mut module = module;
+    let mut export_name = export_name;
+    let mut visited = HashSet::new();
+    while visited.insert((module, export_name.clone())) {
+        if !*is_marked_side_effect_free(module.ident().path()).await? {
+            break;
+        }
+        let EcmascriptExports::EsmExports(exports) = &*module.get_exports().await? else {
+            break;
+        };
+        let exports = exports.await?;
+        match exports.exports.get(&export_name) {
+            Some(EsmExport::ImportedBinding(reference, name)) => {
+                let Some(referenced) = referenced_placeable(*reference).await? else {
+                    break;
+                };
+                let name = reference.get_referenced_export(Some(name.clone())).await?;
+                let Some(name) = &*name else {
+                    return Ok(FollowExportsResult {
+                        module: referenced,
+                        export_name: None,
+                    }
+                    .cell());
+                };
+                module = referenced;
+                export_name = name.clone();
+            }
+            Some(EsmExport::ImportedNamespace(reference)) => {
+                let Some(referenced) = referenced_placeable(*reference).await? else {
+                    break;
+                };
+                return Ok(FollowExportsResult {
+                    module: referenced,
+                    export_name: None,
+                }
+                .cell());
+            }
+            Some(EsmExport::LocalBinding(_) | EsmExport::Error) => break,
+            None => {
+                // The default export is never exported by `export *`
+                if export_name == "default" {
+                    break;
+                }
+                let mut found = None;
+                for reference in exports.star_exports.iter() {
+                    let Some(referenced) = referenced_placeable(*reference).await? else {
+                        continue;
+                    };
+                    let star_exports = expand_star_exports(referenced).await?;
+                    if star_exports.star_exports.contains(&export_name) {
+                        found = Some(referenced);
+                        break;
+                    }
+                    if star_exports.has_cjs_exports {
+                        // The export might be provided by a CommonJS module at runtime
+                        break;
+                    }
+                }
+                let Some(referenced) = found else {
+                    break;
+                };
+                module = referenced;
+            }
+        }
+    }
+    Ok(FollowExportsResult {
+        module,
+        export_name: Some(export_name),
+    }
+    .cell())
+}
+
+async fn referenced_placeable(
+    reference: EsmAssetReferenceVc,
+) -> Result<Option<EcmascriptChunkPlaceableVc>> {
+    Ok(match &*reference.get_referenced_asset().await? {
+        ReferencedAsset::Some(placeable) => Some(*placeable),
+        _ => None,
+    })
+}
+
+/// Turns a pattern of the `sideEffects` field into a glob relative to the
+/// package directory. Like in webpack, patterns without a slash match files
+/// in any directory. Returns `None` for patterns using `?` or `[...]`, which
+/// [Glob] doesn't support.
+fn normalize_side_effects_pattern(pattern: &str) -> Option<String> {
+    if pattern.contains(['?', '[']) {
+        return None;
+    }
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    Some(if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{pattern}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_side_effects_pattern;
+
+    #[test]
+    fn side_effects_patterns() {
+        assert_eq!(
+            normalize_side_effects_pattern("*.css").as_deref(),
+            Some("**/*.css")
+        );
+        assert_eq!(
+            normalize_side_effects_pattern("./src/polyfill.js").as_deref(),
+            Some("src/polyfill.js")
+        );
+        assert_eq!(
+            normalize_side_effects_pattern("**/*.scss").as_deref(),
+            Some("**/*.scss")
+        );
+    }
+
+    #[test]
+    fn unsupported_side_effects_patterns() {
+        assert_eq!(normalize_side_effects_pattern("src/polyfill?.js"), None);
+        assert_eq!(normalize_side_effects_pattern("*.[jt]s"), None);
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{bail, Result};
+use serde_json::Value as JsonValue;
+use turbo_tasks::{primitives::StringVc, Value, ValueToString};
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    chunk::{ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetVc, ChunkingContextVc},
+    ident::AssetIdentVc,
+    issue::{analyze::AnalyzeIssue, IssueSeverity, IssueSource, IssueSourceVc},
+    reference::AssetReferencesVc,
+    source_pos::SourcePos,
+};
+use turbopack_ecmascript::chunk::{
+    EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+    EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkVc,
+    EcmascriptExports, EcmascriptExportsVc,
+};
+
+/// A data format which is converted to JSON at build time.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(PartialOrd, Ord, Hash, Debug, Copy, Clone)]
+pub enum DataFormat {
+    Yaml,
+    Toml,
+}
+
+impl DataFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            DataFormat::Yaml => "YAML",
+            DataFormat::Toml => "TOML",
+        }
+    }
+}
+
+#[turbo_tasks::function]
+fn modifier(format: Value<DataFormat>) -> StringVc {
+    StringVc::cell(match *format {
+        DataFormat::Yaml => "yaml".to_string(),
+        DataFormat::Toml => "toml".to_string(),
+    })
+}
+
+/// A YAML or TOML file, which exports its value like a JSON module.
+#[turbo_tasks::value]
+pub struct DataModuleAsset {
+    source: AssetVc,
+    format: DataFormat,
+}
+
+#[turbo_tasks::value_impl]
+impl DataModuleAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(source: AssetVc, format: Value<DataFormat>) -> Self {
+        Self::cell(DataModuleAsset {
+            source,
+            format: format.into_value(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for DataModuleAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.source
+            .ident()
+            .with_modifier(modifier(Value::new(self.format)))
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        self.source.content()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAsset for DataModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk(self_vc: DataModuleAssetVc, context: ChunkingContextVc) -> ChunkVc {
+        EcmascriptChunkVc::new(context, self_vc.as_ecmascript_chunk_placeable()).into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for DataModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: DataModuleAssetVc,
+        context: ChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        DataChunkItemVc::cell(DataChunkItem {
+            module: self_vc,
+            context,
+        })
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> EcmascriptExportsVc {
+        EcmascriptExports::Value.cell()
+    }
+}
+
+#[turbo_tasks::value]
+struct DataChunkItem {
+    module: DataModuleAssetVc,
+    context: ChunkingContextVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for DataChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        self.module.references()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for DataChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> ChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
+        let module = self.module.await?;
+        let FileContent::Content(file) = &*module.source.content().file_content().await? else {
+            bail!(
+                "{} file not found: {}",
+                module.format.name(),
+                self.module.ident().to_string().await?
+            );
+        };
+        let text = file.content().to_str()?;
+
+        let inner_code = match parse(module.format, &text) {
+            Ok(data) => {
+                let js_str_content = serde_json::to_string(&data.to_string())?;
+                format!("__turbopack_export_value__(JSON.parse({js_str_content}));")
+            }
+            Err(error) => {
+                let source = match error.location {
+                    ErrorLocation::Offset(offset) => {
+                        IssueSourceVc::from_byte_offset(module.source, offset, offset)
+                    }
+                    ErrorLocation::Position(pos) => IssueSource {
+                        asset: module.source,
+                        start: pos,
+                        end: pos,
+                    }
+                    .cell(),
+                };
+                AnalyzeIssue {
+                    severity: IssueSeverity::Error.cell(),
+                    source_ident: self.module.ident(),
+                    title: StringVc::cell(format!("Unable to parse {}", module.format.name())),
+                    message: StringVc::cell(error.message),
+                    category: StringVc::cell("parse".to_string()),
+                    code: None,
+                    source: Some(source),
+                }
+                .cell()
+                .as_issue()
+                .emit();
+
+                let path = self.module.ident().path().to_string().await?;
+                format!(
+                    "const e = new Error(\"Could not parse module '{path}'\");\ne.code = \
+                     'MODULE_UNPARSEABLE';\nthrow e;"
+                )
+            }
+        };
+
+        Ok(EcmascriptChunkItemContent {
+            inner_code: inner_code.into(),
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+#[derive(Debug)]
+enum ErrorLocation {
+    Offset(usize),
+    Position(SourcePos),
+}
+
+#[derive(Debug)]
+struct ParseError {
+    message: String,
+    location: ErrorLocation,
+}
+
+fn parse(format: DataFormat, text: &str) -> Result<JsonValue, ParseError> {
+    match format {
+        DataFormat::Yaml => serde_yaml::from_str(text).map_err(|err| ParseError {
+            location: ErrorLocation::Offset(err.location().map_or(0, |location| location.index())),
+            message: err.to_string(),
+        }),
+        DataFormat::Toml => text
+            .parse::<toml::Value>()
+            .map(toml_to_json)
+            .map_err(|err| {
+                let (line, column) = err.line_col().unwrap_or_default();
+                ParseError {
+                    location: ErrorLocation::Position(SourcePos { line, column }),
+                    message: err.to_string(),
+                }
+            }),
+    }
+}
+
+/// TOML has dates and times, which are exported as strings.
+fn toml_to_json(value: toml::Value) -> JsonValue {
+    match value {
+        toml::Value::String(value) => JsonValue::String(value),
+        toml::Value::Integer(value) => value.into(),
+        // NaN and infinity, which JSON can't represent, become `null`
+        toml::Value::Float(value) => value.into(),
+        toml::Value::Boolean(value) => value.into(),
+        toml::Value::Datetime(value) => JsonValue::String(value.to_string()),
+        toml::Value::Array(values) => values.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse, toml_to_json, DataFormat, ErrorLocation, SourcePos};
+
+    #[test]
+    fn parses_yaml() {
+        let value = parse(
+            DataFormat::Yaml,
+            "name: turbo\nversion: 1.5\nkeywords:\n  - bundler\n  - rust\nprivate: true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "turbo",
+                "version": 1.5,
+                "keywords": ["bundler", "rust"],
+                "private": true,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_toml() {
+        let value = parse(
+            DataFormat::Toml,
+            "name = \"turbo\"\nversion = 1\n\n[dependencies]\nserde = { version = \"1.0\", \
+             features = [\"derive\"] }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "turbo",
+                "version": 1,
+                "dependencies": {
+                    "serde": { "version": "1.0", "features": ["derive"] },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn converts_toml_values_without_json_equivalent() {
+        let value = "released = 1979-05-27T07:32:00Z\nday = 1979-05-27\nratio = nan\n"
+            .parse::<toml::Value>()
+            .unwrap();
+        assert_eq!(
+            toml_to_json(value),
+            json!({
+                "released": "1979-05-27T07:32:00Z",
+                "day": "1979-05-27",
+                "ratio": null,
+            })
+        );
+    }
+
+    #[test]
+    fn yaml_errors_have_offsets() {
+        let error = parse(DataFormat::Yaml, "name: turbo\nlist: [1, 2\n").unwrap_err();
+        // The error is reported at the end of the unterminated sequence
+        assert!(matches!(error.location, ErrorLocation::Offset(24)));
+        assert!(error.message.contains("line 3 column 1"));
+    }
+
+    #[test]
+    fn toml_errors_have_positions() {
+        let error = parse(DataFormat::Toml, "name = \"turbo\"\nversion = \n").unwrap_err();
+        // Lines and columns are 0-based, like `SourcePos`
+        assert!(matches!(
+            error.location,
+            ErrorLocation::Position(SourcePos {
+                line: 1,
+                column: 10
+            })
+        ));
+        assert!(error.message.contains("line 2 column 11"));
+    }
+}
@@ -3,27 +3,43 @@
 //! JSON assets are parsed to ensure they contain valid JSON.
 //!
 //! When imported from ES modules, they produce a module that exports the
-//! JSON value as an object.
+//! JSON value as an object. The top-level keys of a JSON object are also
+//! available as named exports, which allows unused keys to be omitted when
+//! the chunking context tracks the used exports.
+//!
+//! YAML and TOML assets are converted to JSON at build time and produce the
+//! same kind of module.
 
 #![feature(min_specialization)]
 
-use std::fmt::Write;
+mod data;
+
+use std::{collections::BTreeMap, fmt::Write};
 
 use anyhow::{bail, Error, Result};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use turbo_tasks::{primitives::StringVc, ValueToString};
 use turbo_tasks_fs::{FileContent, FileJsonContent};
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
-    chunk::{ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetVc, ChunkingContextVc},
+    chunk::{
+        ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetVc, ChunkingContext,
+        ChunkingContextVc, UsedExports,
+    },
     ident::AssetIdentVc,
     reference::AssetReferencesVc,
 };
-use turbopack_ecmascript::chunk::{
-    EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
-    EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkVc,
-    EcmascriptExports, EcmascriptExportsVc,
+use turbopack_ecmascript::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc,
+        EcmascriptChunkVc, EcmascriptExports, EcmascriptExportsVc,
+    },
+    EsmExport, EsmExports,
 };
 
+pub use self::data::{DataFormat, DataModuleAsset, DataModuleAssetVc};
+
 #[turbo_tasks::function]
 fn modifier() -> StringVc {
     StringVc::cell("json".to_string())
@@ -77,9 +93,55 @@ impl EcmascriptChunkPlaceable for JsonModuleAsset {
         .into()
     }
 
+    /// The top-level keys of an object are exported by name, next to the
+    /// whole value as the default export. Other values are only exported as
+    /// the default export.
     #[turbo_tasks::function]
-    fn get_exports(&self) -> EcmascriptExportsVc {
-        EcmascriptExports::Value.cell()
+    async fn get_exports(&self) -> Result<EcmascriptExportsVc> {
+        let FileJsonContent::Content(JsonValue::Object(object)) =
+            &*self.source.content().file_content().parse_json().await?
+        else {
+            return Ok(EcmascriptExports::Value.cell());
+        };
+        Ok(EcmascriptExports::EsmExports(
+            EsmExports {
+                exports: object_exports(object),
+                star_exports: Vec::new(),
+            }
+            .cell(),
+        )
+        .cell())
+    }
+}
+
+/// Every top-level key of a JSON object is a named export, next to the whole
+/// object as the default export.
+fn object_exports(object: &JsonMap<String, JsonValue>) -> BTreeMap<String, EsmExport> {
+    let mut exports = object
+        .keys()
+        .map(|key| (key.clone(), EsmExport::LocalBinding(key.clone())))
+        .collect::<BTreeMap<_, _>>();
+    exports.insert(
+        "default".to_string(),
+        EsmExport::LocalBinding("default".to_string()),
+    );
+    exports
+}
+
+/// Returns the object without the keys which aren't used, when only named
+/// exports of it are used.
+fn filter_unused_keys(data: &JsonValue, used_exports: &UsedExports) -> Option<JsonValue> {
+    match (data, used_exports) {
+        (JsonValue::Object(object), UsedExports::Names(names)) if !names.contains("default") => {
+            Some(JsonValue::Object(
+                object
+                    .iter()
+                    .filter(|(key, _)| names.contains(key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ))
+        }
+        _ => None,
     }
 }
 
@@ -117,6 +179,12 @@ impl EcmascriptChunkItem for JsonChunkItem {
         let data = content.parse_json().await?;
         match &*data {
             FileJsonContent::Content(data) => {
+                // The module still exports the value itself, so that it can be
+                // required from CommonJS. Keys which aren't imported by name
+                // are omitted when the whole value isn't used.
+                let used_exports = self.context.used_exports(self.module.ident()).await?;
+                let filtered = filter_unused_keys(data, &used_exports);
+                let data = filtered.as_ref().unwrap_or(data);
                 let js_str_content = serde_json::to_string(&data.to_string())?;
                 let inner_code =
                     format!("__turbopack_export_value__(JSON.parse({js_str_content}));");
@@ -155,3 +223,46 @@ pub fn register() {
     turbopack_ecmascript::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use turbopack_core::chunk::UsedExports;
+
+    use super::{filter_unused_keys, object_exports};
+
+    #[test]
+    fn exports_top_level_keys() {
+        let value = json!({ "name": "turbo", "version": 1, "nested": { "key": true } });
+        let exports = object_exports(value.as_object().unwrap());
+        assert_eq!(
+            exports.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["default", "name", "nested", "version"]
+        );
+    }
+
+    #[test]
+    fn filters_unused_keys() {
+        let value = json!({ "name": "turbo", "version": 1, "nested": { "key": true } });
+        let used = UsedExports::Names(["name".to_string(), "nested".to_string()].into());
+        assert_eq!(
+            filter_unused_keys(&value, &used),
+            Some(json!({ "name": "turbo", "nested": { "key": true } }))
+        );
+
+        let none_used = UsedExports::Names(Default::default());
+        assert_eq!(filter_unused_keys(&value, &none_used), Some(json!({})));
+    }
+
+    #[test]
+    fn keeps_keys_when_whole_value_is_used() {
+        let value = json!({ "name": "turbo", "version": 1 });
+        let default_used = UsedExports::Names(["default".to_string()].into());
+        assert_eq!(filter_unused_keys(&value, &default_used), None);
+        assert_eq!(filter_unused_keys(&value, &UsedExports::All), None);
+
+        let array = json!(["name", "version"]);
+        let used = UsedExports::Names(["name".to_string()].into());
+        assert_eq!(filter_unused_keys(&array, &used), None);
+    }
+}
@@ -0,0 +1,65 @@
+// @ts-ignore
+import less from "@vercel/turbopack/less";
+import { resolve as pathResolve } from "path";
+import type { Ipc } from "../ipc/evaluate";
+import { contextDir, toPath } from "./util";
+
+const transform = async (ipc: Ipc, content: string, name: string) => {
+  const filename = pathResolve(contextDir, name);
+
+  let result;
+  try {
+    result = await less.render(content, {
+      filename,
+      sourceMap: {},
+    });
+  } catch (err: any) {
+    // Errors of the compiler have a location, everything else is unexpected.
+    if (typeof err.line !== "number") {
+      throw err;
+    }
+    const errorFilename =
+      typeof err.filename === "string"
+        ? pathResolve(contextDir, err.filename)
+        : undefined;
+    // The error might be in an imported file, which needs to be watched so
+    // that fixing the error recompiles the stylesheet.
+    if (errorFilename !== undefined && errorFilename !== filename) {
+      ipc.send({
+        type: "fileDependency",
+        path: toPath(errorFilename),
+      });
+    }
+    // Less reports 1-based lines and 0-based columns.
+    const position = { line: err.line - 1, column: err.column ?? 0 };
+    const issue = {
+      severity: "error",
+      message: err.message,
+      source:
+        errorFilename !== undefined
+          ? { path: toPath(errorFilename), start: position, end: position }
+          : undefined,
+    };
+    return { css: null, dependencies: [], issues: [issue] };
+  }
+
+  // Files imported with `@import`
+  const dependencies = [];
+  for (const file of result.imports) {
+    const path = toPath(pathResolve(contextDir, file));
+    ipc.send({
+      type: "fileDependency",
+      path,
+    });
+    dependencies.push(path);
+  }
+
+  return {
+    css: result.css,
+    map: result.map,
+    dependencies,
+    issues: [],
+  };
+};
+
+export { transform as default };
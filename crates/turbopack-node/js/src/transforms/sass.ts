@@ -0,0 +1,95 @@
+// @ts-ignore
+import sass from "@vercel/turbopack/sass";
+import { resolve as pathResolve } from "path";
+import { fileURLToPath, pathToFileURL } from "url";
+import type { Ipc } from "../ipc/evaluate";
+import { contextDir, toPath } from "./util";
+
+type SourceLocation = { line: number; column: number };
+type SourceSpan = { url?: URL; start: SourceLocation; end: SourceLocation };
+
+const toIssue = (
+  severity: "error" | "warning",
+  message: string,
+  span?: SourceSpan
+) => ({
+  severity,
+  message,
+  source:
+    span?.url?.protocol === "file:"
+      ? {
+          path: toPath(fileURLToPath(span.url)),
+          start: span.start,
+          end: span.end,
+        }
+      : undefined,
+});
+
+const transform = async (
+  ipc: Ipc,
+  content: string,
+  name: string,
+  options: { [k: string]: any }
+) => {
+  // `includePaths` is the name of the option in the legacy API, which is
+  // still used by most configs.
+  const { includePaths, loadPaths, ...sassOptions } = options;
+  const url = pathToFileURL(pathResolve(contextDir, name));
+  const issues = [];
+
+  let result;
+  try {
+    result = sass.compileString(content, {
+      ...sassOptions,
+      loadPaths: [...(loadPaths ?? []), ...(includePaths ?? [])],
+      syntax: name.endsWith(".sass") ? "indented" : "scss",
+      url,
+      sourceMap: true,
+      logger: {
+        warn(message: string, { span }: { span?: SourceSpan }) {
+          issues.push(toIssue("warning", message, span));
+        },
+      },
+    });
+  } catch (err: any) {
+    if (!(err instanceof sass.Exception)) {
+      throw err;
+    }
+    // The error might be in a partial, which needs to be watched so that
+    // fixing the error recompiles the stylesheet.
+    if (
+      err.span?.url?.protocol === "file:" &&
+      err.span.url.href !== url.href
+    ) {
+      ipc.send({
+        type: "fileDependency",
+        path: toPath(fileURLToPath(err.span.url)),
+      });
+    }
+    issues.push(toIssue("error", err.sassMessage, err.span));
+    return { css: null, dependencies: [], issues };
+  }
+
+  // Partials loaded with `@use`, `@forward` or `@import`
+  const dependencies = [];
+  for (const loadedUrl of result.loadedUrls) {
+    if (loadedUrl.protocol !== "file:" || loadedUrl.href === url.href) {
+      continue;
+    }
+    const path = toPath(fileURLToPath(loadedUrl));
+    ipc.send({
+      type: "fileDependency",
+      path,
+    });
+    dependencies.push(path);
+  }
+
+  return {
+    css: result.css,
+    map: JSON.stringify(result.sourceMap),
+    dependencies,
+    issues,
+  };
+};
+
+export { transform as default };
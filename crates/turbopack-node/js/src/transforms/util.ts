@@ -0,0 +1,18 @@
+import { relative, isAbsolute, sep } from "path";
+
+/** The project path, which the transforms are evaluated in. */
+export const contextDir = process.cwd();
+
+/**
+ * Converts an absolute path into a path relative to the project path, which
+ * is how dependencies are reported to turbopack.
+ */
+export const toPath = (file: string) => {
+  const relPath = relative(contextDir, file);
+  if (isAbsolute(relPath)) {
+    throw new Error(
+      `Cannot depend on path (${file}) outside of root directory (${contextDir})`
+    );
+  }
+  return sep !== "/" ? relPath.replaceAll(sep, "/") : relPath;
+};
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use turbo_tasks::Value;
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    context::{AssetContext, AssetContextVc},
+    ident::AssetIdentVc,
+    issue::IssueContextExt,
+    reference::AssetReferencesVc,
+    source_asset::SourceAssetVc,
+    source_transform::{SourceTransform, SourceTransformVc},
+};
+use turbopack_ecmascript::{
+    EcmascriptInputTransform, EcmascriptInputTransformsVc, EcmascriptModuleAssetType,
+    EcmascriptModuleAssetVc,
+};
+
+use super::preprocessor::{dependency_references, preprocess, PreprocessedStylesheetVc};
+use crate::{embed_js::embed_file_path, execution_context::ExecutionContextVc};
+
+/// Compiles Less stylesheets to CSS with the `less` package installed in the
+/// project.
+#[turbo_tasks::value]
+pub struct LessTransform {
+    evaluate_context: AssetContextVc,
+    execution_context: ExecutionContextVc,
+}
+
+#[turbo_tasks::value_impl]
+impl LessTransformVc {
+    #[turbo_tasks::function]
+    pub fn new(evaluate_context: AssetContextVc, execution_context: ExecutionContextVc) -> Self {
+        LessTransform {
+            evaluate_context,
+            execution_context,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for LessTransform {
+    #[turbo_tasks::function]
+    fn transform(&self, source: AssetVc) -> AssetVc {
+        LessTransformedAsset {
+            evaluate_context: self.evaluate_context,
+            execution_context: self.execution_context,
+            source,
+        }
+        .cell()
+        .into()
+    }
+}
+
+#[turbo_tasks::value]
+struct LessTransformedAsset {
+    evaluate_context: AssetContextVc,
+    execution_context: ExecutionContextVc,
+    source: AssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for LessTransformedAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.source.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn content(self_vc: LessTransformedAssetVc) -> Result<AssetContentVc> {
+        let this = self_vc.await?;
+        Ok(self_vc
+            .process()
+            .issue_context(this.source.ident().path(), "Less compilation")
+            .await?
+            .await?
+            .content)
+    }
+
+    /// The files imported with `@import`.
+    #[turbo_tasks::function]
+    async fn references(self_vc: LessTransformedAssetVc) -> Result<AssetReferencesVc> {
+        dependency_references(self_vc.process(), "less import").await
+    }
+}
+
+#[turbo_tasks::function]
+fn less_executor(context: AssetContextVc) -> AssetVc {
+    EcmascriptModuleAssetVc::new(
+        SourceAssetVc::new(embed_file_path("transforms/less.ts")).into(),
+        context,
+        Value::new(EcmascriptModuleAssetType::Typescript),
+        EcmascriptInputTransformsVc::cell(vec![EcmascriptInputTransform::TypeScript]),
+        context.compile_time_info(),
+    )
+    .into()
+}
+
+#[turbo_tasks::value_impl]
+impl LessTransformedAssetVc {
+    #[turbo_tasks::function]
+    async fn process(self) -> Result<PreprocessedStylesheetVc> {
+        let this = self.await?;
+        preprocess(
+            "Less",
+            less_executor(this.evaluate_context),
+            this.evaluate_context,
+            this.execution_context,
+            this.source,
+            Vec::new(),
+        )
+        .await
+    }
+}
@@ -1,3 +1,6 @@
+pub mod less;
 pub mod postcss;
+pub(crate) mod preprocessor;
+pub mod sass;
 mod util;
 pub mod webpack;
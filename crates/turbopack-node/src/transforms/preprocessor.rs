@@ -0,0 +1,236 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    primitives::{JsonValueVc, StringVc},
+    CompletionVc, ValueToString,
+};
+use turbo_tasks_fs::{
+    json::parse_json_rope_with_source_context, File, FileContent, FileSystemPathVc,
+};
+use turbopack_core::{
+    asset::{Asset, AssetContent, AssetContentVc, AssetVc},
+    context::AssetContextVc,
+    issue::{
+        Issue, IssueSeverity, IssueSeverityVc, IssueSource, IssueSourceVc, IssueVc,
+        OptionIssueSourceVc,
+    },
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+    source_asset::SourceAssetVc,
+    source_pos::SourcePos,
+};
+
+use crate::{
+    evaluate::{evaluate, JavaScriptValue},
+    execution_context::{ExecutionContext, ExecutionContextVc},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[turbo_tasks::value(transparent, serialization = "custom")]
+struct PreprocessingResult {
+    css: Option<String>,
+    map: Option<String>,
+    dependencies: Vec<String>,
+    #[turbo_tasks(trace_ignore)]
+    issues: Vec<PreprocessorMessage>,
+}
+
+/// An error or warning reported by a CSS preprocessor.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+struct PreprocessorMessage {
+    severity: IssueSeverity,
+    message: String,
+    source: Option<PreprocessorMessageSource>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+struct PreprocessorMessageSource {
+    /// The path of the stylesheet, relative to the project path.
+    path: String,
+    start: SourcePos,
+    end: SourcePos,
+}
+
+/// A stylesheet compiled to CSS by a CSS preprocessor and the files it was
+/// compiled from.
+#[turbo_tasks::value]
+pub(crate) struct PreprocessedStylesheet {
+    pub content: AssetContentVc,
+    pub dependencies: Vec<FileSystemPathVc>,
+}
+
+impl PreprocessedStylesheet {
+    fn not_found() -> PreprocessedStylesheetVc {
+        PreprocessedStylesheet {
+            content: AssetContent::File(FileContent::NotFound.cell()).cell(),
+            dependencies: Vec::new(),
+        }
+        .cell()
+    }
+}
+
+/// Compiles the `source` stylesheet with the `executor` of a preprocessor
+/// transform from `js/src/transforms`. The transform is called with the
+/// content and the path of the stylesheet, followed by `args`.
+///
+/// Errors and warnings of the preprocessor are emitted as
+/// [PreprocessorIssue]s titled "{name} compilation".
+pub(crate) async fn preprocess(
+    name: &str,
+    executor: AssetVc,
+    evaluate_context: AssetContextVc,
+    execution_context: ExecutionContextVc,
+    source: AssetVc,
+    args: Vec<JsonValueVc>,
+) -> Result<PreprocessedStylesheetVc> {
+    let ExecutionContext {
+        project_path,
+        intermediate_output_path,
+        env,
+    } = *execution_context.await?;
+    let AssetContent::File(file) = *source.content().await? else {
+        bail!("{name} transform only support transforming files");
+    };
+    let FileContent::Content(content) = &*file.await? else {
+        return Ok(PreprocessedStylesheet::not_found());
+    };
+    let content = content.content().to_str()?;
+
+    let fs_path = source.ident().path().await?;
+    let path = fs_path.path.as_str();
+    let config_value = evaluate(
+        project_path,
+        executor,
+        project_path,
+        env,
+        source.ident(),
+        evaluate_context,
+        intermediate_output_path,
+        None,
+        [
+            JsonValueVc::cell(content.into()),
+            JsonValueVc::cell(path.into()),
+        ]
+        .into_iter()
+        .chain(args)
+        .collect(),
+        CompletionVc::immutable(),
+        /* debug */ false,
+    )
+    .await?;
+    let JavaScriptValue::Value(val) = &*config_value else {
+        // An error happened, which has already been converted into an issue.
+        return Ok(PreprocessedStylesheet::not_found());
+    };
+    let processed: PreprocessingResult =
+        parse_json_rope_with_source_context(val).with_context(|| {
+            format!("Unable to deserializate response from {name} transform operation")
+        })?;
+
+    for message in processed.issues {
+        let issue_source = message.source.map(|message_source| {
+            // Errors in the stylesheet itself point to the source, which might have
+            // been transformed already
+            let asset = if message_source.path == path {
+                source
+            } else {
+                SourceAssetVc::new(project_path.join(&message_source.path)).into()
+            };
+            IssueSource {
+                asset,
+                start: message_source.start,
+                end: message_source.end,
+            }
+            .cell()
+        });
+        PreprocessorIssue {
+            path: source.ident().path(),
+            title: StringVc::cell(format!("{name} compilation")),
+            severity: message.severity.cell(),
+            message: StringVc::cell(message.message),
+            source: issue_source,
+        }
+        .cell()
+        .as_issue()
+        .emit();
+    }
+
+    let dependencies = processed
+        .dependencies
+        .iter()
+        .map(|path| project_path.join(path))
+        .collect();
+    // TODO handle SourceMap
+    let content = match processed.css {
+        Some(css) => FileContent::Content(File::from(css)),
+        // The stylesheet doesn't compile, which has been reported as an issue.
+        None => FileContent::NotFound,
+    };
+    Ok(PreprocessedStylesheet {
+        content: AssetContent::File(content.cell()).cell(),
+        dependencies,
+    }
+    .cell())
+}
+
+/// References the files a stylesheet was compiled from, described as
+/// "{kind} {path}".
+pub(crate) async fn dependency_references(
+    stylesheet: PreprocessedStylesheetVc,
+    kind: &str,
+) -> Result<AssetReferencesVc> {
+    let mut references = Vec::new();
+    for &path in stylesheet.await?.dependencies.iter() {
+        references.push(
+            SingleAssetReferenceVc::new(
+                SourceAssetVc::new(path).into(),
+                StringVc::cell(format!("{kind} {}", path.to_string().await?)),
+            )
+            .into(),
+        );
+    }
+    Ok(AssetReferencesVc::cell(references))
+}
+
+/// An error or warning reported by a CSS preprocessor.
+#[turbo_tasks::value(shared)]
+pub struct PreprocessorIssue {
+    pub path: FileSystemPathVc,
+    pub title: StringVc,
+    pub severity: IssueSeverityVc,
+    pub message: StringVc,
+    pub source: Option<IssueSourceVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for PreprocessorIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        self.severity
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        self.title
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("css".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        self.message
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> OptionIssueSourceVc {
+        OptionIssueSourceVc::cell(self.source)
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use turbo_tasks::{primitives::JsonValueVc, Value};
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    context::{AssetContext, AssetContextVc},
+    ident::AssetIdentVc,
+    issue::IssueContextExt,
+    reference::AssetReferencesVc,
+    source_asset::SourceAssetVc,
+    source_transform::{SourceTransform, SourceTransformVc},
+};
+use turbopack_ecmascript::{
+    EcmascriptInputTransform, EcmascriptInputTransformsVc, EcmascriptModuleAssetType,
+    EcmascriptModuleAssetVc,
+};
+
+use super::preprocessor::{dependency_references, preprocess, PreprocessedStylesheetVc};
+use crate::{embed_js::embed_file_path, execution_context::ExecutionContextVc};
+
+/// Compiles Sass stylesheets (`.scss` and `.sass`) to CSS with the `sass`
+/// package installed in the project.
+#[turbo_tasks::value]
+pub struct SassTransform {
+    evaluate_context: AssetContextVc,
+    execution_context: ExecutionContextVc,
+    /// Options passed to the Sass compiler, e.g. `loadPaths`.
+    options: JsonValueVc,
+}
+
+#[turbo_tasks::value_impl]
+impl SassTransformVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        evaluate_context: AssetContextVc,
+        execution_context: ExecutionContextVc,
+        options: JsonValueVc,
+    ) -> Self {
+        SassTransform {
+            evaluate_context,
+            execution_context,
+            options,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for SassTransform {
+    #[turbo_tasks::function]
+    fn transform(&self, source: AssetVc) -> AssetVc {
+        SassTransformedAsset {
+            evaluate_context: self.evaluate_context,
+            execution_context: self.execution_context,
+            options: self.options,
+            source,
+        }
+        .cell()
+        .into()
+    }
+}
+
+#[turbo_tasks::value]
+struct SassTransformedAsset {
+    evaluate_context: AssetContextVc,
+    execution_context: ExecutionContextVc,
+    options: JsonValueVc,
+    source: AssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for SassTransformedAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.source.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn content(self_vc: SassTransformedAssetVc) -> Result<AssetContentVc> {
+        let this = self_vc.await?;
+        Ok(self_vc
+            .process()
+            .issue_context(this.source.ident().path(), "Sass compilation")
+            .await?
+            .await?
+            .content)
+    }
+
+    /// The partials loaded with `@use`, `@forward` or `@import`.
+    #[turbo_tasks::function]
+    async fn references(self_vc: SassTransformedAssetVc) -> Result<AssetReferencesVc> {
+        dependency_references(self_vc.process(), "sass import").await
+    }
+}
+
+#[turbo_tasks::function]
+fn sass_executor(context: AssetContextVc) -> AssetVc {
+    EcmascriptModuleAssetVc::new(
+        SourceAssetVc::new(embed_file_path("transforms/sass.ts")).into(),
+        context,
+        Value::new(EcmascriptModuleAssetType::Typescript),
+        EcmascriptInputTransformsVc::cell(vec![EcmascriptInputTransform::TypeScript]),
+        context.compile_time_info(),
+    )
+    .into()
+}
+
+#[turbo_tasks::value_impl]
+impl SassTransformedAssetVc {
+    #[turbo_tasks::function]
+    async fn process(self) -> Result<PreprocessedStylesheetVc> {
+        let this = self.await?;
+        preprocess(
+            "Sass",
+            sass_executor(this.evaluate_context),
+            this.evaluate_context,
+            this.execution_context,
+            this.source,
+            vec![this.options],
+        )
+        .await
+    }
+}
@@ -19,15 +19,23 @@ use turbo_tasks_fs::{
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::{
     condition::ContextCondition,
-    ecmascript::{chunk::EcmascriptChunkPlaceablesVc, EcmascriptModuleAssetVc},
+    ecmascript::{
+        chunk::{EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc},
+        export_usage::compute_export_usage,
+        scope_hoisting::compute_module_concatenation,
+        EcmascriptModuleAssetVc,
+    },
     module_options::ModuleOptionsContext,
     resolve_options_context::ResolveOptionsContext,
     transition::TransitionsByNameVc,
     ModuleAssetContextVc,
 };
 use turbopack_core::{
-    asset::{Asset, AssetVc},
-    chunk::{dev::DevChunkingContextVc, ChunkableAsset, ChunkableAssetVc},
+    asset::{Asset, AssetVc, AssetsVc},
+    chunk::{
+        dev::DevChunkingContextVc, production::ProductionChunkingContextVc, ChunkableAsset,
+        ChunkableAssetVc,
+    },
     compile_time_defines,
     compile_time_info::CompileTimeInfo,
     context::{AssetContext, AssetContextVc},
@@ -63,6 +71,19 @@ struct SnapshotOptions {
     browserslist: String,
     #[serde(default = "default_entry")]
     entry: String,
+    /// Uses a production chunking context instead of a dev one.
+    #[serde(default)]
+    production: Option<ProductionOptions>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ProductionOptions {
+    minify: bool,
+    /// Omits exports and declarations which are not used by the entry.
+    tree_shaking: bool,
+    /// Concatenates modules into the modules importing them.
+    scope_hoisting: bool,
 }
 
 impl Default for SnapshotOptions {
@@ -70,6 +91,7 @@ impl Default for SnapshotOptions {
         SnapshotOptions {
             browserslist: default_browserslist(),
             entry: default_entry(),
+            production: None,
         }
     }
 }
@@ -223,11 +245,54 @@ async fn run_test(resource: String) -> Result<FileSystemPathVc> {
     )
     .into();
 
+    let modules = entry_paths
+        .into_iter()
+        .map(SourceAssetVc::new)
+        .map(|p| {
+            context.process(
+                p.into(),
+                Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined)),
+            )
+        })
+        .collect::<Vec<_>>();
+
     let chunk_root_path = path.join("output");
     let static_root_path = path.join("static");
-    let chunking_context =
+    let chunking_context = if let Some(production) = &options.production {
+        let mut builder = ProductionChunkingContextVc::builder(
+            project_root,
+            path,
+            chunk_root_path,
+            static_root_path,
+            env,
+        );
+        builder = builder.module_ids(AssetsVc::cell(modules.clone()));
+        if production.minify {
+            builder = builder.minify();
+        }
+        if production.tree_shaking || production.scope_hoisting {
+            let mut entries = Vec::new();
+            for module in modules.iter() {
+                if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(*module).await? {
+                    entries.push(placeable);
+                }
+            }
+            if let Some(runtime_entries) = runtime_entries {
+                entries.extend(runtime_entries.await?.iter().copied());
+            }
+            let entries = EcmascriptChunkPlaceablesVc::cell(entries);
+            if production.tree_shaking {
+                builder = builder.tree_shaking(compute_export_usage(entries));
+            }
+            if production.scope_hoisting {
+                builder = builder.scope_hoisting(compute_module_concatenation(entries));
+            }
+        }
+        builder.build()
+    } else {
         DevChunkingContextVc::builder(project_root, path, chunk_root_path, static_root_path, env)
-            .build();
+            .build()
+    };
 
     let expected_paths = expected(chunk_root_path)
         .await?
@@ -235,14 +300,8 @@ async fn run_test(resource: String) -> Result<FileSystemPathVc> {
         .copied()
         .collect();
 
-    let modules = entry_paths.into_iter().map(SourceAssetVc::new).map(|p| {
-        context.process(
-            p.into(),
-            Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined)),
-        )
-    });
-
     let chunks = modules
+        .into_iter()
         .map(|module| async move {
             if let Some(ecmascript) = EcmascriptModuleAssetVc::resolve_from(module).await? {
                 // TODO: Load runtime entries from snapshots
@@ -0,0 +1,8 @@
+let count = require("./count.js");
+
+module.hot.accept("./count.js", () => {
+  count = require("./count.js");
+  console.log("count", count);
+});
+
+console.log("count", count);
@@ -0,0 +1,22 @@
+import { message } from "./message.js";
+import { render } from "./view.js";
+import "./counter.js";
+import "./declined.js";
+
+render(message);
+
+// `message` is imported again before the callback is called.
+import.meta.hot.accept("./message.js", () => {
+  render(message);
+});
+
+// Updates of `./theme.js` bubble through `./view.js`, which doesn't accept
+// them, up to this module.
+import.meta.hot.accept(["./view.js"], (outdated) => {
+  console.log("updated", outdated);
+  render(message);
+});
+
+// Updates of `./declined.js` fall back to a full reload, like updates of this
+// module, which nothing accepts.
+import.meta.hot.decline("./declined.js");
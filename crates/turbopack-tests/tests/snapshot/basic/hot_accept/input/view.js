@@ -0,0 +1,5 @@
+import { color } from "./theme.js";
+
+export function render(text) {
+  console.log(`%c${text}`, `color: ${color}`);
+}
@@ -0,0 +1,11 @@
+const locales = require.context("./locales", true, /\.json$/i);
+
+for (const key of locales.keys()) {
+  console.log(key, locales(key));
+}
+
+const shallow = require.context("./locales", false, /^\.\/en/);
+console.log(shallow.keys(), shallow.resolve("./en.json"));
+
+// Only directories relative to the module are supported
+require.context("locales");
@@ -0,0 +1,3 @@
+const response = await Promise.resolve({ name: "config" });
+
+export const config = response;
@@ -0,0 +1,4 @@
+import { b } from "./cycle_b.js";
+
+// Async through `cycle_b.js`, which imports this module again
+export const a = () => b;
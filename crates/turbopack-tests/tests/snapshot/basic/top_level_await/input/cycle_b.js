@@ -0,0 +1,4 @@
+import { a } from "./cycle_a.js";
+import { config } from "./config.js";
+
+export const b = () => [a, config];
@@ -0,0 +1,5 @@
+import { config } from "./config.js";
+
+export function describe(value) {
+  return value === config ? "loaded config" : "other value";
+}
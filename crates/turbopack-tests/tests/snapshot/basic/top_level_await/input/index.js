@@ -0,0 +1,6 @@
+import { config } from "./config.js";
+import { describe } from "./describe.js";
+import { a } from "./cycle_a.js";
+import { a as syncA } from "./sync_cycle_a.js";
+
+console.log(describe(config), a, syncA);
@@ -0,0 +1,4 @@
+import { b } from "./sync_cycle_b.js";
+
+// Not async, none of the modules in the cycle use top-level `await`
+export const a = () => b;
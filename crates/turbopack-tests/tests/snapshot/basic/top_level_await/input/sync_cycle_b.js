@@ -0,0 +1,3 @@
+import { a } from "./sync_cycle_a.js";
+
+export const b = () => a;
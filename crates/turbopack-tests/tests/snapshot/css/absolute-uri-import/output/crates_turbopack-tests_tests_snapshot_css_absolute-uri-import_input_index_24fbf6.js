@@ -49,6 +49,13 @@ const BACKEND = {
     });
   },
 
+  loadWebAssembly(wasmPath, importsObj, _from) {
+    return WebAssembly.instantiateStreaming(
+      fetch(`/${wasmPath}`),
+      importsObj
+    ).then(({ instance }) => instance.exports);
+  },
+
   restart: () => self.location.reload(),
 };
 /* eslint-disable @next/next/no-assign-module-variable */
@@ -182,6 +189,30 @@ function exportValue(module, value) {
   module.exports = value;
 }
 
+/**
+ * Evaluates the body of an async module, i.e. a module which uses top-level
+ * `await` or imports async modules. The exports are defined synchronously,
+ * but they are only initialized once `module.async` resolved.
+ *
+ * @param {Module} module
+ * @param {() => Promise<void>} body
+ */
+function asyncModule(module, body) {
+  module.async = body();
+}
+
+/**
+ * Waits for the async modules among the imported modules to be evaluated.
+ * Modules which are still being instantiated, e.g. in a circular import, are
+ * not waited for.
+ *
+ * @param {ModuleId[]} ids
+ * @returns {Promise<any>}
+ */
+function asyncDependencies(ids) {
+  return Promise.all(ids.map((id) => moduleCache[id]?.async));
+}
+
 /**
  * @param {Record<string, any>} obj
  * @param {string} key
@@ -269,6 +300,19 @@ function loadChunk(from, chunkPath) {
   return chunkLoader.promise;
 }
 
+/**
+ * Instantiates the WebAssembly module at `wasmPath` and resolves to its
+ * exports.
+ *
+ * @param {ModuleId} from
+ * @param {string} wasmPath
+ * @param {WebAssembly.Imports} importsObj
+ * @returns {Promise<WebAssembly.Exports>}
+ */
+function loadWebAssembly(from, wasmPath, importsObj) {
+  return BACKEND.loadWebAssembly(wasmPath, importsObj, from);
+}
+
 /**
  * @param {string} chunkPath
  * @param {ModuleId} from
@@ -374,6 +418,7 @@ function instantiateModule(id, sourceType, sourceId) {
     parents: [],
     children: [],
     interopNamespace: undefined,
+    async: undefined,
     hot,
   };
   moduleCache[id] = module;
@@ -397,9 +442,12 @@ function instantiateModule(id, sourceType, sourceId) {
       s: esm.bind(null, module.exports),
       j: cjs.bind(null, module.exports),
       v: exportValue.bind(null, module),
+      a: asyncModule.bind(null, module),
+      w: asyncDependencies,
       m: module,
       c: moduleCache,
       l: loadChunk.bind(null, id),
+      u: loadWebAssembly.bind(null, id),
       p: _process,
       g: globalThis,
       __dirname: module.id.replace(/(^|\/)[\/]+$/, ""),
@@ -553,10 +601,11 @@ function _eval({ code, url, map }) {
 
 /**
  * @param {EcmascriptChunkUpdate} update
- * @returns {{outdatedModules: Set<any>, newModuleFactories: Map<any, any>}}
+ * @returns {{outdatedModules: Set<any>, outdatedDependencies: Map<ModuleId, Set<ModuleId>>, newModuleFactories: Map<any, any>}}
  */
 function computeOutdatedModules(update) {
   const outdatedModules = new Set();
+  const outdatedDependencies = new Map();
   const newModuleFactories = new Map();
 
   for (const [moduleId, factory] of Object.entries(update.added)) {
@@ -579,17 +628,32 @@ function computeOutdatedModules(update) {
             effect.dependencyChain
           )}.`
         );
+      case "declined":
+        throw new Error(
+          `cannot apply update: declined dependency of module ${
+            effect.parentId
+          }. ${formatDependencyChain(effect.dependencyChain)}.`
+        );
       case "accepted":
         newModuleFactories.set(moduleId, _eval(factory));
         for (const outdatedModuleId of effect.outdatedModules) {
           outdatedModules.add(outdatedModuleId);
         }
+        for (const [parentId, dependencyIds] of effect.outdatedDependencies) {
+          let parentDependencies = outdatedDependencies.get(parentId);
+          if (!parentDependencies) {
+            parentDependencies = new Set();
+            outdatedDependencies.set(parentId, parentDependencies);
+          }
+          for (const dependencyId of dependencyIds) {
+            parentDependencies.add(dependencyId);
+          }
+        }
         break;
-      // TODO(alexkirsz) Dependencies: handle dependencies effects.
     }
   }
 
-  return { outdatedModules, newModuleFactories };
+  return { outdatedModules, outdatedDependencies, newModuleFactories };
 }
 
 /**
@@ -697,11 +761,13 @@ function disposeModule(module) {
  *
  * @param {ChunkPath} chunkPath
  * @param {{ moduleId: ModuleId, errorHandler: true | Function }[]} outdatedSelfAcceptedModules
+ * @param {Map<ModuleId, Set<ModuleId>>} outdatedDependencies
  * @param {Map<string, ModuleFactory>} newModuleFactories
  */
 function applyPhase(
   chunkPath,
   outdatedSelfAcceptedModules,
+  outdatedDependencies,
   newModuleFactories
 ) {
   // Update module factories.
@@ -712,7 +778,59 @@ function applyPhase(
 
   // TODO(alexkirsz) Run new runtime entries here.
 
-  // TODO(alexkirsz) Dependencies: call accept handlers for outdated deps.
+  // Call the accept handlers of modules which accepted their outdated
+  // dependencies. Every handler is called once with all outdated dependencies
+  // of the module.
+  for (const [moduleId, dependencyIds] of outdatedDependencies) {
+    const module = moduleCache[moduleId];
+    if (!module) {
+      continue;
+    }
+
+    const hotState = moduleHotState.get(module);
+    const outdated = [...dependencyIds];
+
+    // Re-import the outdated dependencies before the handlers are called, so
+    // the updated modules are instantiated and the accepting module is their
+    // parent again. For ESM, the compiler wraps the handlers to also update
+    // the imported bindings.
+    for (const dependencyId of outdated) {
+      try {
+        getOrInstantiateModuleFromParent(dependencyId, module);
+      } catch (err) {
+        const { errorHandler } = hotState.acceptedDependencies[dependencyId];
+        if (typeof errorHandler === "function") {
+          try {
+            errorHandler(err, { moduleId, dependencyId });
+          } catch (_) {
+            // Ignore error.
+          }
+        }
+      }
+    }
+
+    const calledCallbacks = new Set();
+    for (const dependencyId of outdated) {
+      const { callback, errorHandler } =
+        hotState.acceptedDependencies[dependencyId];
+      if (calledCallbacks.has(callback)) {
+        continue;
+      }
+      calledCallbacks.add(callback);
+
+      try {
+        callback(outdated);
+      } catch (err) {
+        if (typeof errorHandler === "function") {
+          try {
+            errorHandler(err, { moduleId, dependencyId });
+          } catch (_) {
+            // Ignore error.
+          }
+        }
+      }
+    }
+  }
 
   // Re-instantiate all outdated self-accepted modules.
   for (const { moduleId, errorHandler } of outdatedSelfAcceptedModules) {
@@ -736,7 +854,7 @@ function applyPhase(
  * @param {EcmascriptChunkUpdate} update
  */
 function applyUpdate(chunkPath, update) {
-  const { outdatedModules, newModuleFactories } =
+  const { outdatedModules, outdatedDependencies, newModuleFactories } =
     computeOutdatedModules(update);
 
   const deletedModules = new Set(update.deleted);
@@ -745,7 +863,12 @@ function applyUpdate(chunkPath, update) {
     computeOutdatedSelfAcceptedModules(outdatedModules);
 
   disposePhase(chunkPath, outdatedModules, deletedModules);
-  applyPhase(chunkPath, outdatedSelfAcceptedModules, newModuleFactories);
+  applyPhase(
+    chunkPath,
+    outdatedSelfAcceptedModules,
+    outdatedDependencies,
+    newModuleFactories
+  );
 }
 
 /**
@@ -755,6 +878,8 @@ function applyUpdate(chunkPath, update) {
  */
 function getAffectedModuleEffects(moduleId) {
   const outdatedModules = new Set();
+  /** @type {Map<ModuleId, Set<ModuleId>>} */
+  const outdatedDependencies = new Map();
 
   /** @typedef {{moduleId?: ModuleId, dependencyChain: ModuleId[]}} QueueItem */
 
@@ -817,8 +942,28 @@ function getAffectedModuleEffects(moduleId) {
         continue;
       }
 
-      // TODO(alexkirsz) Dependencies: check accepted and declined
-      // dependencies here.
+      const parentHotState = moduleHotState.get(parent);
+
+      if (parentHotState.declinedDependencies[moduleId]) {
+        return {
+          type: "declined",
+          dependencyChain: [...dependencyChain, moduleId],
+          moduleId,
+          parentId,
+        };
+      }
+
+      // The parent accepts updates of this module, so the update doesn't
+      // need to bubble further up this path.
+      if (parentHotState.acceptedDependencies[moduleId]) {
+        let dependencyIds = outdatedDependencies.get(parentId);
+        if (!dependencyIds) {
+          dependencyIds = new Set();
+          outdatedDependencies.set(parentId, dependencyIds);
+        }
+        dependencyIds.add(moduleId);
+        continue;
+      }
 
       queue.push({
         moduleId: parentId,
@@ -831,6 +976,7 @@ function getAffectedModuleEffects(moduleId) {
     type: "accepted",
     moduleId,
     outdatedModules,
+    outdatedDependencies,
   };
 }
 
@@ -841,7 +987,14 @@ function getAffectedModuleEffects(moduleId) {
 function handleApply(chunkPath, update) {
   switch (update.type) {
     case "partial":
-      applyUpdate(chunkPath, update.instruction);
+      try {
+        applyUpdate(chunkPath, update.instruction);
+      } catch (err) {
+        // The update can't be applied in place, e.g. because no module
+        // accepts it. Fall back to a full reload.
+        console.warn(`[turbopack] ${err.message} Performing full reload.`);
+        BACKEND.restart();
+      }
       break;
     case "restart":
       BACKEND.restart();
@@ -861,23 +1014,31 @@ function createModuleHot(hotData) {
     selfAccepted: false,
     selfDeclined: false,
     selfInvalidated: false,
+    acceptedDependencies: {},
+    declinedDependencies: {},
     disposeHandlers: [],
   };
 
   /**
-   * TODO(alexkirsz) Support full (dep, callback, errorHandler) form.
+   * Dependencies are referenced by their module ids, the compiler replaces
+   * the requests passed to `accept` with the ids.
    *
-   * @param {string | string[] | AcceptErrorHandler} [dep]
-   * @param {AcceptCallback} [_callback]
-   * @param {AcceptErrorHandler} [_errorHandler]
+   * @param {ModuleId | ModuleId[] | AcceptErrorHandler} [dep]
+   * @param {AcceptCallback} [callback]
+   * @param {AcceptErrorHandler} [errorHandler]
    */
-  function accept(dep, _callback, _errorHandler) {
+  function accept(dep, callback, errorHandler) {
     if (dep === undefined) {
       hotState.selfAccepted = true;
     } else if (typeof dep === "function") {
       hotState.selfAccepted = dep;
     } else {
-      throw new Error("unsupported `accept` signature");
+      for (const dependencyId of Array.isArray(dep) ? dep : [dep]) {
+        hotState.acceptedDependencies[dependencyId] = {
+          callback: callback ?? (() => {}),
+          errorHandler,
+        };
+      }
     }
   }
 
@@ -896,7 +1057,9 @@ function createModuleHot(hotData) {
       if (dep === undefined) {
         hotState.selfDeclined = true;
       } else {
-        throw new Error("unsupported `decline` signature");
+        for (const dependencyId of Array.isArray(dep) ? dep : [dep]) {
+          hotState.declinedDependencies[dependencyId] = true;
+        }
       }
     },
 
@@ -985,15 +1148,42 @@ function removeModuleFromChunk(moduleId, chunkPath) {
 }
 
 /**
- * Instantiates a runtime module.
+ * Resolves once the async runtime modules which were instantiated so far are
+ * evaluated.
+ *
+ * @type {Promise<void> | undefined}
  */
+let runtimeModulesEvaluation;
+
 /**
+ * Instantiates a runtime module. Runtime modules are evaluated in order, so
+ * when an async runtime module is still being evaluated, the instantiation is
+ * deferred until it's done.
  *
  * @param {ModuleId} moduleId
- * @returns {Module}
  */
 function instantiateRuntimeModule(moduleId) {
-  return instantiateModule(moduleId, SourceType.Runtime);
+  const evaluation = runtimeModulesEvaluation
+    ? runtimeModulesEvaluation.then(() => evaluateRuntimeModule(moduleId))
+    : evaluateRuntimeModule(moduleId);
+  if (!evaluation) {
+    return;
+  }
+
+  runtimeModulesEvaluation = evaluation;
+  evaluation.then(() => {
+    if (runtimeModulesEvaluation === evaluation) {
+      runtimeModulesEvaluation = undefined;
+    }
+  });
+}
+
+/**
+ * @param {ModuleId} moduleId
+ * @returns {Promise<void> | undefined}
+ */
+function evaluateRuntimeModule(moduleId) {
+  return instantiateModule(moduleId, SourceType.Runtime).async;
 }
 
 /**
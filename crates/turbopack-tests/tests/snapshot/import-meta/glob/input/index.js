@@ -0,0 +1,14 @@
+const lazy = import.meta.glob("./pages/**/*.js");
+const eager = import.meta.glob("./pages/*.js", { eager: true });
+const titles = import.meta.glob(["./pages/*.js", "./pages/blog/*.js"], {
+  eager: true,
+  import: "title",
+});
+// `?` and `[...]` patterns aren't supported
+const unsupported = import.meta.glob("./pages/?.js");
+
+console.log(lazy, eager, titles, unsupported);
+
+// Modules matched by eager globs are awaited like imported modules when they
+// are async, as `./pages/data.js` is
+export { eager, titles };
@@ -0,0 +1,2 @@
+export const title = "Post";
+export default function Post() {}
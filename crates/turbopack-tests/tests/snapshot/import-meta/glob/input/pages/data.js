@@ -0,0 +1,5 @@
+// An async module, which eager globs need to wait for
+const data = await Promise.resolve({ items: [] });
+
+export const title = "Data";
+export default data;
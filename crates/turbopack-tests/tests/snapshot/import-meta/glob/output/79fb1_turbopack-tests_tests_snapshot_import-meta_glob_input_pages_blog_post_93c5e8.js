@@ -0,0 +1,10 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/79fb1_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_blog_post_93c5e8.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/import-meta/glob/input/pages/blog/post.js (ecmascript, manifest chunk)": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__([
+  "output/crates_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_blog_post.js"
+]);
+
+})()),
+}]);
\ No newline at end of file
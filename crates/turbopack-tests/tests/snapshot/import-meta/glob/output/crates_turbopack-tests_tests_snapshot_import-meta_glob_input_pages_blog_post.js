@@ -0,0 +1,16 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/crates_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_blog_post.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/import-meta/glob/input/pages/blog/post.js (ecmascript)": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "default": ()=>Post,
+    "title": ()=>title
+});
+const title = "Post";
+function Post() {}
+
+})()),
+}]);
+
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_blog_post.js.map
\ No newline at end of file
@@ -0,0 +1,21 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/crates_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_data.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/import-meta/glob/input/pages/data.js (ecmascript)": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname, a: __turbopack_async_module__, w: __turbopack_async_dependencies__ }) => (() => {
+
+__turbopack_async_module__(async () => {
+__turbopack_esm__({
+    "default": ()=>__TURBOPACK__default__export__,
+    "title": ()=>title
+});
+const data = await Promise.resolve({
+    items: []
+});
+const title = "Data";
+const __TURBOPACK__default__export__ = data;
+
+});
+})()),
+}]);
+
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_import-meta_glob_input_pages_data.js.map
\ No newline at end of file
@@ -0,0 +1,9 @@
+const worker = new Worker(new URL("./worker.js", import.meta.url), {
+  type: "module",
+});
+worker.postMessage("ping");
+worker.addEventListener("message", (event) => console.log(event.data));
+
+// Not a JavaScript module, so only the URL is rewritten
+const text = new Worker(new URL("./worker.txt", import.meta.url));
+console.log(text);
@@ -0,0 +1,5 @@
+import { reply } from "./reply.js";
+
+self.addEventListener("message", (event) => {
+  self.postMessage(reply(event.data));
+});
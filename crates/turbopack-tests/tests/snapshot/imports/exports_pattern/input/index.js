@@ -0,0 +1,7 @@
+function load(locale) {
+  // Resolves to all subpaths of "./*" except the ones excluded by
+  // "./internal/*"
+  return import(`pkg/${locale}`);
+}
+
+load(navigator.language).then(console.log);
@@ -0,0 +1,14 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/334ba_pkg_src_en.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/imports/exports_pattern/input/node_modules/pkg/src/en.js (ecmascript)": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "default": ()=>__TURBOPACK__default__export__
+});
+const __TURBOPACK__default__export__ = "en";
+
+})()),
+}]);
+
+
+//# sourceMappingURL=334ba_pkg_src_en.js.map
\ No newline at end of file
@@ -0,0 +1,3 @@
+import { Button, Label, icons } from "components";
+
+console.log(Button(), Label(), icons.check);
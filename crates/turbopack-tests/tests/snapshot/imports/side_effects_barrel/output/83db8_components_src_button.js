@@ -0,0 +1,16 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/83db8_components_src_button.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/imports/side_effects_barrel/input/node_modules/components/src/button.js (ecmascript)": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "Button": ()=>Button
+});
+function Button() {
+    return "button";
+}
+
+})()),
+}]);
+
+
+//# sourceMappingURL=83db8_components_src_button.js.map
\ No newline at end of file
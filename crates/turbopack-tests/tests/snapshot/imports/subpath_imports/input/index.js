@@ -0,0 +1,6 @@
+import dep from "#dep";
+import { format } from "#utils/format";
+import React from "#react";
+import missing from "#missing";
+
+console.log(dep, format, React, missing);
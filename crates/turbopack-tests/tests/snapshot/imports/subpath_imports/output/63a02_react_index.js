@@ -0,0 +1,12 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/63a02_react_index.js", {
+
+"[project]/crates/turbopack-tests/tests/node_modules/react/index.js (ecmascript)": (function({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname, m: module, e: exports }) { !function() {
+
+"purposefully empty stub";
+"react/index.js";
+
+}.call(this) }),
+}]);
+
+
+//# sourceMappingURL=63a02_react_index.js.map
\ No newline at end of file
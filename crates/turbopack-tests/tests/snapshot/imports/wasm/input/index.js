@@ -0,0 +1,5 @@
+import { add } from "./add.wasm";
+// Malformed modules are reported and have no exports
+import * as broken from "./broken.wasm";
+
+console.log(add(1, 2), broken);
@@ -0,0 +1,7 @@
+import "./style.css";
+import { message } from "./message";
+
+console.log(message);
+import("./lazy").then(({ lazy }) => {
+  console.log(lazy);
+});
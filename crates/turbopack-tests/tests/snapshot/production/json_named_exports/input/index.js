@@ -0,0 +1,6 @@
+import { name, version } from "./package.json";
+import config from "./config.json";
+
+// Only `name` and `version` are kept in `package.json`, while `config.json`
+// is kept as a whole because its default export is used.
+console.log(name, version, config.debug);
@@ -0,0 +1,11 @@
+import "./style.css";
+import { add } from "./math";
+
+function describe(first, second) {
+  if (process.env.NODE_ENV !== "development") {
+    console.log("not in development");
+  }
+  return `${first} + ${second} = ${add(first, second)}`;
+}
+
+console.log(describe(1, 2));
@@ -0,0 +1,3 @@
+export function add(first, second) {
+  return first + second;
+}
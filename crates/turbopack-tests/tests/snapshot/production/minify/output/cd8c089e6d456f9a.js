@@ -0,0 +1,6 @@
+(self.TURBOPACK=self.TURBOPACK||[]).push(["output/cd8c089e6d456f9a.js",{"3d57":({r:e,x:t,i:n,s:r,v:l,c:o,l:i,j:d,p:a,g:s,__dirname:c})=>(()=>{r({add:()=>e});function e(e,t){return e+t;}})(),b5a7:({r:e,x:t,i:n,s:r,v:l,c:o,l:i,j:d,p:a,g:s,__dirname:c})=>(()=>{var e=n("3d57");console.log(function(t,n){return`${t} + ${n} = ${e.add(t,n)}`;}(1,2));})()},({loadedChunks:e,instantiateRuntimeModule:t})=>{if(!e.has("output/b7611b4e9656ef43.js"))return!0;t("b5a7");}]),(()=>{let runtimeModulesEvaluation;if(!Array.isArray(globalThis.TURBOPACK))return;let BACKEND={loadChunk:(e,t)=>new Promise((t,n)=>{if(e.endsWith(".css")){let r=document.createElement("link");r.rel="stylesheet",r.href=`/${e}`,r.onerror=()=>{n();},r.onload=()=>{t();},document.body.appendChild(r);}else if(e.endsWith(".js")){let t=document.createElement("script");t.src=`/${e}`,t.onerror=()=>{n();},document.body.appendChild(t);}else throw Error(`can't infer type of chunk from path ${e}`);}),loadWebAssembly:(e,t,n)=>WebAssembly.instantiateStreaming(fetch(`/${e}`),t).then(({instance:e})=>e.exports),restart:()=>self.location.reload()},runnable=[],moduleFactories={__proto__:null},moduleCache={__proto__:null},loadedChunks=new Set,chunkLoaders=new Map,moduleHotData=new Map,moduleHotState=new Map,runtimeModules=new Set,moduleChunksMap=new Map,hOP=Object.prototype.hasOwnProperty,_process="undefined"!=typeof process?process:{env:{},browser:!0},toStringTag="undefined"!=typeof Symbol&&Symbol.toStringTag;function defineProp(e,t,n){hOP.call(e,t)||Object.defineProperty(e,t,n);}function esm(e,t){for(let n in defineProp(e,"__esModule",{value:!0}),toStringTag&&defineProp(e,toStringTag,{value:"Module"}),t)defineProp(e,n,{get:t[n],enumerable:!0});}function cjs(e,t){for(let n in t)defineProp(e,n,{get:()=>t[n],enumerable:!0});}function exportValue(e,t){e.exports=t;}function asyncModule(e,t){e.async=t();}function asyncDependencies(e){return Promise.all(e.map(e=>moduleCache[e]?.async));}function createGetter(e,t){return()=>e[t];}function interopEsm(e,t,n){let r={__proto__:null};for(let t in e)r[t]=createGetter(e,t);n&&"default"in r||(r.default=()=>e),esm(t,r);}function esmImport(e,t,n){let r=getOrInstantiateModuleFromParent(t,e),l=r.exports;if(l.__esModule)return l;if(r.interopNamespace)return r.interopNamespace;let o=r.interopNamespace={};return interopEsm(l,o,n),o;}function commonJsRequire(e,t){return getOrInstantiateModuleFromParent(t,e).exports;}function externalRequire(e,t){let n;try{n=require(e);}catch(t){throw Error(`Failed to load external module ${e}: ${t}`);}if(!t||n.__esModule)return n;let r={};return interopEsm(n,r,!0),r;}function loadChunk(e,t){if(loadedChunks.has(t))return Promise.resolve();let n=getOrCreateChunkLoader(t,e);return n.promise;}function loadWebAssembly(e,t,n){return BACKEND.loadWebAssembly(t,n,e);}function getOrCreateChunkLoader(e,t){let n,r,l=chunkLoaders.get(e);if(l)return l;let o=new Promise((e,t)=>{n=e,r=t;}),i=t=>{chunkLoaders.delete(e),r(Error(`Failed to load chunk from ${e}${t?`: ${t}`:""}`));},d=()=>{loadedChunks.add(e),chunkLoaders.delete(e),n();};return l={promise:o,onLoad:d},chunkLoaders.set(e,l),BACKEND.loadChunk(e,t).then(d,i),l;}externalRequire.resolve=(e,t)=>require.resolve(e,t);let SourceType={Runtime:0,Parent:1,Update:2};function instantiateModule(e,t,n){let r=moduleFactories[e];if("function"!=typeof r){let r;switch(t){case SourceType.Runtime:r="as a runtime entry";break;case SourceType.Parent:r=`because it was required from module ${n}`;break;case SourceType.Update:r="because of an HMR update";}throw Error(`Module ${e} was instantiated ${r}, but the module factory is not available. It might have been deleted in an HMR update.`);}let l=moduleHotData.get(e),{hot:o,hotState:i}=createModuleHot(l),d={exports:{},loaded:!1,id:e,parents:[],children:[],interopNamespace:void 0,async:void 0,hot:o};return moduleCache[e]=d,moduleHotState.set(d,i),t===SourceType.Runtime?runtimeModules.add(e):t===SourceType.Parent&&d.parents.push(n),runModuleExecutionHooks(d,()=>{r.call(d.exports,{e:d.exports,r:commonJsRequire.bind(null,d),x:externalRequire,i:esmImport.bind(null,d),s:esm.bind(null,d.exports),j:cjs.bind(null,d.exports),v:exportValue.bind(null,d),a:asyncModule.bind(null,d),w:asyncDependencies,m:d,c:moduleCache,l:loadChunk.bind(null,e),u:loadWebAssembly.bind(null,e),p:_process,g:globalThis,__dirname:d.id.replace(/(^|\/)[\/]+$/,"")});}),d.loaded=!0,d.interopNamespace&&interopEsm(d.exports,d.interopNamespace),d;}function runModuleExecutionHooks(e,t){let n="function"==typeof globalThis.$RefreshInterceptModuleExecution$?globalThis.$RefreshInterceptModuleExecution$(e.id):()=>{};t(),"$RefreshHelpers$"in globalThis&&registerExportsAndSetupBoundaryForReactRefresh(e,globalThis.$RefreshHelpers$),n();}function getOrInstantiateModuleFromParent(e,t){t.hot.active||console.warn(`Unexpected import of module ${e} from module ${t.id}, which was deleted by an HMR update`);let n=moduleCache[e];return(-1===t.children.indexOf(e)&&t.children.push(e),n)?(-1===n.parents.indexOf(t.id)&&n.parents.push(t.id),n):instantiateModule(e,SourceType.Parent,t.id);}function registerExportsAndSetupBoundaryForReactRefresh(e,t){let n=e.exports,r=e.hot.data.prevExports??null;if(t.registerExportsForReactRefresh(n,e.id),t.isReactRefreshBoundary(n))e.hot.dispose(e=>{e.prevExports=n;}),e.hot.accept(),null!==r&&(t.shouldInvalidateReactRefreshBoundary(r,n)?e.hot.invalidate():t.scheduleUpdate());else{let t=null!==r;t&&e.hot.invalidate();}}function formatDependencyChain(e){return`Dependency chain: ${e.join(" -> ")}`;}function _eval({code,url,map}){return code+=`
+
+//# sourceURL=${location.origin}${url}`,map&&(code+=`
+//# sourceMappingURL=${map}`),eval(code);}function computeOutdatedModules(e){let t=new Set,n=new Map,r=new Map;for(let[t,n]of Object.entries(e.added))r.set(t,_eval(n));for(let[l,o]of Object.entries(e.modified)){let e=getAffectedModuleEffects(l);switch(e.type){case"unaccepted":throw Error(`cannot apply update: unaccepted module. ${formatDependencyChain(e.dependencyChain)}.`);case"self-declined":throw Error(`cannot apply update: self-declined module. ${formatDependencyChain(e.dependencyChain)}.`);case"declined":throw Error(`cannot apply update: declined dependency of module ${e.parentId}. ${formatDependencyChain(e.dependencyChain)}.`);case"accepted":for(let n of(r.set(l,_eval(o)),e.outdatedModules))t.add(n);for(let[t,r]of e.outdatedDependencies){let e=n.get(t);for(let l of(e||(e=new Set,n.set(t,e)),r))e.add(l);}}}return{outdatedModules:t,outdatedDependencies:n,newModuleFactories:r};}function computeOutdatedSelfAcceptedModules(e){let t=[];for(let n of e){let e=moduleCache[n],r=moduleHotState.get(e);e&&r.selfAccepted&&!r.selfInvalidated&&t.push({moduleId:n,errorHandler:r.selfAccepted});}return t;}function disposePhase(e,t,n){for(let e of t){let t=moduleCache[e];if(!t)continue;let n=disposeModule(t);moduleHotData.set(e,n);}for(let t of n){let n=moduleCache[t];if(!n)continue;let r=removeModuleFromChunk(t,e);r&&(disposeModule(n),moduleHotData.delete(t));}}function disposeModule(e){let t=moduleHotState.get(e),n={};for(let e of t.disposeHandlers)e(n);for(let t of(e.hot.active=!1,delete moduleCache[e.id],moduleHotState.delete(e),e.children)){let n=moduleCache[t];if(!n)continue;let r=n.parents.indexOf(e.id);r>=0&&n.parents.splice(r,1);}return n;}function applyPhase(e,t,n,r){for(let[t,n]of r.entries())moduleFactories[t]=n,addModuleToChunk(t,e);for(let[e,t]of n){let n=moduleCache[e];if(!n)continue;let r=moduleHotState.get(n),l=[...t];for(let t of l)try{getOrInstantiateModuleFromParent(t,n);}catch(l){let{errorHandler:n}=r.acceptedDependencies[t];if("function"==typeof n)try{n(l,{moduleId:e,dependencyId:t});}catch(e){}}let o=new Set;for(let t of l){let{callback:n,errorHandler:i}=r.acceptedDependencies[t];if(!o.has(n)){o.add(n);try{n(l);}catch(n){if("function"==typeof i)try{i(n,{moduleId:e,dependencyId:t});}catch(e){}}}}}for(let{moduleId:e,errorHandler:n}of t)try{instantiateModule(e,SourceType.Update);}catch(t){if("function"==typeof n)try{n(t,{moduleId:e,module:moduleCache[e]});}catch(e){}}}function applyUpdate(e,t){let{outdatedModules:n,outdatedDependencies:r,newModuleFactories:l}=computeOutdatedModules(t),o=new Set(t.deleted),i=computeOutdatedSelfAcceptedModules(n);disposePhase(e,n,o),applyPhase(e,i,r,l);}function getAffectedModuleEffects(e){let t=new Set,n=new Map,r=[{moduleId:e,dependencyChain:[]}];for(;r.length>0;){let{moduleId:e,dependencyChain:l}=r.shift();if(t.add(e),void 0===e)return{type:"unaccepted",dependencyChain:l};let o=moduleCache[e],i=moduleHotState.get(o);if(o&&(!i.selfAccepted||i.selfInvalidated)){if(i.selfDeclined)return{type:"self-declined",dependencyChain:l,moduleId:e};if(runtimeModules.has(e)){r.push({moduleId:void 0,dependencyChain:[...l,e]});continue;}for(let t of o.parents){let o=moduleCache[t];if(!o)continue;let i=moduleHotState.get(o);if(i.declinedDependencies[e])return{type:"declined",dependencyChain:[...l,e],moduleId:e,parentId:t};if(i.acceptedDependencies[e]){let r=n.get(t);r||(r=new Set,n.set(t,r)),r.add(e);continue;}r.push({moduleId:t,dependencyChain:[...l,e]});}}}return{type:"accepted",moduleId:e,outdatedModules:t,outdatedDependencies:n};}function handleApply(e,t){switch(t.type){case"partial":try{applyUpdate(e,t.instruction);}catch(e){console.warn(`[turbopack] ${e.message} Performing full reload.`),BACKEND.restart();}break;case"restart":BACKEND.restart();break;default:throw Error(`Unknown update type: ${t.type}`);}}function createModuleHot(e){let n={selfAccepted:!1,selfDeclined:!1,selfInvalidated:!1,acceptedDependencies:{},declinedDependencies:{},disposeHandlers:[]};function t(e,t,r){if(void 0===e)n.selfAccepted=!0;else if("function"==typeof e)n.selfAccepted=e;else for(let l of Array.isArray(e)?e:[e])n.acceptedDependencies[l]={callback:t??(()=>{}),errorHandler:r};}let r={active:!0,data:e??{},accept:t,decline:e=>{if(void 0===e)n.selfDeclined=!0;else for(let t of Array.isArray(e)?e:[e])n.declinedDependencies[t]=!0;},dispose:e=>{n.disposeHandlers.push(e);},addDisposeHandler:e=>{n.disposeHandlers.push(e);},removeDisposeHandler:e=>{let t=n.disposeHandlers.indexOf(e);t>=0&&n.disposeHandlers.splice(t,1);},invalidate:()=>{n.selfInvalidated=!0;},status:()=>"idle",addStatusHandler:e=>{},removeStatusHandler:e=>{}};return{hot:r,hotState:n};}function addModuleToChunk(e,t){let n=moduleChunksMap.get(e);n?n.add(t):(n=new Set([t]),moduleChunksMap.set(e,n));}function getFirstModuleChunk(e){let t=moduleChunksMap.get(e);return null==t?null:t.values().next().value;}function removeModuleFromChunk(e,t){let n=moduleChunksMap.get(e);return n.delete(t),!(n.size>0)&&(moduleChunksMap.delete(e),!0);}function instantiateRuntimeModule(e){let t=runtimeModulesEvaluation?runtimeModulesEvaluation.then(()=>evaluateRuntimeModule(e)):evaluateRuntimeModule(e);t&&(runtimeModulesEvaluation=t,t.then(()=>{runtimeModulesEvaluation===t&&(runtimeModulesEvaluation=void 0);}));}function evaluateRuntimeModule(e){return instantiateModule(e,SourceType.Runtime).async;}function subscribeToChunkUpdates(e){globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS.push([e,handleApply.bind(null,e)]);}function markChunkAsLoaded(e){let t=chunkLoaders.get(e);if(!t){loadedChunks.add(e);return;}t.onLoad();}let runtime={loadedChunks,modules:moduleFactories,cache:moduleCache,instantiateRuntimeModule};function registerChunk([e,t,...n]){for(let[n,r]of(markChunkAsLoaded(e),subscribeToChunkUpdates(e),Object.entries(t)))moduleFactories[n]||(moduleFactories[n]=r),addModuleToChunk(n,e);runnable.push(...n),runnable=runnable.filter(e=>e(runtime));}globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS=globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS||[],globalThis.TURBOPACK.forEach(registerChunk),globalThis.TURBOPACK={push:registerChunk};})();
+
+//# sourceMappingURL=cd8c089e6d456f9a.js.map
\ No newline at end of file
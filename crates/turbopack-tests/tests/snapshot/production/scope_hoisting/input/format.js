@@ -0,0 +1,5 @@
+const prefix = ">";
+
+export function format(value) {
+  return `${prefix} ${value}`;
+}
@@ -0,0 +1,7 @@
+import { format } from "./format.js";
+
+console.log("greet.js evaluated");
+
+export function greet(name) {
+  return format(`Hello ${name}`);
+}
@@ -0,0 +1,5 @@
+import { greet } from "./greet.js";
+import "./setup.js";
+import { name } from "./name.js";
+
+console.log(greet(name));
@@ -0,0 +1,5 @@
+const prefix = "name:";
+
+console.log(prefix, "name.js evaluated after setup.js");
+
+export const name = "world";
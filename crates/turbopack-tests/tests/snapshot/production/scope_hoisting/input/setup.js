@@ -0,0 +1,3 @@
+// CommonJS modules keep their own module factory
+globalThis.setupDone = true;
+module.exports = {};
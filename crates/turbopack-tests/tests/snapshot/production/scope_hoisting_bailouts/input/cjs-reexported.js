@@ -0,0 +1 @@
+module.exports = { reexported: "cjs-reexported" };
@@ -0,0 +1,2 @@
+// CommonJS modules keep their own module factory
+exports.fromCjs = "cjs";
@@ -0,0 +1,2 @@
+// Imported dynamically, so it's loaded separately
+export const dynamic = "dynamic";
@@ -0,0 +1,6 @@
+// Uses its own module object
+if (import.meta.hot) {
+  import.meta.hot.accept();
+}
+
+export const hot = "hot";
@@ -0,0 +1,11 @@
+import { fromCjs } from "./cjs.js";
+import { reexported } from "./reexports-cjs.js";
+import * as namespace from "./namespace.js";
+import { hot } from "./hot.js";
+import { inlined } from "./inlined.js";
+import "./side-effect.js";
+import "./side-effect-esm.js";
+
+import("./dynamic.js").then(console.log);
+
+console.log(fromCjs, reexported, namespace, hot, inlined);
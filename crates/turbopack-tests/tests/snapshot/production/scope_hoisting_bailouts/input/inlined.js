@@ -0,0 +1,2 @@
+// Only imported by named imports, so it's concatenated
+export const inlined = "inlined";
@@ -0,0 +1,2 @@
+// Used as namespace object, which needs a real module
+export const name = "namespace";
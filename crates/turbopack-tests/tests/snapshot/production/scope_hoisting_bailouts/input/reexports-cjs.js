@@ -0,0 +1,2 @@
+// The exports of CommonJS modules are only known at runtime
+export * from "./cjs-reexported.js";
@@ -0,0 +1 @@
+export const dependency = "dependency";
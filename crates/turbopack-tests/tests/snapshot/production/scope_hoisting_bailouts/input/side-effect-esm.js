@@ -0,0 +1,5 @@
+// Imported for its side effects only, which doesn't prevent the
+// concatenation of ESM modules, even without exports
+import "./side-effect-dependency.js";
+
+globalThis.sideEffectEsm = true;
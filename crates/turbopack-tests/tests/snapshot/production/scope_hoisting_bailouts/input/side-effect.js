@@ -0,0 +1,3 @@
+// Imported for its side effects only, but it's a script without imports or
+// exports, so it keeps its own module factory
+globalThis.sideEffect = true;
@@ -0,0 +1,14 @@
+(self.TURBOPACK = self.TURBOPACK || []).push(["output/c1ef69d612407c1d.js", {
+
+"18a3": (({ r: __turbopack_require__, x: __turbopack_external_require__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_cjs__, p: process, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "dynamic": ()=>dynamic
+});
+const dynamic = "dynamic";
+
+})()),
+}]);
+
+
+//# sourceMappingURL=c1ef69d612407c1d.js.map
\ No newline at end of file
@@ -0,0 +1,11 @@
+const value = "a";
+
+function describe() {
+  return `describe ${value}`;
+}
+
+export function helper() {
+  return describe();
+}
+
+export { value };
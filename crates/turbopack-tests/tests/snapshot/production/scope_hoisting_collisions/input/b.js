@@ -0,0 +1,8 @@
+// Shadows the top-level `value` inside of the function only
+function helper(value) {
+  return value.toUpperCase();
+}
+
+const value = helper("b");
+
+export { value };
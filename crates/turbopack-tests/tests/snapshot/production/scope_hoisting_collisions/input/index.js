@@ -0,0 +1,11 @@
+import { value as a, helper } from "./a.js";
+import { value as b } from "./b.js";
+
+// Collides with the top-level declarations of a.js and b.js
+const value = "index";
+
+function describe() {
+  return [value, a, b, helper()].join(", ");
+}
+
+console.log(describe());
@@ -0,0 +1,2 @@
+// Keeps its own module factory, but is still evaluated after second.js
+console.log("cjs");
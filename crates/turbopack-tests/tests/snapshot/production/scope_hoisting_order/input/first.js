@@ -0,0 +1,3 @@
+import "./shared.js";
+
+console.log("first");
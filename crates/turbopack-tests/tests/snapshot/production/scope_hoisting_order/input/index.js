@@ -0,0 +1,6 @@
+import "./first.js";
+import { second } from "./second.js";
+import "./cjs.js";
+import { shared } from "./shared.js";
+
+console.log("index", second, shared);
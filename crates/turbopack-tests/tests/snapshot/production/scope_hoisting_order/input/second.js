@@ -0,0 +1,5 @@
+import { shared } from "./shared.js";
+
+console.log("second");
+
+export const second = `second ${shared}`;
@@ -0,0 +1,4 @@
+// Evaluated once, before first.js which imports it first
+console.log("shared");
+
+export const shared = "shared";
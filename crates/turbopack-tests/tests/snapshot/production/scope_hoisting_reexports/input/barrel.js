@@ -0,0 +1,4 @@
+export * from "./numbers.js";
+export { four as renamed } from "./more.js";
+export { default as Default } from "./default.js";
+export * from "./nested.js";
@@ -0,0 +1,3 @@
+import { one, two, renamed, three, Default } from "./barrel.js";
+
+console.log(one, two, renamed, three, Default);
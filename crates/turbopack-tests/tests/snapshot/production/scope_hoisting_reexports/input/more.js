@@ -0,0 +1 @@
+export const four = 4;
@@ -0,0 +1 @@
+export { three } from "./three.js";
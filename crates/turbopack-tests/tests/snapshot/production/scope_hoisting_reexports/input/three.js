@@ -0,0 +1 @@
+export const three = 3;
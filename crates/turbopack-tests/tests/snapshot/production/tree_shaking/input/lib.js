@@ -0,0 +1,15 @@
+import { helper } from "helper";
+
+export function used() {
+  return format("used");
+}
+
+export function unused() {
+  return helper();
+}
+
+function format(value) {
+  return `[${value}]`;
+}
+
+export const table = { unused };
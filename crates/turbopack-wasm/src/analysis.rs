@@ -0,0 +1,258 @@
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{FileContent, FileSystemPathVc};
+use turbopack_core::{
+    asset::{Asset, AssetContent, AssetVc},
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+};
+
+const SECTION_IMPORT: u8 = 2;
+const SECTION_EXPORT: u8 = 7;
+
+/// The imports and exports of a WebAssembly module.
+#[turbo_tasks::value]
+#[derive(Default, Debug)]
+pub struct WebAssemblyAnalysis {
+    /// The names imported by the module, grouped by the module they are
+    /// imported from.
+    pub imports: IndexMap<String, Vec<String>>,
+    pub exports: Vec<String>,
+}
+
+/// Analyzes the WebAssembly module. A malformed module is reported as an
+/// issue and treated as a module without imports and exports.
+#[turbo_tasks::function]
+pub async fn analyze(source: AssetVc) -> Result<WebAssemblyAnalysisVc> {
+    let AssetContent::File(file) = &*source.content().await? else {
+        bail!("WebAssembly module must be a file");
+    };
+    let FileContent::Content(file) = &*file.await? else {
+        bail!("WebAssembly module not found");
+    };
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(&mut file.read(), &mut bytes)?;
+    Ok(match parse(&bytes) {
+        Ok(analysis) => analysis.cell(),
+        Err(err) => {
+            WebAssemblyParsingIssue {
+                path: source.ident().path(),
+                message: StringVc::cell(format!("{err:#}")),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            WebAssemblyAnalysis::default().cell()
+        }
+    })
+}
+
+/// Parses the import and export sections of a WebAssembly binary. The other
+/// sections are skipped without validation.
+pub(crate) fn parse(bytes: &[u8]) -> Result<WebAssemblyAnalysis> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.bytes(4)? != b"\0asm" {
+        bail!("not a WebAssembly binary");
+    }
+    let version = reader.bytes(4)?;
+    if version != [1, 0, 0, 0] {
+        bail!("unsupported WebAssembly version {:?}", version);
+    }
+
+    let mut analysis = WebAssemblyAnalysis::default();
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let size = reader.u32()? as usize;
+        let mut section = Reader {
+            bytes: reader.bytes(size)?,
+            pos: 0,
+        };
+        match id {
+            SECTION_IMPORT => {
+                for _ in 0..section.u32()? {
+                    let module = section.name()?;
+                    let name = section.name()?;
+                    section.import_desc()?;
+                    analysis.imports.entry(module).or_default().push(name);
+                }
+            }
+            SECTION_EXPORT => {
+                for _ in 0..section.u32()? {
+                    let name = section.name()?;
+                    // The kind and the index of the exported item
+                    section.byte()?;
+                    section.u32()?;
+                    analysis.exports.push(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(analysis)
+}
+
+#[turbo_tasks::value(shared)]
+struct WebAssemblyParsingIssue {
+    path: FileSystemPathVc,
+    message: StringVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for WebAssemblyParsingIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Error.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Unable to parse the WebAssembly module".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("parse".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        self.message
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .context("unexpected end of WebAssembly binary")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .context("unexpected end of WebAssembly binary")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    fn u64(&mut self) -> Result<u64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= 64 {
+                bail!("invalid LEB128 integer in WebAssembly binary");
+            }
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::try_from(self.u64()?)?)
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        Ok(String::from_utf8(self.bytes(len)?.to_vec())?)
+    }
+
+    fn limits(&mut self) -> Result<()> {
+        let flags = self.byte()?;
+        self.u64()?;
+        if flags & 1 != 0 {
+            self.u64()?;
+        }
+        Ok(())
+    }
+
+    /// Skips the description of an imported item.
+    fn import_desc(&mut self) -> Result<()> {
+        match self.byte()? {
+            // function: type index
+            0x00 => {
+                self.u32()?;
+            }
+            // table: reference type and limits
+            0x01 => {
+                self.byte()?;
+                self.limits()?;
+            }
+            // memory: limits
+            0x02 => self.limits()?,
+            // global: value type and mutability
+            0x03 => {
+                self.bytes(2)?;
+            }
+            // tag: attribute and type index
+            0x04 => {
+                self.byte()?;
+                self.u32()?;
+            }
+            kind => bail!("unknown import kind {kind:#x} in WebAssembly binary"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn imports_and_exports() {
+        #[rustfmt::skip]
+        let bytes = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+            // type section: (func (param i32))
+            0x01, 0x05, 0x01, 0x60, 0x01, 0x7f, 0x00,
+            // import section: "./env.js" "log" (func 0), "./env.js" "memory" (memory 1)
+            0x02, 0x23, 0x02,
+            0x08, b'.', b'/', b'e', b'n', b'v', b'.', b'j', b's', 0x03, b'l', b'o', b'g', 0x00, 0x00,
+            0x08, b'.', b'/', b'e', b'n', b'v', b'.', b'j', b's',
+            0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, 0x01,
+            // export section: "log" (func 0)
+            0x07, 0x07, 0x01, 0x03, b'l', b'o', b'g', 0x00, 0x00,
+        ];
+        let analysis = parse(&bytes).unwrap();
+        assert_eq!(
+            analysis.imports.into_iter().collect::<Vec<_>>(),
+            vec![(
+                "./env.js".to_string(),
+                vec!["log".to_string(), "memory".to_string()]
+            )]
+        );
+        assert_eq!(analysis.exports, vec!["log".to_string()]);
+    }
+
+    #[test]
+    fn rejects_malformed_binaries() {
+        assert!(parse(b"not wasm").is_err());
+        // The export section is cut off
+        assert!(
+            parse(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x07, 0x07, 0x01]).is_err()
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! WebAssembly support for turbopack.
+//!
+//! WebAssembly modules are copied to the output folder and integrate with ES
+//! modules: they can be imported like any other module, their exports are
+//! exposed as ESM exports and their imports are resolved as ESM imports.
+//!
+//! Instantiating a WebAssembly module is asynchronous, so WebAssembly modules
+//! are async modules.
+
+#![feature(min_specialization)]
+
+pub mod analysis;
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use turbo_tasks::{
+    primitives::{BoolVc, StringVc},
+    Value, ValueToString, ValueToStringVc,
+};
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContent, AssetContentVc, AssetVc},
+    chunk::{
+        ChunkItem, ChunkItemVc, ChunkVc, ChunkableAsset, ChunkableAssetReference,
+        ChunkableAssetReferenceVc, ChunkableAssetVc, ChunkingContext, ChunkingContextVc,
+    },
+    context::AssetContextVc,
+    ident::AssetIdentVc,
+    issue::{code_gen::CodeGenerationIssue, IssueSeverity},
+    reference::{AssetReference, AssetReferenceVc, AssetReferencesVc, SingleAssetReferenceVc},
+    resolve::{
+        origin::{PlainResolveOriginVc, ResolveOriginVc},
+        parse::RequestVc,
+        pattern::Pattern,
+        ResolveResultVc,
+    },
+};
+use turbopack_ecmascript::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemOptions, EcmascriptChunkItemVc, EcmascriptChunkPlaceable,
+        EcmascriptChunkPlaceableVc, EcmascriptChunkVc, EcmascriptExports, EcmascriptExportsVc,
+    },
+    resolve::esm_resolve,
+    utils::stringify_js,
+    EsmExport, EsmExports,
+};
+
+use self::analysis::{analyze, WebAssemblyAnalysisVc};
+
+#[turbo_tasks::function]
+fn modifier() -> StringVc {
+    StringVc::cell("wasm".to_string())
+}
+
+#[turbo_tasks::value]
+#[derive(Clone)]
+pub struct WebAssemblyModuleAsset {
+    pub source: AssetVc,
+    pub context: AssetContextVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WebAssemblyModuleAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(source: AssetVc, context: AssetContextVc) -> Self {
+        Self::cell(WebAssemblyModuleAsset { source, context })
+    }
+
+    #[turbo_tasks::function]
+    async fn analysis(self) -> Result<WebAssemblyAnalysisVc> {
+        Ok(analyze(self.await?.source))
+    }
+
+    /// The references to the modules the WebAssembly module imports from,
+    /// keyed by the module name in the import section.
+    #[turbo_tasks::function]
+    async fn import_references(self) -> Result<WebAssemblyImportReferencesVc> {
+        let this = self.await?;
+        let origin = PlainResolveOriginVc::new(this.context, this.source.ident().path());
+        Ok(WebAssemblyImportReferencesVc::cell(
+            self.analysis()
+                .await?
+                .imports
+                .keys()
+                .map(|module| {
+                    let request = RequestVc::parse(Value::new(Pattern::Constant(module.clone())));
+                    (
+                        module.clone(),
+                        WebAssemblyImportReferenceVc::new(origin.into(), request),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn wasm_asset(self, context: ChunkingContextVc) -> Result<WebAssemblyAssetVc> {
+        Ok(WebAssemblyAssetVc::cell(WebAssemblyAsset {
+            context,
+            source: self.await?.source,
+        }))
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct WebAssemblyImportReferences(IndexMap<String, WebAssemblyImportReferenceVc>);
+
+#[turbo_tasks::value_impl]
+impl Asset for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.source.ident().with_modifier(modifier())
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        self.source.content()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self_vc: WebAssemblyModuleAssetVc) -> Result<AssetReferencesVc> {
+        Ok(AssetReferencesVc::cell(
+            self_vc
+                .import_references()
+                .await?
+                .values()
+                .map(|reference| reference.as_asset_reference())
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAsset for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk(self_vc: WebAssemblyModuleAssetVc, context: ChunkingContextVc) -> ChunkVc {
+        EcmascriptChunkVc::new(context, self_vc.as_ecmascript_chunk_placeable()).into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: WebAssemblyModuleAssetVc,
+        context: ChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        ModuleChunkItemVc::cell(ModuleChunkItem {
+            module: self_vc,
+            context,
+            wasm_asset: self_vc.wasm_asset(context),
+        })
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    async fn get_exports(self_vc: WebAssemblyModuleAssetVc) -> Result<EcmascriptExportsVc> {
+        let exports = self_vc
+            .analysis()
+            .await?
+            .exports
+            .iter()
+            .map(|name| (name.clone(), EsmExport::LocalBinding(name.clone())))
+            .collect::<BTreeMap<_, _>>();
+        Ok(EcmascriptExports::EsmExports(
+            EsmExports {
+                exports,
+                star_exports: Vec::new(),
+            }
+            .cell(),
+        )
+        .cell())
+    }
+
+    #[turbo_tasks::function]
+    fn is_async(&self) -> BoolVc {
+        BoolVc::cell(true)
+    }
+}
+
+/// A reference to a module a WebAssembly module imports from.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct WebAssemblyImportReference {
+    origin: ResolveOriginVc,
+    request: RequestVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WebAssemblyImportReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(origin: ResolveOriginVc, request: RequestVc) -> Self {
+        Self::cell(WebAssemblyImportReference { origin, request })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for WebAssemblyImportReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(&self) -> ResolveResultVc {
+        esm_resolve(self.origin, self.request)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for WebAssemblyImportReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "wasm import {}",
+            self.request.to_string().await?,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableAssetReference for WebAssemblyImportReference {}
+
+/// The WebAssembly binary in the output folder.
+#[turbo_tasks::value]
+struct WebAssemblyAsset {
+    context: ChunkingContextVc,
+    source: AssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WebAssemblyAsset {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<AssetIdentVc> {
+        let content = self.source.content();
+        let content_hash = if let AssetContent::File(file) = &*content.await? {
+            if let FileContent::Content(file) = &*file.await? {
+                turbo_tasks_hash::hash_xxh3_hash64(file.content())
+            } else {
+                return Err(anyhow!("WebAssemblyAsset::path: not found"));
+            }
+        } else {
+            return Err(anyhow!("WebAssemblyAsset::path: unsupported file content"));
+        };
+        let content_hash_b16 = turbo_tasks_hash::encode_hex(content_hash);
+        Ok(AssetIdentVc::from_path(
+            self.context.asset_path(&content_hash_b16, "wasm"),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        self.source.content()
+    }
+}
+
+#[turbo_tasks::value]
+struct ModuleChunkItem {
+    module: WebAssemblyModuleAssetVc,
+    context: ChunkingContextVc,
+    wasm_asset: WebAssemblyAssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for ModuleChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(&self) -> Result<AssetReferencesVc> {
+        let mut references = self.module.references().await?.clone_value();
+        references.push(
+            SingleAssetReferenceVc::new(
+                self.wasm_asset.into(),
+                StringVc::cell(format!(
+                    "wasm(url) {}",
+                    self.wasm_asset.ident().to_string().await?
+                )),
+            )
+            .into(),
+        );
+        Ok(AssetReferencesVc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for ModuleChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> ChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
+        let mut code = String::new();
+
+        // The exports are only available once the module has been instantiated.
+        writeln!(code, "__turbopack_esm__({{")?;
+        for name in self.module.analysis().await?.exports.iter() {
+            let name = stringify_js(name);
+            writeln!(code, "    {name}: () => wasm[{name}],")?;
+        }
+        writeln!(code, "}});")?;
+
+        let mut ids = Vec::new();
+        writeln!(code, "const imports = {{")?;
+        for (module, reference) in self.module.import_references().await?.iter() {
+            let asset = *reference.resolve_reference().first_asset().await?;
+            let placeable = match asset {
+                Some(asset) => EcmascriptChunkPlaceableVc::resolve_from(asset).await?,
+                None => None,
+            };
+            let Some(placeable) = placeable else {
+                let message = match asset {
+                    Some(_) => format!("The imported module {module} isn't a JavaScript module"),
+                    None => format!("The imported module {module} can't be resolved"),
+                };
+                CodeGenerationIssue {
+                    severity: IssueSeverity::Error.into(),
+                    path: self.module.ident().path(),
+                    title: StringVc::cell("WebAssembly import can't be provided".to_string()),
+                    message: StringVc::cell(message.clone()),
+                }
+                .cell()
+                .as_issue()
+                .emit();
+                // Instantiating the module would fail with a less helpful error
+                writeln!(
+                    code,
+                    "    {}: (() => {{ throw new Error({}); }})(),",
+                    stringify_js(module),
+                    stringify_js(&message)
+                )?;
+                continue;
+            };
+            let id = stringify_js(&*placeable.as_chunk_item(self.context).id().await?);
+            writeln!(
+                code,
+                "    {}: __turbopack_import__({id}),",
+                stringify_js(module)
+            )?;
+            ids.push(id);
+        }
+        writeln!(code, "}};")?;
+        if !ids.is_empty() {
+            writeln!(
+                code,
+                "await __turbopack_async_dependencies__([{}]);",
+                ids.join(", ")
+            )?;
+        }
+
+        let asset_path = &*self.wasm_asset.ident().path().await?;
+        let output_root = self.context.output_root().await?;
+        let Some(wasm_path) = output_root.get_path_to(asset_path) else {
+            bail!("WebAssembly module {} is not in the output root", asset_path.path);
+        };
+        writeln!(
+            code,
+            "const wasm = await __turbopack_wasm__({}, imports);",
+            stringify_js(wasm_path)
+        )?;
+
+        Ok(EcmascriptChunkItemContent {
+            inner_code: code.into(),
+            options: EcmascriptChunkItemOptions {
+                async_module: true,
+                wasm: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    turbopack_ecmascript::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
@@ -60,9 +60,10 @@ pub mod transition;
 
 pub use turbopack_css as css;
 pub use turbopack_ecmascript as ecmascript;
-use turbopack_json::JsonModuleAssetVc;
+use turbopack_json::{DataFormat, DataModuleAssetVc, JsonModuleAssetVc};
 use turbopack_mdx::MdxModuleAssetVc;
 use turbopack_static::StaticModuleAssetVc;
+use turbopack_wasm::WebAssemblyModuleAssetVc;
 
 use self::{
     resolve_options_context::ResolveOptionsContextVc,
@@ -145,6 +146,8 @@ async fn apply_module_type(
         )
         .into(),
         ModuleType::Json => JsonModuleAssetVc::new(source).into(),
+        ModuleType::Yaml => DataModuleAssetVc::new(source, Value::new(DataFormat::Yaml)).into(),
+        ModuleType::Toml => DataModuleAssetVc::new(source, Value::new(DataFormat::Toml)).into(),
         ModuleType::Raw => source,
         ModuleType::Css(transforms) => {
             CssModuleAssetVc::new(source, context.into(), *transforms).into()
@@ -153,6 +156,7 @@ async fn apply_module_type(
             ModuleCssModuleAssetVc::new(source, context.into(), *transforms).into()
         }
         ModuleType::Static => StaticModuleAssetVc::new(source, context.into()).into(),
+        ModuleType::WebAssembly => WebAssemblyModuleAssetVc::new(source, context.into()).into(),
         ModuleType::Mdx(transforms) => {
             MdxModuleAssetVc::new(source, context.into(), *transforms).into()
         }
@@ -589,5 +593,6 @@ pub fn register() {
     turbopack_mdx::register();
     turbopack_json::register();
     turbopack_static::register();
+    turbopack_wasm::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
@@ -6,15 +6,19 @@ use anyhow::{Context, Result};
 pub use module_options_context::*;
 pub use module_rule::*;
 pub use rule_condition::*;
+use turbo_tasks::primitives::JsonValueVc;
 use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::{
     reference_type::{ReferenceType, UrlReferenceSubType},
     resolve::options::{ImportMap, ImportMapVc, ImportMapping, ImportMappingVc},
-    source_transform::SourceTransformsVc,
+    source_transform::{SourceTransformVc, SourceTransformsVc},
 };
 use turbopack_css::{CssInputTransform, CssInputTransformsVc};
 use turbopack_ecmascript::{EcmascriptInputTransform, EcmascriptInputTransformsVc};
-use turbopack_node::transforms::{postcss::PostCssTransformVc, webpack::WebpackLoadersVc};
+use turbopack_node::transforms::{
+    less::LessTransformVc, postcss::PostCssTransformVc, sass::SassTransformVc,
+    webpack::WebpackLoadersVc,
+};
 
 use crate::evaluate_context::node_evaluate_asset_context;
 
@@ -66,6 +70,8 @@ impl ModuleOptionsVc {
             enable_typescript_transform,
             enable_mdx,
             ref enable_postcss_transform,
+            ref enable_sass_transform,
+            ref enable_less_transform,
             ref enable_webpack_loaders,
             preset_env_versions,
             ref custom_ecmascript_app_transforms,
@@ -133,38 +139,56 @@ impl ModuleOptionsVc {
                 .collect(),
         );
 
+        let postcss_transform: Option<SourceTransformVc> =
+            if let Some(options) = enable_postcss_transform {
+                let execution_context = execution_context
+                    .context("execution_context is required for the postcss_transform")?
+                    .join("postcss");
+
+                let import_map = if let Some(postcss_package) = options.postcss_package {
+                    package_import_map_from_import_mapping("postcss", postcss_package)
+                } else {
+                    package_import_map_from_context("postcss", path)
+                };
+                Some(
+                    PostCssTransformVc::new(
+                        node_evaluate_asset_context(
+                            execution_context.project_path(),
+                            Some(import_map),
+                            None,
+                        ),
+                        execution_context,
+                    )
+                    .into(),
+                )
+            } else {
+                None
+            };
+
         let mut rules = vec![
             ModuleRule::new(
                 ModuleRuleCondition::ResourcePathEndsWith(".json".to_string()),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Json)],
             ),
+            ModuleRule::new(
+                ModuleRuleCondition::any(vec![
+                    ModuleRuleCondition::ResourcePathEndsWith(".yaml".to_string()),
+                    ModuleRuleCondition::ResourcePathEndsWith(".yml".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Yaml)],
+            ),
+            ModuleRule::new(
+                ModuleRuleCondition::ResourcePathEndsWith(".toml".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Toml)],
+            ),
             ModuleRule::new(
                 ModuleRuleCondition::ResourcePathEndsWith(".css".to_string()),
                 [
-                    if let Some(options) = enable_postcss_transform {
-                        let execution_context = execution_context
-                            .context("execution_context is required for the postcss_transform")?
-                            .join("postcss");
-
-                        let import_map = if let Some(postcss_package) = options.postcss_package {
-                            package_import_map_from_import_mapping("postcss", postcss_package)
-                        } else {
-                            package_import_map_from_context("postcss", path)
-                        };
-                        Some(ModuleRuleEffect::SourceTransforms(
-                            SourceTransformsVc::cell(vec![PostCssTransformVc::new(
-                                node_evaluate_asset_context(
-                                    execution_context.project_path(),
-                                    Some(import_map),
-                                    None,
-                                ),
-                                execution_context,
-                            )
-                            .into()]),
-                        ))
-                    } else {
-                        None
-                    },
+                    postcss_transform.map(|transform| {
+                        ModuleRuleEffect::SourceTransforms(SourceTransformsVc::cell(vec![
+                            transform,
+                        ]))
+                    }),
                     Some(ModuleRuleEffect::ModuleType(ModuleType::Css(
                         css_transforms,
                     ))),
@@ -232,6 +256,10 @@ impl ModuleOptionsVc {
                 ]),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Static)],
             ),
+            ModuleRule::new(
+                ModuleRuleCondition::ResourcePathEndsWith(".wasm".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::WebAssembly)],
+            ),
             ModuleRule::new(
                 ModuleRuleCondition::ResourcePathHasNoExtension,
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Ecmascript(
@@ -255,6 +283,88 @@ impl ModuleOptionsVc {
             ));
         }
 
+        if let Some(options) = enable_sass_transform {
+            let execution_context = execution_context
+                .context("execution_context is required for the sass_transform")?
+                .join("sass");
+
+            let import_map = if let Some(sass_package) = options.sass_package {
+                package_import_map_from_import_mapping("sass", sass_package)
+            } else {
+                package_import_map_from_context("sass", path)
+            };
+            let sass_transform = SassTransformVc::new(
+                node_evaluate_asset_context(
+                    execution_context.project_path(),
+                    Some(import_map),
+                    None,
+                ),
+                execution_context,
+                options
+                    .sass_options
+                    .unwrap_or_else(|| JsonValueVc::cell(serde_json::json!({}))),
+            );
+            // The compiled CSS is processed by PostCSS like any other stylesheet
+            let transforms = std::iter::once(sass_transform.into())
+                .chain(postcss_transform)
+                .collect();
+            rules.push(ModuleRule::new(
+                ModuleRuleCondition::any(vec![
+                    ModuleRuleCondition::ResourcePathEndsWith(".scss".to_string()),
+                    ModuleRuleCondition::ResourcePathEndsWith(".sass".to_string()),
+                ]),
+                vec![
+                    ModuleRuleEffect::SourceTransforms(SourceTransformsVc::cell(transforms)),
+                    ModuleRuleEffect::ModuleType(ModuleType::Css(css_transforms)),
+                ],
+            ));
+            rules.push(ModuleRule::new(
+                ModuleRuleCondition::any(vec![
+                    ModuleRuleCondition::ResourcePathEndsWith(".module.scss".to_string()),
+                    ModuleRuleCondition::ResourcePathEndsWith(".module.sass".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::CssModule(
+                    css_transforms,
+                ))],
+            ));
+        }
+
+        if let Some(options) = enable_less_transform {
+            let execution_context = execution_context
+                .context("execution_context is required for the less_transform")?
+                .join("less");
+
+            let import_map = if let Some(less_package) = options.less_package {
+                package_import_map_from_import_mapping("less", less_package)
+            } else {
+                package_import_map_from_context("less", path)
+            };
+            let less_transform = LessTransformVc::new(
+                node_evaluate_asset_context(
+                    execution_context.project_path(),
+                    Some(import_map),
+                    None,
+                ),
+                execution_context,
+            );
+            let transforms = std::iter::once(less_transform.into())
+                .chain(postcss_transform)
+                .collect();
+            rules.push(ModuleRule::new(
+                ModuleRuleCondition::ResourcePathEndsWith(".less".to_string()),
+                vec![
+                    ModuleRuleEffect::SourceTransforms(SourceTransformsVc::cell(transforms)),
+                    ModuleRuleEffect::ModuleType(ModuleType::Css(css_transforms)),
+                ],
+            ));
+            rules.push(ModuleRule::new(
+                ModuleRuleCondition::ResourcePathEndsWith(".module.less".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::CssModule(
+                    css_transforms,
+                ))],
+            ));
+        }
+
         if let Some(webpack_loaders_options) = enable_webpack_loaders {
             let execution_context = execution_context
                 .context("execution_context is required for webpack_loaders")?
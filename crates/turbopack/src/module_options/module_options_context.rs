@@ -1,6 +1,6 @@
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use turbo_tasks::trace::TraceRawVcs;
+use turbo_tasks::{primitives::JsonValueVc, trace::TraceRawVcs};
 use turbopack_core::{environment::EnvironmentVc, resolve::options::ImportMappingVc};
 use turbopack_ecmascript::EcmascriptInputTransform;
 use turbopack_node::{
@@ -16,6 +16,20 @@ pub struct PostCssTransformOptions {
     pub placeholder_for_future_extensions: (),
 }
 
+#[derive(Default, Clone, PartialEq, Eq, Debug, TraceRawVcs, Serialize, Deserialize)]
+pub struct LessTransformOptions {
+    pub less_package: Option<ImportMappingVc>,
+    pub placeholder_for_future_extensions: (),
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, TraceRawVcs, Serialize, Deserialize)]
+pub struct SassTransformOptions {
+    pub sass_package: Option<ImportMappingVc>,
+    /// Options passed to the Sass compiler.
+    pub sass_options: Option<JsonValueVc>,
+    pub placeholder_for_future_extensions: (),
+}
+
 #[turbo_tasks::value(shared)]
 #[derive(Default, Clone, Debug)]
 pub struct WebpackLoadersOptions {
@@ -54,6 +68,10 @@ pub struct ModuleOptionsContext {
     #[serde(default)]
     pub enable_postcss_transform: Option<PostCssTransformOptions>,
     #[serde(default)]
+    pub enable_sass_transform: Option<SassTransformOptions>,
+    #[serde(default)]
+    pub enable_less_transform: Option<LessTransformOptions>,
+    #[serde(default)]
     pub enable_webpack_loaders: Option<WebpackLoadersOptions>,
     #[serde(default)]
     pub enable_types: bool,
@@ -52,11 +52,14 @@ pub enum ModuleType {
     TypescriptWithTypes(EcmascriptInputTransformsVc),
     TypescriptDeclaration(EcmascriptInputTransformsVc),
     Json,
+    Yaml,
+    Toml,
     Raw,
     Mdx(EcmascriptInputTransformsVc),
     Css(CssInputTransformsVc),
     CssModule(CssInputTransformsVc),
     Static,
+    WebAssembly,
     // TODO allow custom function when we support function pointers
     Custom(u8),
 }
@@ -109,6 +109,53 @@ async fn base_resolve_options(
     }
     let import_map = import_map.cell();
 
+    let conditions = {
+        let mut conditions: BTreeMap<String, ConditionValue> = [
+            ("import".to_string(), ConditionValue::Unknown),
+            ("require".to_string(), ConditionValue::Unknown),
+        ]
+        .into_iter()
+        .collect();
+        if opt.browser {
+            conditions.insert("browser".to_string(), ConditionValue::Set);
+        }
+        if opt.module {
+            conditions.insert("module".to_string(), ConditionValue::Set);
+        }
+        if let Some(environment) = emulating {
+            for condition in environment.resolve_conditions().await?.iter() {
+                conditions.insert(condition.to_string(), ConditionValue::Set);
+            }
+        }
+        for condition in opt.custom_conditions.iter() {
+            conditions.insert(condition.to_string(), ConditionValue::Set);
+        }
+        // Infer some well-known conditions
+        let dev = conditions.get("development").cloned();
+        let prod = conditions.get("production").cloned();
+        if prod.is_none() {
+            conditions.insert(
+                "production".to_string(),
+                if matches!(dev, Some(ConditionValue::Set)) {
+                    ConditionValue::Unset
+                } else {
+                    ConditionValue::Unknown
+                },
+            );
+        }
+        if dev.is_none() {
+            conditions.insert(
+                "development".to_string(),
+                if matches!(prod, Some(ConditionValue::Set)) {
+                    ConditionValue::Unset
+                } else {
+                    ConditionValue::Unknown
+                },
+            );
+        }
+        conditions
+    };
+
     Ok(ResolveOptions {
         extensions: if let Some(environment) = emulating {
             environment.resolve_extensions().await?.clone_value()
@@ -147,58 +194,16 @@ async fn base_resolve_options(
                     vec!["node_modules".to_string()],
                 ));
             }
+            if let Some((registry, locked_versions)) = opt.enable_registry {
+                mods.push(ResolveModules::Registry(registry, locked_versions));
+            }
             mods
         },
         into_package: {
             let mut resolve_into = Vec::new();
             resolve_into.push(ResolveIntoPackage::ExportsField {
                 field: "exports".to_string(),
-                conditions: {
-                    let mut conditions: BTreeMap<String, ConditionValue> = [
-                        ("import".to_string(), ConditionValue::Unknown),
-                        ("require".to_string(), ConditionValue::Unknown),
-                    ]
-                    .into_iter()
-                    .collect();
-                    if opt.browser {
-                        conditions.insert("browser".to_string(), ConditionValue::Set);
-                    }
-                    if opt.module {
-                        conditions.insert("module".to_string(), ConditionValue::Set);
-                    }
-                    if let Some(environment) = emulating {
-                        for condition in environment.resolve_conditions().await?.iter() {
-                            conditions.insert(condition.to_string(), ConditionValue::Set);
-                        }
-                    }
-                    for condition in opt.custom_conditions.iter() {
-                        conditions.insert(condition.to_string(), ConditionValue::Set);
-                    }
-                    // Infer some well-known conditions
-                    let dev = conditions.get("development").cloned();
-                    let prod = conditions.get("production").cloned();
-                    if prod.is_none() {
-                        conditions.insert(
-                            "production".to_string(),
-                            if matches!(dev, Some(ConditionValue::Set)) {
-                                ConditionValue::Unset
-                            } else {
-                                ConditionValue::Unknown
-                            },
-                        );
-                    }
-                    if dev.is_none() {
-                        conditions.insert(
-                            "development".to_string(),
-                            if matches!(prod, Some(ConditionValue::Set)) {
-                                ConditionValue::Unset
-                            } else {
-                                ConditionValue::Unknown
-                            },
-                        );
-                    }
-                    conditions
-                },
+                conditions: conditions.clone(),
                 unspecified_conditions: ConditionValue::Unset,
             });
             if opt.browser {
@@ -216,6 +221,10 @@ async fn base_resolve_options(
             if opt.browser {
                 resolve_in.push(ResolveInPackage::AliasField("browser".to_string()));
             }
+            resolve_in.push(ResolveInPackage::ImportsField {
+                conditions,
+                unspecified_conditions: ConditionValue::Unset,
+            });
             resolve_in
         },
         import_map: Some(import_map),
@@ -3,7 +3,7 @@ use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::{
     environment::EnvironmentVc,
     resolve::{
-        options::{ImportMapVc, ResolvedMapVc},
+        options::{ImportMapVc, LockedVersionsVc, ResolvedMapVc},
         plugin::ResolvePluginVc,
     },
 };
@@ -28,6 +28,11 @@ pub struct ResolveOptionsContext {
     /// directory
     pub enable_node_modules: Option<FileSystemPathVc>,
     #[serde(default)]
+    /// Enable resolving of packages from a registry directory structured like
+    /// `@scope/module/version/<path-in-package>`, picking versions from the
+    /// provided locked versions instead of a `node_modules` folder.
+    pub enable_registry: Option<(FileSystemPathVc, LockedVersionsVc)>,
+    #[serde(default)]
     /// Mark well-known Node.js modules as external imports and load them using
     /// native `require`. e.g. url, querystring, os
     pub enable_node_externals: bool,